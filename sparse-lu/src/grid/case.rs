@@ -0,0 +1,507 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::{fs, io};
+
+/*
+    Power system case read from the CSV exports under data/
+
+    bus.csv, branch.csv, shunt.csv, load.csv and gen.csv hold PSS/E v33
+    records flattened by parse_psse_raw.ipynb, one record per row, and
+    columns are looked up by header name. Values keep the PSS/E units: R, X
+    and line charging B in per unit on the system base, Vm in per unit, Va
+    and Shift in degrees, shunt G and B in MW / MVAr drawn at 1 pu voltage,
+    loads and generation in MW / MVAr.
+
+    Bus numbers are arbitrary positive integers. Matrices built from a case
+    are indexed by the position of the bus in bus.csv instead, see
+    Case::bus_index. Rows referring to a bus missing from bus.csv are
+    rejected while reading.
+
+    Line shunts GI + jBI and GJ + jBJ, per unit on the system base, are
+    optional columns of branch.csv and become shunts at the two ends, after
+    the rows of shunt.csv, as in RawCase::to_case.
+
+    Transformers sit in their own PSS/E section and are not exported, so a
+    branch has Tap 1 and Shift 0 unless branch.csv has Tap / Shift columns
+    of its own, as MATPOWER style exports do (Tap 0 there means nominal).
+    Without them the network read from the CSVs falls apart into islands,
+    most with no slack bus, and Case::read_dir refuses it; RawCase::to_case
+    (psse.rs) builds a complete case from the RAW file.
+*/
+
+// SBASE is not exported to the CSVs, all cases under data/ use 100 MVA
+pub const DEFAULT_BASE_MVA: f64 = 100.0;
+
+#[derive(Debug)]
+pub enum CaseError {
+    Io(PathBuf, io::Error),
+    // file, column
+    MissingColumn(&'static str, &'static str),
+    // file, line, column, text that failed to parse
+    Parse(&'static str, usize, &'static str, String),
+    // file, line, bus number
+    UnknownBus(&'static str, usize, usize),
+    // line in bus.csv, bus number
+    DuplicateBus(usize, usize),
+    // number of islands, some of them without a slack bus
+    Islanded(usize),
+}
+
+impl fmt::Display for CaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaseError::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+            CaseError::MissingColumn(file, column) => {
+                write!(f, "{}: no column {}", file, column)
+            }
+            CaseError::Parse(file, line, column, text) => {
+                write!(f, "{} line {}: invalid {} {:?}", file, line, column, text)
+            }
+            CaseError::UnknownBus(file, line, bus) => {
+                write!(f, "{} line {}: bus {} is not in bus.csv", file, line, bus)
+            }
+            CaseError::DuplicateBus(line, bus) => {
+                write!(f, "bus.csv line {}: bus {} appears twice", line, bus)
+            }
+            CaseError::Islanded(islands) => write!(
+                f,
+                "network splits into {} islands, not all with a slack bus \
+                 (transformers missing from branch.csv?)",
+                islands
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CaseError {}
+
+// PSS/E bus type codes 1 to 4
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusType {
+    Pq,
+    Pv,
+    Slack,
+    Isolated,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bus {
+    pub number: usize,
+    pub name: String,
+    pub base_kv: f64,
+    pub bus_type: BusType,
+    pub vm: f64,
+    pub va: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Branch {
+    pub from: usize,
+    pub to: usize,
+    pub circuit: String,
+    pub r: f64,
+    pub x: f64,
+    // total line charging, half at each end
+    pub b: f64,
+    // off-nominal ratio and phase shift (degrees) of the from side transformer
+    pub tap: f64,
+    pub shift: f64,
+    pub in_service: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shunt {
+    pub bus: usize,
+    pub g: f64,
+    pub b: f64,
+    pub in_service: bool,
+}
+
+// constant power load
+#[derive(Debug, Clone, PartialEq)]
+pub struct Load {
+    pub bus: usize,
+    pub id: String,
+    pub pd: f64,
+    pub qd: f64,
+    pub in_service: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Generator {
+    pub bus: usize,
+    pub id: String,
+    pub pg: f64,
+    pub qg: f64,
+    pub qmax: f64,
+    pub qmin: f64,
+    pub vset: f64,
+    pub in_service: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Case {
+    pub base_mva: f64,
+    pub buses: Vec<Bus>,
+    pub branches: Vec<Branch>,
+    pub shunts: Vec<Shunt>,
+    pub loads: Vec<Load>,
+    pub generators: Vec<Generator>,
+}
+
+impl Case {
+    /// Bus positions of each island joined by in-service branches, Isolated buses left out.
+    pub fn islands(&self) -> Vec<Vec<usize>> {
+        let n = self.buses.len();
+        let index = self.bus_index();
+        let mut adjacency = vec![vec![]; n];
+        for branch in self.branches.iter().filter(|branch| branch.in_service) {
+            let (i, j) = (index[&branch.from], index[&branch.to]);
+            adjacency[i].push(j);
+            adjacency[j].push(i);
+        }
+
+        let mut seen: Vec<bool> = self
+            .buses
+            .iter()
+            .map(|bus| bus.bus_type == BusType::Isolated)
+            .collect();
+        let mut islands = vec![];
+        for start in 0..n {
+            if seen[start] {
+                continue;
+            }
+            seen[start] = true;
+            let mut island = vec![start];
+            let mut next = 0;
+            while let Some(&k) = island.get(next) {
+                next += 1;
+                for &j in &adjacency[k] {
+                    if !seen[j] {
+                        seen[j] = true;
+                        island.push(j);
+                    }
+                }
+            }
+            islands.push(island);
+        }
+        islands
+    }
+
+    /// Reads bus.csv, branch.csv, shunt.csv, load.csv and gen.csv from a case directory,
+    /// failing with Islanded unless every island has a slack bus.
+    pub fn read_dir(dir: impl AsRef<Path>) -> Result<Case, CaseError> {
+        let read = |file: &str| {
+            let path = dir.as_ref().join(file);
+            fs::read_to_string(&path).map_err(|err| CaseError::Io(path, err))
+        };
+        let case = Case::from_csv(
+            &read("bus.csv")?,
+            &read("branch.csv")?,
+            &read("shunt.csv")?,
+            &read("load.csv")?,
+            &read("gen.csv")?,
+        )?;
+
+        let islands = case.islands();
+        let has_slack = |island: &Vec<usize>| {
+            island
+                .iter()
+                .any(|&k| case.buses[k].bus_type == BusType::Slack)
+        };
+        if !islands.iter().all(has_slack) {
+            return Err(CaseError::Islanded(islands.len()));
+        }
+        Ok(case)
+    }
+
+    /// Parses the contents of bus.csv, branch.csv, shunt.csv, load.csv and gen.csv,
+    /// without checking that the network is connected.
+    pub fn from_csv(
+        bus: &str,
+        branch: &str,
+        shunt: &str,
+        load: &str,
+        generator: &str,
+    ) -> Result<Case, CaseError> {
+        let buses = read_buses(&Csv::new("bus.csv", bus))?;
+        let mut index = HashMap::with_capacity(buses.len());
+        for (k, (line, bus)) in buses.iter().enumerate() {
+            if index.insert(bus.number, k).is_some() {
+                return Err(CaseError::DuplicateBus(*line, bus.number));
+            }
+        }
+        let check_bus = |file, line, bus| {
+            if index.contains_key(&bus) {
+                Ok(())
+            } else {
+                Err(CaseError::UnknownBus(file, line, bus))
+            }
+        };
+
+        let branches = read_branches(&Csv::new("branch.csv", branch))?;
+        for (line, branch, _) in &branches {
+            check_bus("branch.csv", *line, branch.from)?;
+            check_bus("branch.csv", *line, branch.to)?;
+        }
+        let shunts = read_shunts(&Csv::new("shunt.csv", shunt))?;
+        for (line, shunt) in &shunts {
+            check_bus("shunt.csv", *line, shunt.bus)?;
+        }
+        let line_shunts: Vec<Shunt> = branches
+            .iter()
+            .flat_map(|(_, _, ends)| ends.clone())
+            .filter(|shunt| shunt.g != 0.0 || shunt.b != 0.0)
+            .collect();
+        let loads = read_loads(&Csv::new("load.csv", load))?;
+        for (line, load) in &loads {
+            check_bus("load.csv", *line, load.bus)?;
+        }
+        let generators = read_generators(&Csv::new("gen.csv", generator))?;
+        for (line, generator) in &generators {
+            check_bus("gen.csv", *line, generator.bus)?;
+        }
+
+        Ok(Case {
+            base_mva: DEFAULT_BASE_MVA,
+            buses: buses.into_iter().map(|(_, bus)| bus).collect(),
+            branches: branches.into_iter().map(|(_, branch, _)| branch).collect(),
+            shunts: shunts
+                .into_iter()
+                .map(|(_, shunt)| shunt)
+                .chain(line_shunts)
+                .collect(),
+            loads: loads.into_iter().map(|(_, load)| load).collect(),
+            generators: generators
+                .into_iter()
+                .map(|(_, generator)| generator)
+                .collect(),
+        })
+    }
+
+    /// Bus number -> row / column of the bus in matrices built from the case.
+    pub fn bus_index(&self) -> HashMap<usize, usize> {
+        self.buses
+            .iter()
+            .enumerate()
+            .map(|(k, bus)| (bus.number, k))
+            .collect()
+    }
+}
+
+// one CSV file, split on commas, fields trimmed, blank lines skipped
+struct Csv<'a> {
+    file: &'static str,
+    header: Vec<&'a str>,
+    text: &'a str,
+}
+
+struct Row<'a> {
+    file: &'static str,
+    line: usize,
+    fields: Vec<&'a str>,
+}
+
+impl<'a> Csv<'a> {
+    fn new(file: &'static str, text: &'a str) -> Self {
+        let header = text.lines().next().unwrap_or("");
+        Csv {
+            file,
+            header: header.split(',').map(str::trim).collect(),
+            text,
+        }
+    }
+
+    fn optional_column(&self, name: &'static str) -> Option<(usize, &'static str)> {
+        self.header
+            .iter()
+            .position(|&column| column == name)
+            .map(|k| (k, name))
+    }
+
+    fn column(&self, name: &'static str) -> Result<(usize, &'static str), CaseError> {
+        self.optional_column(name)
+            .ok_or(CaseError::MissingColumn(self.file, name))
+    }
+
+    fn rows(&self) -> impl Iterator<Item = Row<'a>> + '_ {
+        self.text
+            .lines()
+            .enumerate()
+            .skip(1)
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(k, line)| Row {
+                file: self.file,
+                line: k + 1,
+                fields: line.split(',').map(str::trim).collect(),
+            })
+    }
+}
+
+impl Row<'_> {
+    fn text(&self, (k, _): (usize, &'static str)) -> &str {
+        self.fields.get(k).copied().unwrap_or("")
+    }
+
+    fn parse<F: FromStr>(&self, column: (usize, &'static str)) -> Result<F, CaseError> {
+        let text = self.text(column);
+        text.parse()
+            .map_err(|_| CaseError::Parse(self.file, self.line, column.1, text.to_string()))
+    }
+
+    fn in_service(&self, column: (usize, &'static str)) -> Result<bool, CaseError> {
+        Ok(self.parse::<i32>(column)? != 0)
+    }
+}
+
+fn read_buses(csv: &Csv) -> Result<Vec<(usize, Bus)>, CaseError> {
+    let number = csv.column("BusNum")?;
+    let name = csv.column("Name")?;
+    let base_kv = csv.column("BaseKV")?;
+    let bus_type = csv.column("Type")?;
+    let vm = csv.column("Vm")?;
+    let va = csv.column("Va")?;
+
+    csv.rows()
+        .map(|row| {
+            let bus_type = match row.parse::<i32>(bus_type)? {
+                1 => BusType::Pq,
+                2 => BusType::Pv,
+                3 => BusType::Slack,
+                4 => BusType::Isolated,
+                _ => {
+                    let text = row.text(bus_type).to_string();
+                    return Err(CaseError::Parse(row.file, row.line, bus_type.1, text));
+                }
+            };
+            let bus = Bus {
+                number: row.parse(number)?,
+                name: row.text(name).to_string(),
+                base_kv: row.parse(base_kv)?,
+                bus_type,
+                vm: row.parse(vm)?,
+                va: row.parse(va)?,
+            };
+            Ok((row.line, bus))
+        })
+        .collect()
+}
+
+// each branch with its line shunts at the from and to ends, in MW / MVAr
+fn read_branches(csv: &Csv) -> Result<Vec<(usize, Branch, [Shunt; 2])>, CaseError> {
+    let from = csv.column("FromBus")?;
+    let to = csv.column("ToBus")?;
+    let circuit = csv.column("CircuitID")?;
+    let r = csv.column("R")?;
+    let x = csv.column("X")?;
+    let b = csv.column("B")?;
+    let status = csv.column("Status")?;
+    let tap = csv.optional_column("Tap");
+    let shift = csv.optional_column("Shift");
+    let line_shunt = ["GI", "BI", "GJ", "BJ"].map(|name| csv.optional_column(name));
+
+    csv.rows()
+        .map(|row| {
+            let mut end = [0.0; 4];
+            for (value, column) in end.iter_mut().zip(line_shunt) {
+                if let Some(column) = column {
+                    *value = row.parse::<f64>(column)? * DEFAULT_BASE_MVA;
+                }
+            }
+            let tap = match tap {
+                Some(tap) => row.parse::<f64>(tap)?,
+                None => 1.0,
+            };
+            let branch = Branch {
+                from: row.parse(from)?,
+                to: row.parse(to)?,
+                circuit: row.text(circuit).to_string(),
+                r: row.parse(r)?,
+                x: row.parse(x)?,
+                b: row.parse(b)?,
+                tap: if tap == 0.0 { 1.0 } else { tap },
+                shift: shift.map_or(Ok(0.0), |shift| row.parse(shift))?,
+                in_service: row.in_service(status)?,
+            };
+            let ends =
+                [(branch.from, end[0], end[1]), (branch.to, end[2], end[3])].map(|(bus, g, b)| {
+                    Shunt {
+                        bus,
+                        g,
+                        b,
+                        in_service: branch.in_service,
+                    }
+                });
+            Ok((row.line, branch, ends))
+        })
+        .collect()
+}
+
+fn read_shunts(csv: &Csv) -> Result<Vec<(usize, Shunt)>, CaseError> {
+    let bus = csv.column("BusNum")?;
+    let g = csv.column("G")?;
+    let b = csv.column("B")?;
+    let status = csv.column("Status")?;
+
+    csv.rows()
+        .map(|row| {
+            let shunt = Shunt {
+                bus: row.parse(bus)?,
+                g: row.parse(g)?,
+                b: row.parse(b)?,
+                in_service: row.in_service(status)?,
+            };
+            Ok((row.line, shunt))
+        })
+        .collect()
+}
+
+fn read_loads(csv: &Csv) -> Result<Vec<(usize, Load)>, CaseError> {
+    let bus = csv.column("BusNum")?;
+    let id = csv.column("ID")?;
+    let pd = csv.column("Pd")?;
+    let qd = csv.column("Qd")?;
+    let status = csv.column("Status")?;
+
+    csv.rows()
+        .map(|row| {
+            let load = Load {
+                bus: row.parse(bus)?,
+                id: row.text(id).to_string(),
+                pd: row.parse(pd)?,
+                qd: row.parse(qd)?,
+                in_service: row.in_service(status)?,
+            };
+            Ok((row.line, load))
+        })
+        .collect()
+}
+
+fn read_generators(csv: &Csv) -> Result<Vec<(usize, Generator)>, CaseError> {
+    let bus = csv.column("BusNum")?;
+    let id = csv.column("ID")?;
+    let pg = csv.column("Pg")?;
+    let qg = csv.column("Qg")?;
+    let qmax = csv.column("Qmax")?;
+    let qmin = csv.column("Qmin")?;
+    let vset = csv.column("Vset")?;
+    let status = csv.column("Status")?;
+
+    csv.rows()
+        .map(|row| {
+            let generator = Generator {
+                bus: row.parse(bus)?,
+                id: row.text(id).to_string(),
+                pg: row.parse(pg)?,
+                qg: row.parse(qg)?,
+                qmax: row.parse(qmax)?,
+                qmin: row.parse(qmin)?,
+                vset: row.parse(vset)?,
+                in_service: row.in_service(status)?,
+            };
+            Ok((row.line, generator))
+        })
+        .collect()
+}
@@ -0,0 +1,172 @@
+use crate::grid::case::Case;
+use crate::grid::powerflow::{PowerFlowError, PowerFlowOptions, Unknowns, mismatch, voltages};
+use crate::sparse::sparse_csc::SparseCSC;
+use num_complex::Complex;
+
+/*
+    Fast-decoupled power flow
+
+    Newton's Jacobian with the coupling blocks dP/dVm and dQ/dVa dropped and
+    the rest frozen at a flat profile leaves two constant real matrices,
+        B'  dVa = dP / |V|    at PV and PQ buses
+        B'' dVm = dQ / |V|    at PQ buses
+    both -Im Ybus of a simplified network. They are factored once, after
+    which an iteration is two solves: a P half updating the angles, then a
+    Q half updating the magnitudes, each followed by a fresh mismatch.
+
+    B' leaves out shunts, line charging and off-nominal taps and phase
+    shifts; B'' keeps all of them but the phase shifts. The variants differ
+    in which of the two drops the branch resistance:
+        XB  B' from X alone, B'' from R + jX
+        BX  B' from R + jX,  B'' from X alone
+    BX tends to do better on networks with high R / X branches.
+
+    Convergence is checked after every half iteration, so a run can stop
+    after a P half.
+*/
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FastDecoupledMethod {
+    Xb,
+    Bx,
+}
+
+#[derive(Debug, Clone)]
+pub struct FastDecoupledResult {
+    // per bus, in the order of Case::buses, Va in degrees
+    pub vm: Vec<f64>,
+    pub va: Vec<f64>,
+    pub converged: bool,
+    // counting a final lone P half as an iteration
+    pub iterations: usize,
+    // largest |P| and |Q| mismatch before the first and after every half
+    // iteration, P halves at odd positions and Q halves at even ones
+    pub mismatches: Vec<(f64, f64)>,
+}
+
+impl Case {
+    /// B' over PV and PQ buses and B'' over PQ buses, in Unknowns order.
+    pub fn fast_decoupled_matrices(
+        &self,
+        method: FastDecoupledMethod,
+    ) -> Result<(SparseCSC<f64>, SparseCSC<f64>), PowerFlowError> {
+        let unknowns = Unknowns::new(self)?;
+
+        let mut b_prime = self.clone();
+        b_prime.shunts.clear();
+        for branch in &mut b_prime.branches {
+            branch.b = 0.0;
+            branch.tap = 1.0;
+            branch.shift = 0.0;
+            if method == FastDecoupledMethod::Xb {
+                branch.r = 0.0;
+            }
+        }
+        let mut b_double_prime = self.clone();
+        for branch in &mut b_double_prime.branches {
+            branch.shift = 0.0;
+            if method == FastDecoupledMethod::Bx {
+                branch.r = 0.0;
+            }
+        }
+
+        Ok((
+            susceptance(&b_prime.ybus(), &unknowns.angle, unknowns.pvpq.len()),
+            susceptance(
+                &b_double_prime.ybus(),
+                &unknowns.magnitude,
+                unknowns.pq.len(),
+            ),
+        ))
+    }
+
+    pub fn fast_decoupled_power_flow(
+        &self,
+        method: FastDecoupledMethod,
+        options: &PowerFlowOptions,
+    ) -> Result<FastDecoupledResult, PowerFlowError> {
+        let unknowns = Unknowns::new(self)?;
+        let (b_prime, b_double_prime) = self.fast_decoupled_matrices(method)?;
+        let b_prime = b_prime.lu_with_options(&options.lu)?;
+        let b_double_prime = b_double_prime.lu_with_options(&options.lu)?;
+
+        let ybus = self.ybus();
+        let s_spec = self.power_injections();
+        let (mut vm, mut va) = self.initial_voltages(options.flat_start);
+        let npvpq = unknowns.pvpq.len();
+
+        let (mut mismatch, _) = mismatch(&ybus, &voltages(&vm, &va), &s_spec, &unknowns);
+        let mut norms = split_norms(&mismatch, npvpq);
+        let mut mismatches = vec![norms];
+        let converged = |(p, q): (f64, f64)| p <= options.tol && q <= options.tol;
+        let mut iterations = 0;
+
+        while !converged(norms) && iterations < options.max_iter {
+            iterations += 1;
+
+            let dp: Vec<f64> = unknowns
+                .pvpq
+                .iter()
+                .enumerate()
+                .map(|(position, &k)| mismatch[position] / vm[k])
+                .collect();
+            for (position, dva) in b_prime.solve(&dp).into_iter().enumerate() {
+                va[unknowns.pvpq[position]] += dva;
+            }
+            (mismatch, _) = self::mismatch(&ybus, &voltages(&vm, &va), &s_spec, &unknowns);
+            norms = split_norms(&mismatch, npvpq);
+            mismatches.push(norms);
+            if converged(norms) {
+                break;
+            }
+
+            let dq: Vec<f64> = unknowns
+                .pq
+                .iter()
+                .enumerate()
+                .map(|(position, &k)| mismatch[npvpq + position] / vm[k])
+                .collect();
+            for (position, dvm) in b_double_prime.solve(&dq).into_iter().enumerate() {
+                vm[unknowns.pq[position]] += dvm;
+            }
+            (mismatch, _) = self::mismatch(&ybus, &voltages(&vm, &va), &s_spec, &unknowns);
+            norms = split_norms(&mismatch, npvpq);
+            mismatches.push(norms);
+        }
+
+        Ok(FastDecoupledResult {
+            vm,
+            va: va.iter().map(|va| va.to_degrees()).collect(),
+            converged: converged(norms),
+            iterations,
+            mismatches,
+        })
+    }
+}
+
+// -Im Ybus on the buses with a position, renumbered to those positions
+fn susceptance(
+    ybus: &SparseCSC<Complex<f64>>,
+    position: &[Option<usize>],
+    n: usize,
+) -> SparseCSC<f64> {
+    let mut flat_indices = vec![];
+    let mut values = vec![];
+    for col in 0..ybus.ncols {
+        let Some(j) = position[col] else { continue };
+        let (start, end) = ybus.get_column_range(col);
+        for p in start..end {
+            if let Some(i) = position[ybus.rowind[p]] {
+                flat_indices.push(i * n + j);
+                values.push(-ybus.values[p].im);
+            }
+        }
+    }
+    SparseCSC::from_flat_indices(n, n, flat_indices, values)
+}
+
+// largest |P| and largest |Q| of a [dP; dQ] mismatch
+fn split_norms(mismatch: &[f64], npvpq: usize) -> (f64, f64) {
+    let norm = |part: &[f64]| part.iter().fold(0.0, |max: f64, x| max.max(x.abs()));
+    (norm(&mismatch[..npvpq]), norm(&mismatch[npvpq..]))
+}
@@ -0,0 +1,5 @@
+pub mod case;
+pub mod fast_decoupled;
+pub mod powerflow;
+pub mod psse;
+pub mod ybus;
@@ -0,0 +1,300 @@
+use crate::grid::case::{BusType, Case};
+use crate::sparse::lu::{ColumnOrdering, LuError, LuOptions};
+use crate::sparse::sparse_csc::SparseCSC;
+use crate::sparse::sparse_matrix::SparseMatrixTrait;
+use num_complex::Complex;
+use std::collections::HashMap;
+use std::fmt;
+
+/*
+    AC power flow, full Newton in polar coordinates
+
+    Unknowns are the voltage angles of PV and PQ buses and the magnitudes of
+    PQ buses; the slack bus holds both, PV buses hold |V|, and Isolated buses
+    take no part. The equations are the power balances
+        S_spec - V conj(Ybus V) = 0
+    real part at PV and PQ buses, imaginary part at PQ buses, with S_spec
+    the in-service generation minus the in-service load of the bus, per unit.
+
+    Every iteration solves J dx = mismatch with the Jacobian
+        [ dP/dVa  dP/dVm ]
+        [ dQ/dVa  dQ/dVm ]
+    assembled from the complex derivatives
+        dS/dVa = j diag(V) conj(diag(I) - Ybus diag(V))
+        dS/dVm = diag(V) conj(Ybus diag(V / |V|)) + conj(diag(I)) diag(V / |V|)
+    with I = Ybus V. Its pattern is the same every iteration, so it is
+    analyzed once, factored on the first iteration and refactored after
+    that, falling back to a fresh factorization with pivoting if a pivot
+    of the old order vanishes.
+
+    |V| of PV and slack buses is held at the case's Vm, which for a solved
+    case is the voltage the generators actually regulate, remote or at a
+    reactive limit. Reactive limits are not enforced.
+*/
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PowerFlowError {
+    NoSlack,
+    Lu(LuError),
+}
+
+impl fmt::Display for PowerFlowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PowerFlowError::NoSlack => write!(f, "the case has no slack bus"),
+            PowerFlowError::Lu(err) => write!(f, "power flow Jacobian: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for PowerFlowError {}
+
+impl From<LuError> for PowerFlowError {
+    fn from(err: LuError) -> Self {
+        PowerFlowError::Lu(err)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PowerFlowOptions {
+    // largest |P| or |Q| mismatch in per unit to reach
+    pub tol: f64,
+    pub max_iter: usize,
+    // start PQ buses at 1 pu and every angle at the slack angle instead of
+    // the case's Vm / Va
+    pub flat_start: bool,
+    pub lu: LuOptions,
+}
+
+impl Default for PowerFlowOptions {
+    fn default() -> Self {
+        Self {
+            tol: 1e-8,
+            max_iter: 20,
+            flat_start: true,
+            lu: LuOptions {
+                ordering: ColumnOrdering::Amd,
+                diag_pivot_thresh: 0.1,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PowerFlowResult {
+    // per bus, in the order of Case::buses, Va in degrees
+    pub vm: Vec<f64>,
+    pub va: Vec<f64>,
+    pub converged: bool,
+    pub iterations: usize,
+    // largest mismatch before the first and after every iteration
+    pub mismatches: Vec<f64>,
+}
+
+// which unknowns and equations a bus has, positions in the Newton vector
+pub(crate) struct Unknowns {
+    // PV and PQ buses: angle, P equation
+    pub pvpq: Vec<usize>,
+    // PQ buses: magnitude, Q equation
+    pub pq: Vec<usize>,
+    // per bus, position in pvpq / pq
+    pub angle: Vec<Option<usize>>,
+    pub magnitude: Vec<Option<usize>>,
+}
+
+impl Unknowns {
+    pub fn new(case: &Case) -> Result<Self, PowerFlowError> {
+        let n = case.buses.len();
+        let types: Vec<BusType> = case.buses.iter().map(|bus| bus.bus_type).collect();
+        if !types.contains(&BusType::Slack) {
+            return Err(PowerFlowError::NoSlack);
+        }
+        let pvpq: Vec<usize> = (0..n)
+            .filter(|&k| matches!(types[k], BusType::Pv | BusType::Pq))
+            .collect();
+        let pq: Vec<usize> = (0..n).filter(|&k| types[k] == BusType::Pq).collect();
+
+        let mut angle = vec![None; n];
+        let mut magnitude = vec![None; n];
+        for (position, &k) in pvpq.iter().enumerate() {
+            angle[k] = Some(position);
+        }
+        for (position, &k) in pq.iter().enumerate() {
+            magnitude[k] = Some(position);
+        }
+        Ok(Unknowns {
+            pvpq,
+            pq,
+            angle,
+            magnitude,
+        })
+    }
+}
+
+impl Case {
+    /// Specified complex power injection per bus, generation minus load, per unit.
+    pub fn power_injections(&self) -> Vec<Complex<f64>> {
+        let index = self.bus_index();
+        let mut injections = vec![Complex::new(0.0, 0.0); self.buses.len()];
+        for generator in self.generators.iter().filter(|g| g.in_service) {
+            injections[index[&generator.bus]] += Complex::new(generator.pg, generator.qg);
+        }
+        for load in self.loads.iter().filter(|load| load.in_service) {
+            injections[index[&load.bus]] -= Complex::new(load.pd, load.qd);
+        }
+        injections.iter().map(|s| s / self.base_mva).collect()
+    }
+
+    pub(crate) fn initial_voltages(&self, flat_start: bool) -> (Vec<f64>, Vec<f64>) {
+        let slack_va = self
+            .buses
+            .iter()
+            .find(|bus| bus.bus_type == BusType::Slack)
+            .map_or(0.0, |bus| bus.va);
+        self.buses
+            .iter()
+            .map(|bus| match (flat_start, bus.bus_type) {
+                (true, BusType::Pq) => (1.0, slack_va.to_radians()),
+                (true, BusType::Pv) => (bus.vm, slack_va.to_radians()),
+                _ => (bus.vm, bus.va.to_radians()),
+            })
+            .unzip()
+    }
+
+    pub fn newton_power_flow(
+        &self,
+        options: &PowerFlowOptions,
+    ) -> Result<PowerFlowResult, PowerFlowError> {
+        let unknowns = Unknowns::new(self)?;
+        let ybus = self.ybus();
+        let s_spec = self.power_injections();
+        let (mut vm, mut va) = self.initial_voltages(options.flat_start);
+        let npvpq = unknowns.pvpq.len();
+
+        let mut v = voltages(&vm, &va);
+        let (mut mismatch, mut norm) = mismatch(&ybus, &v, &s_spec, &unknowns);
+        let mut mismatches = vec![norm];
+        let mut symbolic = None;
+        let mut factors = None;
+        let mut iterations = 0;
+
+        while norm > options.tol && iterations < options.max_iter {
+            let jacobian = jacobian(&ybus, &v, &unknowns);
+            let symbolic = match &symbolic {
+                Some(symbolic) => symbolic,
+                None => symbolic.insert(jacobian.analyze(&options.lu)?),
+            };
+            let refactored = match &mut factors {
+                Some(factors) => symbolic.refactor(factors, &jacobian).is_ok(),
+                None => false,
+            };
+            if !refactored {
+                factors = Some(symbolic.factor(&jacobian)?);
+            }
+            let dx = factors.as_ref().unwrap().solve(&mismatch);
+
+            for (position, &k) in unknowns.pvpq.iter().enumerate() {
+                va[k] += dx[position];
+            }
+            for (position, &k) in unknowns.pq.iter().enumerate() {
+                vm[k] += dx[npvpq + position];
+            }
+            v = voltages(&vm, &va);
+            (mismatch, norm) = self::mismatch(&ybus, &v, &s_spec, &unknowns);
+            mismatches.push(norm);
+            iterations += 1;
+        }
+
+        Ok(PowerFlowResult {
+            vm,
+            va: va.iter().map(|va| va.to_degrees()).collect(),
+            converged: norm <= options.tol,
+            iterations,
+            mismatches,
+        })
+    }
+}
+
+pub(crate) fn voltages(vm: &[f64], va: &[f64]) -> Vec<Complex<f64>> {
+    vm.iter()
+        .zip(va)
+        .map(|(&vm, &va)| Complex::from_polar(vm, va))
+        .collect()
+}
+
+// [dP at pvpq; dQ at pq] of S_spec - V conj(Ybus V), and its largest entry
+pub(crate) fn mismatch(
+    ybus: &SparseCSC<Complex<f64>>,
+    v: &[Complex<f64>],
+    s_spec: &[Complex<f64>],
+    unknowns: &Unknowns,
+) -> (Vec<f64>, f64) {
+    let current = ybus.matvec(v);
+    let ds: Vec<Complex<f64>> = (0..v.len())
+        .map(|k| s_spec[k] - v[k] * current[k].conj())
+        .collect();
+    let mismatch: Vec<f64> = unknowns
+        .pvpq
+        .iter()
+        .map(|&k| ds[k].re)
+        .chain(unknowns.pq.iter().map(|&k| ds[k].im))
+        .collect();
+    let norm = mismatch.iter().fold(0.0, |max: f64, x| max.max(x.abs()));
+    (mismatch, norm)
+}
+
+fn jacobian(
+    ybus: &SparseCSC<Complex<f64>>,
+    v: &[Complex<f64>],
+    unknowns: &Unknowns,
+) -> SparseCSC<f64> {
+    let n = unknowns.pvpq.len() + unknowns.pq.len();
+    let npvpq = unknowns.pvpq.len();
+    let current = ybus.matvec(v);
+    let unit: Vec<Complex<f64>> = v.iter().map(|v| v / v.norm()).collect();
+
+    // flat index row * n + col -> J[row, col]
+    let mut entries: HashMap<usize, f64> = HashMap::new();
+    // dS_i / dVa_j and dS_i / dVm_j into the P and Q rows of bus i
+    let mut add = |i: usize, j: usize, ds_dva: Complex<f64>, ds_dvm: Complex<f64>| {
+        let columns = [
+            (unknowns.angle[j], ds_dva),
+            (unknowns.magnitude[j].map(|c| npvpq + c), ds_dvm),
+        ];
+        for (column, ds) in columns {
+            let Some(column) = column else { continue };
+            if let Some(row) = unknowns.angle[i] {
+                *entries.entry(row * n + column).or_default() += ds.re;
+            }
+            if let Some(row) = unknowns.magnitude[i].map(|r| npvpq + r) {
+                *entries.entry(row * n + column).or_default() += ds.im;
+            }
+        }
+    };
+
+    let j = Complex::<f64>::i();
+    for col in 0..ybus.ncols {
+        let (start, end) = ybus.get_column_range(col);
+        for p in start..end {
+            let (row, y) = (ybus.rowind[p], ybus.values[p]);
+            add(
+                row,
+                col,
+                -j * v[row] * (y * v[col]).conj(),
+                v[row] * (y * unit[col]).conj(),
+            );
+        }
+    }
+    for k in 0..v.len() {
+        add(
+            k,
+            k,
+            j * v[k] * current[k].conj(),
+            current[k].conj() * unit[k],
+        );
+    }
+
+    let (flat_indices, values) = entries.into_iter().unzip();
+    SparseCSC::from_flat_indices(n, n, flat_indices, values)
+}
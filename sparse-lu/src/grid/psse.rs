@@ -0,0 +1,795 @@
+use crate::grid::case::{self, BusType, Case};
+use num_complex::Complex;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::{fs, io};
+
+/*
+    PSS/E v33 RAW reader
+
+    A RAW file is a case identification block followed by data sections,
+    each terminated by a record starting with 0 (usually "0 / END OF ...,
+    BEGIN ..."), the file itself optionally ending with Q. Sections read
+    here, in file order:
+
+        case identification  IC, SBASE, REV, XFRRAT, NXFRAT, BASFRQ, then
+                             two title lines
+        bus, load, fixed shunt, generator, branch
+                             one record per line
+        transformer          four lines per two-winding record, five per
+                             three-winding record (K != 0):
+                                 I, J, K, CKT, CW, CZ, CM, MAG1, MAG2, ...
+                                 R1-2, X1-2, SBASE1-2 [, R2-3 ... ANSTAR]
+                                 WINDV1, NOMV1, ANG1, RATA1, ...
+                                 WINDV2, NOMV2 [, ANG2, RATA2, ...]
+                                 [WINDV3, NOMV3, ANG3, RATA3, ...]
+
+        areas ... FACTS devices
+                             skipped, every record of these starts with a
+                             nonzero number or a quoted name, so counting
+                             the terminators is enough
+        switched shunt       one record per line
+
+    The remaining sections (GNE devices, induction machines) are ignored. A
+    Q record ends the data early, leaving the sections after it empty.
+
+    Fields are comma separated, strings in single quotes and '/' outside a
+    string starts a comment. Fields left off the end of a record take the
+    PSS/E defaults. Units are as in the file: powers in MW / MVAr, voltages
+    in per unit, angles in degrees, branch impedances in per unit on SBASE
+    and transformer impedances as selected by CZ.
+
+    Errors carry the 1-based line of the offending field.
+*/
+
+#[derive(Debug)]
+pub enum PsseError {
+    Io(PathBuf, io::Error),
+    UnsupportedVersion(u32),
+    // line, field, text that failed to parse
+    Parse(usize, &'static str, String),
+    // line, field
+    MissingField(usize, &'static str),
+    // line where a string opened with ' is not closed
+    UnterminatedString(usize),
+    // section the file ends in
+    UnexpectedEnd(&'static str),
+}
+
+impl fmt::Display for PsseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PsseError::Io(path, err) => write!(f, "{}: {}", path.display(), err),
+            PsseError::UnsupportedVersion(rev) => {
+                write!(f, "RAW version {} is not supported, expected 33", rev)
+            }
+            PsseError::Parse(line, field, text) => {
+                write!(f, "line {}: invalid {} {:?}", line, field, text)
+            }
+            PsseError::MissingField(line, field) => write!(f, "line {}: missing {}", line, field),
+            PsseError::UnterminatedString(line) => write!(f, "line {}: unterminated string", line),
+            PsseError::UnexpectedEnd(section) => {
+                write!(f, "file ends inside the {} data", section)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PsseError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bus {
+    pub number: usize,
+    pub name: String,
+    pub base_kv: f64,
+    pub bus_type: BusType,
+    pub area: usize,
+    pub zone: usize,
+    pub vm: f64,
+    pub va: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Load {
+    pub bus: usize,
+    pub id: String,
+    pub in_service: bool,
+    pub area: usize,
+    pub zone: usize,
+    // constant power, constant current and constant admittance parts, the
+    // last two in MW / MVAr at 1 pu voltage
+    pub pl: f64,
+    pub ql: f64,
+    pub ip: f64,
+    pub iq: f64,
+    pub yp: f64,
+    pub yq: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedShunt {
+    pub bus: usize,
+    pub id: String,
+    pub in_service: bool,
+    pub gl: f64,
+    pub bl: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Generator {
+    pub bus: usize,
+    pub id: String,
+    pub pg: f64,
+    pub qg: f64,
+    pub qmax: f64,
+    pub qmin: f64,
+    // voltage setpoint of the regulated bus, ireg = 0 for the generator bus
+    pub vs: f64,
+    pub ireg: usize,
+    pub mbase: f64,
+    pub in_service: bool,
+    pub pmax: f64,
+    pub pmin: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Branch {
+    pub from: usize,
+    pub to: usize,
+    pub circuit: String,
+    pub r: f64,
+    pub x: f64,
+    pub b: f64,
+    pub rate_a: f64,
+    pub rate_b: f64,
+    pub rate_c: f64,
+    // line shunts at the from and to ends, per unit
+    pub gi: f64,
+    pub bi: f64,
+    pub gj: f64,
+    pub bj: f64,
+    pub in_service: bool,
+    pub length: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Impedance {
+    pub r: f64,
+    pub x: f64,
+    // winding MVA base, used when CZ is 2 or 3
+    pub sbase: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Winding {
+    // ratio in the units selected by CW, nominal voltage in kV
+    pub windv: f64,
+    pub nomv: f64,
+    pub ang: f64,
+    pub rate_a: f64,
+    pub rate_b: f64,
+    pub rate_c: f64,
+    // control mode, 0 for a fixed tap
+    pub cod: i32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Transformer {
+    pub i: usize,
+    pub j: usize,
+    // third winding bus, None for a two-winding transformer
+    pub k: Option<usize>,
+    pub circuit: String,
+    pub cw: u8,
+    pub cz: u8,
+    pub cm: u8,
+    pub mag1: f64,
+    pub mag2: f64,
+    pub name: String,
+    // 0 out of service, 1 in service, 2 / 3 / 4 winding 2 / 3 / 1 out
+    pub status: u8,
+    // 1-2, then 2-3 and 3-1 for three windings
+    pub impedances: Vec<Impedance>,
+    // star point voltage and angle of a three-winding transformer
+    pub vmstar: f64,
+    pub anstar: f64,
+    pub windings: Vec<Winding>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwitchedShunt {
+    pub bus: usize,
+    // control mode, 0 for fixed
+    pub modsw: i32,
+    pub in_service: bool,
+    pub vswhi: f64,
+    pub vswlo: f64,
+    // MVAr at 1 pu in the solved case
+    pub binit: f64,
+    // (number of steps, MVAr per step) of each block
+    pub blocks: Vec<(u32, f64)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawCase {
+    pub ic: u32,
+    pub sbase: f64,
+    pub rev: u32,
+    pub basfrq: f64,
+    pub titles: [String; 2],
+    pub buses: Vec<Bus>,
+    pub loads: Vec<Load>,
+    pub fixed_shunts: Vec<FixedShunt>,
+    pub generators: Vec<Generator>,
+    pub branches: Vec<Branch>,
+    pub transformers: Vec<Transformer>,
+    pub switched_shunts: Vec<SwitchedShunt>,
+}
+
+impl RawCase {
+    pub fn read(path: impl AsRef<Path>) -> Result<RawCase, PsseError> {
+        let path = path.as_ref();
+        let text =
+            fs::read_to_string(path).map_err(|err| PsseError::Io(path.to_path_buf(), err))?;
+        RawCase::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<RawCase, PsseError> {
+        let mut lines = Lines {
+            lines: text.lines().enumerate(),
+            finished: false,
+        };
+
+        let header = lines.record("case identification")?;
+        let rev = header.get_or(2, "REV", 33)?;
+        if rev != 33 {
+            return Err(PsseError::UnsupportedVersion(rev));
+        }
+        let mut titles: [String; 2] = Default::default();
+        for title in titles.iter_mut() {
+            *title = lines.next_line("case identification")?.1.trim().to_string();
+        }
+        let mut case = RawCase {
+            ic: header.get_or(0, "IC", 0)?,
+            sbase: header.get_or(1, "SBASE", 100.0)?,
+            rev,
+            basfrq: header.get_or(5, "BASFRQ", 60.0)?,
+            titles,
+            buses: vec![],
+            loads: vec![],
+            fixed_shunts: vec![],
+            generators: vec![],
+            branches: vec![],
+            transformers: vec![],
+            switched_shunts: vec![],
+        };
+
+        while let Some(record) = lines.section_record("bus")? {
+            case.buses.push(record.bus()?);
+        }
+        while let Some(record) = lines.section_record("load")? {
+            case.loads.push(record.load()?);
+        }
+        while let Some(record) = lines.section_record("fixed shunt")? {
+            case.fixed_shunts.push(record.fixed_shunt()?);
+        }
+        while let Some(record) = lines.section_record("generator")? {
+            case.generators.push(record.generator()?);
+        }
+        while let Some(record) = lines.section_record("branch")? {
+            case.branches.push(record.branch()?);
+        }
+        while let Some(record) = lines.section_record("transformer")? {
+            let three_winding = record.get_or(2, "K", 0usize)? != 0;
+            let rest = (0..if three_winding { 4 } else { 3 })
+                .map(|_| lines.record("transformer"))
+                .collect::<Result<Vec<_>, _>>()?;
+            case.transformers.push(transformer(&record, &rest)?);
+        }
+        for section in SKIPPED_SECTIONS {
+            while lines.section_record(section)?.is_some() {}
+        }
+        while let Some(record) = lines.section_record("switched shunt")? {
+            case.switched_shunts.push(record.switched_shunt()?);
+        }
+        Ok(case)
+    }
+}
+
+const SKIPPED_SECTIONS: [&str; 10] = [
+    "area",
+    "two-terminal DC",
+    "voltage source converter",
+    "impedance correction",
+    "multi-terminal DC",
+    "multi-section line",
+    "zone",
+    "inter-area transfer",
+    "owner",
+    "FACTS device",
+];
+
+struct Lines<'a> {
+    lines: std::iter::Enumerate<std::str::Lines<'a>>,
+    // a Q record was read
+    finished: bool,
+}
+
+impl<'a> Lines<'a> {
+    fn next_line(&mut self, section: &'static str) -> Result<(usize, &'a str), PsseError> {
+        let (k, line) = self.lines.next().ok_or(PsseError::UnexpectedEnd(section))?;
+        Ok((k + 1, line))
+    }
+
+    fn record(&mut self, section: &'static str) -> Result<Record, PsseError> {
+        Record::new(self.next_line(section)?)
+    }
+
+    // next non-blank record of a section, None at its terminating 0 or Q
+    fn section_record(&mut self, section: &'static str) -> Result<Option<Record>, PsseError> {
+        while !self.finished {
+            let record = self.record(section)?;
+            match record.fields.first().map(String::as_str) {
+                None => continue,
+                Some("0") => return Ok(None),
+                Some("Q") => self.finished = true,
+                Some(_) => return Ok(Some(record)),
+            }
+        }
+        Ok(None)
+    }
+}
+
+// one line split into fields, quotes and comments removed
+struct Record {
+    line: usize,
+    fields: Vec<String>,
+}
+
+impl Record {
+    fn new((line, text): (usize, &str)) -> Result<Record, PsseError> {
+        let mut fields = vec![];
+        let mut field = String::new();
+        let mut chars = text.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\'' | '"' => loop {
+                    match chars.next() {
+                        Some(end) if end == c => break,
+                        Some(inner) => field.push(inner),
+                        None => return Err(PsseError::UnterminatedString(line)),
+                    }
+                },
+                '/' => break,
+                ',' => fields.push(std::mem::take(&mut field).trim().to_string()),
+                _ => field.push(c),
+            }
+        }
+        if !field.trim().is_empty() || !fields.is_empty() {
+            fields.push(field.trim().to_string());
+        }
+        Ok(Record { line, fields })
+    }
+
+    fn text(&self, k: usize) -> Option<&str> {
+        self.fields
+            .get(k)
+            .map(String::as_str)
+            .filter(|text| !text.is_empty())
+    }
+
+    fn get<F: FromStr>(&self, k: usize, name: &'static str) -> Result<F, PsseError> {
+        let text = self
+            .text(k)
+            .ok_or(PsseError::MissingField(self.line, name))?;
+        text.parse()
+            .map_err(|_| PsseError::Parse(self.line, name, text.to_string()))
+    }
+
+    fn get_or<F: FromStr>(&self, k: usize, name: &'static str, default: F) -> Result<F, PsseError> {
+        match self.text(k) {
+            Some(_) => self.get(k, name),
+            None => Ok(default),
+        }
+    }
+
+    fn string(&self, k: usize) -> String {
+        self.text(k).unwrap_or("").to_string()
+    }
+
+    fn bus(&self) -> Result<Bus, PsseError> {
+        let bus_type = match self.get_or(3, "IDE", 1)? {
+            1 => BusType::Pq,
+            2 => BusType::Pv,
+            3 => BusType::Slack,
+            4 => BusType::Isolated,
+            _ => return Err(PsseError::Parse(self.line, "IDE", self.string(3))),
+        };
+        Ok(Bus {
+            number: self.get(0, "I")?,
+            name: self.string(1),
+            base_kv: self.get_or(2, "BASKV", 0.0)?,
+            bus_type,
+            area: self.get_or(4, "AREA", 1)?,
+            zone: self.get_or(5, "ZONE", 1)?,
+            vm: self.get_or(7, "VM", 1.0)?,
+            va: self.get_or(8, "VA", 0.0)?,
+        })
+    }
+
+    fn load(&self) -> Result<Load, PsseError> {
+        Ok(Load {
+            bus: self.get(0, "I")?,
+            id: self.string(1),
+            in_service: self.get_or::<u8>(2, "STATUS", 1)? != 0,
+            area: self.get_or(3, "AREA", 1)?,
+            zone: self.get_or(4, "ZONE", 1)?,
+            pl: self.get_or(5, "PL", 0.0)?,
+            ql: self.get_or(6, "QL", 0.0)?,
+            ip: self.get_or(7, "IP", 0.0)?,
+            iq: self.get_or(8, "IQ", 0.0)?,
+            yp: self.get_or(9, "YP", 0.0)?,
+            yq: self.get_or(10, "YQ", 0.0)?,
+        })
+    }
+
+    fn fixed_shunt(&self) -> Result<FixedShunt, PsseError> {
+        Ok(FixedShunt {
+            bus: self.get(0, "I")?,
+            id: self.string(1),
+            in_service: self.get_or::<u8>(2, "STATUS", 1)? != 0,
+            gl: self.get_or(3, "GL", 0.0)?,
+            bl: self.get_or(4, "BL", 0.0)?,
+        })
+    }
+
+    fn generator(&self) -> Result<Generator, PsseError> {
+        Ok(Generator {
+            bus: self.get(0, "I")?,
+            id: self.string(1),
+            pg: self.get_or(2, "PG", 0.0)?,
+            qg: self.get_or(3, "QG", 0.0)?,
+            qmax: self.get_or(4, "QT", 9999.0)?,
+            qmin: self.get_or(5, "QB", -9999.0)?,
+            vs: self.get_or(6, "VS", 1.0)?,
+            ireg: self.get_or(7, "IREG", 0)?,
+            mbase: self.get_or(8, "MBASE", 100.0)?,
+            in_service: self.get_or::<u8>(14, "STAT", 1)? != 0,
+            pmax: self.get_or(16, "PT", 9999.0)?,
+            pmin: self.get_or(17, "PB", -9999.0)?,
+        })
+    }
+
+    fn branch(&self) -> Result<Branch, PsseError> {
+        Ok(Branch {
+            from: self.get(0, "I")?,
+            to: self.get(1, "J")?,
+            circuit: self.string(2),
+            r: self.get_or(3, "R", 0.0)?,
+            x: self.get(4, "X")?,
+            b: self.get_or(5, "B", 0.0)?,
+            rate_a: self.get_or(6, "RATEA", 0.0)?,
+            rate_b: self.get_or(7, "RATEB", 0.0)?,
+            rate_c: self.get_or(8, "RATEC", 0.0)?,
+            gi: self.get_or(9, "GI", 0.0)?,
+            bi: self.get_or(10, "BI", 0.0)?,
+            gj: self.get_or(11, "GJ", 0.0)?,
+            bj: self.get_or(12, "BJ", 0.0)?,
+            in_service: self.get_or::<u8>(13, "ST", 1)? != 0,
+            length: self.get_or(15, "LEN", 0.0)?,
+        })
+    }
+
+    fn switched_shunt(&self) -> Result<SwitchedShunt, PsseError> {
+        let mut blocks = vec![];
+        let mut k = 10;
+        while self.text(k).is_some() {
+            blocks.push((self.get(k, "N")?, self.get_or(k + 1, "B", 0.0)?));
+            k += 2;
+        }
+        Ok(SwitchedShunt {
+            bus: self.get(0, "I")?,
+            modsw: self.get_or(1, "MODSW", 1)?,
+            in_service: self.get_or::<u8>(3, "STAT", 1)? != 0,
+            vswhi: self.get_or(4, "VSWHI", 1.0)?,
+            vswlo: self.get_or(5, "VSWLO", 1.0)?,
+            binit: self.get_or(9, "BINIT", 0.0)?,
+            blocks,
+        })
+    }
+
+    fn impedance(&self, first: usize, names: [&'static str; 3]) -> Result<Impedance, PsseError> {
+        Ok(Impedance {
+            r: self.get_or(first, names[0], 0.0)?,
+            x: self.get(first + 1, names[1])?,
+            sbase: self.get_or(first + 2, names[2], 100.0)?,
+        })
+    }
+
+    fn winding(&self, names: [&'static str; 3]) -> Result<Winding, PsseError> {
+        Ok(Winding {
+            windv: self.get_or(0, names[0], 1.0)?,
+            nomv: self.get_or(1, names[1], 0.0)?,
+            ang: self.get_or(2, names[2], 0.0)?,
+            rate_a: self.get_or(3, "RATA", 0.0)?,
+            rate_b: self.get_or(4, "RATB", 0.0)?,
+            rate_c: self.get_or(5, "RATC", 0.0)?,
+            cod: self.get_or(6, "COD", 0)?,
+        })
+    }
+}
+
+fn transformer(first: &Record, rest: &[Record]) -> Result<Transformer, PsseError> {
+    let k: usize = first.get_or(2, "K", 0)?;
+    let impedances = if k == 0 {
+        vec![rest[0].impedance(0, ["R1-2", "X1-2", "SBASE1-2"])?]
+    } else {
+        vec![
+            rest[0].impedance(0, ["R1-2", "X1-2", "SBASE1-2"])?,
+            rest[0].impedance(3, ["R2-3", "X2-3", "SBASE2-3"])?,
+            rest[0].impedance(6, ["R3-1", "X3-1", "SBASE3-1"])?,
+        ]
+    };
+    let names = [
+        ["WINDV1", "NOMV1", "ANG1"],
+        ["WINDV2", "NOMV2", "ANG2"],
+        ["WINDV3", "NOMV3", "ANG3"],
+    ];
+    let windings = rest[1..]
+        .iter()
+        .zip(names)
+        .map(|(record, names)| record.winding(names))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Transformer {
+        i: first.get(0, "I")?,
+        j: first.get(1, "J")?,
+        k: (k != 0).then_some(k),
+        circuit: first.string(3),
+        cw: first.get_or(4, "CW", 1)?,
+        cz: first.get_or(5, "CZ", 1)?,
+        cm: first.get_or(6, "CM", 1)?,
+        mag1: first.get_or(7, "MAG1", 0.0)?,
+        mag2: first.get_or(8, "MAG2", 0.0)?,
+        name: first.string(10),
+        status: first.get_or(11, "STAT", 1)?,
+        impedances,
+        vmstar: rest[0].get_or(9, "VMSTAR", 1.0)?,
+        anstar: rest[0].get_or(10, "ANSTAR", 0.0)?,
+        windings,
+    })
+}
+
+/*
+    Conversion to a Case
+
+    Switched shunts become fixed shunts at BINIT. Lines become branches
+    with tap 1 and shift 0, their line shunts GI + jBI
+    and GJ + jBJ fixed shunts at the two ends. Constant current loads are
+    taken at 1 pu and added to the constant power part, constant admittance
+    loads become shunts.
+
+    A two-winding transformer is a branch from I to J with tap WINDV1 /
+    WINDV2, both turned into per unit of the bus base voltage as CW says,
+    shift ANG1 and impedance R1-2 + jX1-2 brought to the system base as CZ
+    says. Its magnetizing admittance (MAG1, MAG2 read as CM says) is a shunt
+    at I.
+
+    A three-winding transformer is expanded into a star: a new PQ bus,
+    Isolated when the transformer is out of service, numbered after the
+    largest bus number and started at VMSTAR / ANSTAR,
+    and one branch per winding from its bus to the star point, carrying the
+    winding's tap and shift and the star impedance
+        Z1 = (Z12 + Z31 - Z23) / 2
+        Z2 = (Z12 + Z23 - Z31) / 2
+        Z3 = (Z23 + Z31 - Z12) / 2
+*/
+
+impl RawCase {
+    pub fn to_case(&self) -> Case {
+        let base_kv: HashMap<usize, f64> = self
+            .buses
+            .iter()
+            .map(|bus| (bus.number, bus.base_kv))
+            .collect();
+        let mut case = Case {
+            base_mva: self.sbase,
+            buses: self
+                .buses
+                .iter()
+                .map(|bus| case::Bus {
+                    number: bus.number,
+                    name: bus.name.clone(),
+                    base_kv: bus.base_kv,
+                    bus_type: bus.bus_type,
+                    vm: bus.vm,
+                    va: bus.va,
+                })
+                .collect(),
+            branches: vec![],
+            shunts: vec![],
+            loads: vec![],
+            generators: vec![],
+        };
+        let shunt = |case: &mut Case, bus, g: f64, b: f64, in_service| {
+            if g != 0.0 || b != 0.0 {
+                case.shunts.push(case::Shunt {
+                    bus,
+                    g,
+                    b,
+                    in_service,
+                });
+            }
+        };
+
+        for load in &self.loads {
+            case.loads.push(case::Load {
+                bus: load.bus,
+                id: load.id.clone(),
+                pd: load.pl + load.ip,
+                qd: load.ql + load.iq,
+                in_service: load.in_service,
+            });
+            // YQ > 0 is a capacitive load
+            shunt(&mut case, load.bus, load.yp, load.yq, load.in_service);
+        }
+        for fixed in &self.fixed_shunts {
+            shunt(&mut case, fixed.bus, fixed.gl, fixed.bl, fixed.in_service);
+        }
+        // held at the solved case's value, no switching
+        for switched in &self.switched_shunts {
+            shunt(
+                &mut case,
+                switched.bus,
+                0.0,
+                switched.binit,
+                switched.in_service,
+            );
+        }
+        for generator in &self.generators {
+            case.generators.push(case::Generator {
+                bus: generator.bus,
+                id: generator.id.clone(),
+                pg: generator.pg,
+                qg: generator.qg,
+                qmax: generator.qmax,
+                qmin: generator.qmin,
+                vset: generator.vs,
+                in_service: generator.in_service,
+            });
+        }
+
+        for line in &self.branches {
+            case.branches.push(case::Branch {
+                from: line.from,
+                to: line.to,
+                circuit: line.circuit.clone(),
+                r: line.r,
+                x: line.x,
+                b: line.b,
+                tap: 1.0,
+                shift: 0.0,
+                in_service: line.in_service,
+            });
+            let (sbase, on) = (self.sbase, line.in_service);
+            shunt(&mut case, line.from, line.gi * sbase, line.bi * sbase, on);
+            shunt(&mut case, line.to, line.gj * sbase, line.bj * sbase, on);
+        }
+
+        let mut next_bus = self.buses.iter().map(|bus| bus.number).max().unwrap_or(0) + 1;
+        for transformer in &self.transformers {
+            let ratio = |w: usize, bus: usize| {
+                let winding = &transformer.windings[w];
+                let kv = base_kv.get(&bus).copied().unwrap_or(0.0);
+                match transformer.cw {
+                    2 if kv > 0.0 => winding.windv / kv,
+                    3 if kv > 0.0 && winding.nomv > 0.0 => winding.windv * winding.nomv / kv,
+                    _ => winding.windv,
+                }
+            };
+            let impedances: Vec<Complex<f64>> = transformer
+                .impedances
+                .iter()
+                .map(|z| system_impedance(z, transformer.cz, self.sbase))
+                .collect();
+            let magnetizing = magnetizing(transformer, self.sbase);
+            let branch = |from, to, z: Complex<f64>, tap, shift, in_service| case::Branch {
+                from,
+                to,
+                circuit: transformer.circuit.clone(),
+                r: z.re,
+                x: z.im,
+                b: 0.0,
+                tap,
+                shift,
+                in_service,
+            };
+
+            let (i, j) = (transformer.i, transformer.j);
+            match transformer.k {
+                None => {
+                    let in_service = transformer.status != 0;
+                    let tap = ratio(0, i) / ratio(1, j);
+                    let shift = transformer.windings[0].ang;
+                    case.branches
+                        .push(branch(i, j, impedances[0], tap, shift, in_service));
+                    let (g, b) = (magnetizing.re, magnetizing.im);
+                    shunt(&mut case, i, g * self.sbase, b * self.sbase, in_service);
+                }
+                Some(k) => {
+                    let star = next_bus;
+                    next_bus += 1;
+                    case.buses.push(case::Bus {
+                        number: star,
+                        name: format!("{} STAR", transformer.name),
+                        base_kv: 1.0,
+                        bus_type: if transformer.status == 0 {
+                            BusType::Isolated
+                        } else {
+                            BusType::Pq
+                        },
+                        vm: transformer.vmstar,
+                        va: transformer.anstar,
+                    });
+                    let (z12, z23, z31) = (impedances[0], impedances[1], impedances[2]);
+                    let star_z = [
+                        (z12 + z31 - z23) / 2.0,
+                        (z12 + z23 - z31) / 2.0,
+                        (z23 + z31 - z12) / 2.0,
+                    ];
+                    // status 2 / 3 / 4 takes winding 2 / 3 / 1 out
+                    let out = [4, 2, 3];
+                    for (w, bus) in [i, j, k].into_iter().enumerate() {
+                        let in_service = transformer.status != 0 && transformer.status != out[w];
+                        let shift = transformer.windings[w].ang;
+                        case.branches.push(branch(
+                            bus,
+                            star,
+                            star_z[w],
+                            ratio(w, bus),
+                            shift,
+                            in_service,
+                        ));
+                    }
+                    let (g, b) = (magnetizing.re, magnetizing.im);
+                    let in_service = transformer.status != 0 && transformer.status != 4;
+                    shunt(&mut case, i, g * self.sbase, b * self.sbase, in_service);
+                }
+            }
+        }
+        case
+    }
+}
+
+// R + jX in per unit on the system base
+fn system_impedance(z: &Impedance, cz: u8, sbase: f64) -> Complex<f64> {
+    match cz {
+        // per unit on the winding base
+        2 => Complex::new(z.r, z.x) * (sbase / z.sbase),
+        // load loss in W and |Z| in per unit on the winding base
+        3 => {
+            let r = z.r / (z.sbase * 1e6);
+            let x = (z.x * z.x - r * r).max(0.0).sqrt();
+            Complex::new(r, x) * (sbase / z.sbase)
+        }
+        _ => Complex::new(z.r, z.x),
+    }
+}
+
+// G + jB in per unit on the system base
+fn magnetizing(transformer: &Transformer, sbase: f64) -> Complex<f64> {
+    match transformer.cm {
+        // no-load loss in W and exciting current in per unit on the 1-2 winding base
+        2 => {
+            let winding_base = transformer.impedances[0].sbase;
+            let g = transformer.mag1 / (winding_base * 1e6);
+            let b = -(transformer.mag2 * transformer.mag2 - g * g)
+                .max(0.0)
+                .sqrt();
+            Complex::new(g, b) * (winding_base / sbase)
+        }
+        _ => Complex::new(transformer.mag1, transformer.mag2),
+    }
+}
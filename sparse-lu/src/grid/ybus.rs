@@ -0,0 +1,59 @@
+use crate::grid::case::Case;
+use crate::sparse::sparse_csc::SparseCSC;
+use num_complex::Complex;
+use std::collections::HashMap;
+
+/*
+    Bus admittance matrix
+
+    I = Ybus V for the complex bus voltages V and current injections I, in
+    per unit, rows and columns in the order of Case::buses.
+
+    Every in-service branch is a pi model, series admittance y = 1 / (R + jX)
+    with half the line charging jB/2 at each end, behind an ideal
+    transformer t = tap e^{j shift} on the from side. It adds the block
+
+        [ If ]   [ (y + jB/2) / |t|^2   -y / conj(t) ] [ Vf ]
+        [ It ] = [ -y / t                 y + jB/2   ] [ Vt ]
+
+    to rows / columns (f, t), parallel branches summing up. The block is
+    symmetric unless shift != 0. Each in-service fixed shunt adds
+    (G + jB) / base_mva to its diagonal entry, G and B being the MW / MVAr
+    it draws at 1 pu.
+*/
+
+impl Case {
+    pub fn ybus(&self) -> SparseCSC<Complex<f64>> {
+        let n = self.buses.len();
+        let index = self.bus_index();
+        let position = |bus: usize| match index.get(&bus) {
+            Some(&k) => k,
+            None => panic!("bus {} is not in the case", bus),
+        };
+
+        // flat index i * n + j -> Ybus[i, j]
+        let mut entries: HashMap<usize, Complex<f64>> = HashMap::new();
+        let mut add = |i: usize, j: usize, y: Complex<f64>| {
+            *entries.entry(i * n + j).or_default() += y;
+        };
+
+        for branch in self.branches.iter().filter(|branch| branch.in_service) {
+            let (f, t) = (position(branch.from), position(branch.to));
+            let y = Complex::new(branch.r, branch.x).inv();
+            let charging = Complex::new(0.0, branch.b / 2.0);
+            let tap = Complex::from_polar(branch.tap, branch.shift.to_radians());
+
+            add(f, f, (y + charging) / tap.norm_sqr());
+            add(f, t, -y / tap.conj());
+            add(t, f, -y / tap);
+            add(t, t, y + charging);
+        }
+        for shunt in self.shunts.iter().filter(|shunt| shunt.in_service) {
+            let k = position(shunt.bus);
+            add(k, k, Complex::new(shunt.g, shunt.b) / self.base_mva);
+        }
+
+        let (flat_indices, values) = entries.into_iter().unzip();
+        SparseCSC::from_flat_indices(n, n, flat_indices, values)
+    }
+}
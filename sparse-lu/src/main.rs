@@ -1,12 +1,9 @@
-mod sparse;
-
-use crate::sparse::sparse_coo::SparseCOO;
-use crate::sparse::sparse_csc::SparseCSC;
-use crate::sparse::sparse_csr::SparseCSR;
-use crate::sparse::sparse_matrix::SparseMatrixTrait;
+use sparse_lu::sparse::sparse_coo::SparseCOO;
+use sparse_lu::sparse::sparse_csr::SparseCSR;
+use sparse_lu::sparse::sparse_matrix::SparseMatrixTrait;
 use std::time::Instant;
 
-pub fn dense_matrix_multiply(a: &Vec<Vec<f32>>, b: &Vec<Vec<f32>>) -> Vec<Vec<f32>> {
+pub fn dense_matrix_multiply(a: &[Vec<f32>], b: &[Vec<f32>]) -> Vec<Vec<f32>> {
     let rows_a = a.len();
     let cols_a = a[0].len();
     let cols_b = b[0].len();
@@ -45,7 +42,7 @@ fn main() {
     let density = 0.0001;
 
     let mut start = Instant::now();
-    let mat = SparseCOO::random(n, n, density);
+    let mat: SparseCOO<f32> = SparseCOO::random(n, n, density);
     let mat2 = SparseCOO::random(n, n, density);
     println!(
         "Instantiated matrices in {:?} nnz {}",
@@ -62,7 +59,7 @@ fn main() {
     );
 
     let mut start = Instant::now();
-    let mat = SparseCSR::random(n, n, density);
+    let mat: SparseCSR<f32> = SparseCSR::random(n, n, density);
     let mat2 = SparseCSR::random(n, n, density);
     println!(
         "CSR-CSR Instantiated matrices in {:?} nnz {}",
@@ -101,7 +98,7 @@ fn main() {
         vec![5.0, 0.0, 6.0],
     ];
 
-    let mut mat5 = SparseCSR::from_dense(dense.clone());
+    let mat5 = SparseCSR::from_dense(dense.clone());
     let mat6 = SparseCSR::from_dense(dense.clone());
 
     let (indexes, values) = mat5.multiply_to_flat_csr(&mat6);
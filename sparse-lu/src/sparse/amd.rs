@@ -0,0 +1,263 @@
+use crate::sparse::scalar::Scalar;
+use crate::sparse::sparse_csc::SparseCSC;
+use crate::sparse::sparse_csr::SparseCSR;
+use std::collections::{BTreeMap, BTreeSet};
+
+/*
+    Approximate Minimum Degree ordering (Amestoy, Davis, Duff)
+
+    Works on the graph of A + A^T, diagonal ignored. Eliminating a node p
+    turns its neighbourhood into a clique; instead of storing that clique
+    the quotient graph keeps p as an "element" whose variable list is the
+    clique. Each variable is adjacent to some variables and some elements.
+
+    Per step:
+        - pick the variable with the smallest approximate degree
+        - Lp = union of its elements' variables and its own variables,
+          the old elements are absorbed into the new element p
+        - for every element e touching Lp compute |Le \ Lp|, which gives
+          a cheap upper bound on the external degree of each i in Lp
+        - variables in Lp with identical adjacency are merged into one
+          supervariable and eliminated together
+
+    The returned permutation has perm[k] = node eliminated at step k.
+*/
+
+#[derive(Clone, Copy, PartialEq)]
+enum Status {
+    Variable,
+    Element,
+    // element contained in a newer one
+    Absorbed,
+    // variable folded into a supervariable
+    Merged,
+}
+
+impl<T: Scalar> SparseCSC<T> {
+    /// AMD ordering of the pattern of A + A^T.
+    pub fn amd(&self) -> Vec<usize> {
+        assert_eq!(self.nrows, self.ncols);
+        amd(self.ncols, &self.colptr, &self.rowind)
+    }
+}
+
+impl<T: Scalar> SparseCSR<T> {
+    /// AMD ordering of the pattern of A + A^T.
+    pub fn amd(&self) -> Vec<usize> {
+        assert_eq!(self.nrows, self.ncols);
+        amd(self.nrows, &self.rowptr, &self.colind)
+    }
+}
+
+// adjacency lists of A + A^T without the diagonal, A given as compressed ptr/ind
+pub(crate) fn symmetric_adjacency(n: usize, ptr: &[usize], ind: &[usize]) -> Vec<Vec<usize>> {
+    let mut adj = vec![Vec::new(); n];
+    for j in 0..n {
+        for &i in &ind[ptr[j]..ptr[j + 1]] {
+            if i != j {
+                adj[i].push(j);
+                adj[j].push(i);
+            }
+        }
+    }
+    for list in adj.iter_mut() {
+        list.sort_unstable();
+        list.dedup();
+    }
+    adj
+}
+
+pub fn amd(n: usize, ptr: &[usize], ind: &[usize]) -> Vec<usize> {
+    let adj_vars = symmetric_adjacency(n, ptr, ind);
+    minimum_degree(n, adj_vars, vec![Vec::new(); n], Vec::new())
+}
+
+/*
+    Minimum degree on a quotient graph with n variables. Element ids below n
+    are variables that have been eliminated, ids from n on are extra
+    elements the caller starts with (COLAMD seeds one per row of A), with
+    their variable lists in initial_elems.
+*/
+pub(crate) fn minimum_degree(
+    n: usize,
+    mut adj_vars: Vec<Vec<usize>>,
+    mut adj_elems: Vec<Vec<usize>>,
+    initial_elems: Vec<Vec<usize>>,
+) -> Vec<usize> {
+    let nodes = n + initial_elems.len();
+    let mut elem_vars: Vec<Vec<usize>> = vec![Vec::new(); n];
+    elem_vars.extend(initial_elems);
+    let mut status = vec![Status::Variable; n];
+    status.resize(nodes, Status::Element);
+
+    // supervariable weights and the original nodes each one stands for
+    let mut nv = vec![1; n];
+    let mut members: Vec<Vec<usize>> = (0..n).map(|i| vec![i]).collect();
+
+    // |adj(i)| bounded by its variables plus the size of each element it touches
+    let mut degree: Vec<usize> = (0..n)
+        .map(|i| {
+            let from_elems: usize = adj_elems[i]
+                .iter()
+                .map(|&e| elem_vars[e].len().saturating_sub(1))
+                .sum();
+            (adj_vars[i].len() + from_elems).min(n.saturating_sub(1))
+        })
+        .collect();
+    let mut queue: BTreeSet<(usize, usize)> = (0..n).map(|i| (degree[i], i)).collect();
+
+    // mark[i] == stamp means i is in the current Lp
+    let mut mark = vec![0; n];
+    // w[e] = weighted |Le \ Lp| while w_stamp[e] == stamp
+    let mut w = vec![0; nodes];
+    let mut w_stamp = vec![0; nodes];
+    let mut stamp = 0;
+
+    let mut perm = Vec::with_capacity(n);
+    let mut eliminated = 0;
+
+    while let Some((_, p)) = queue.pop_first() {
+        stamp += 1;
+        perm.append(&mut members[p]);
+        eliminated += nv[p];
+
+        // build the new element Lp, absorbing the elements p was adjacent to
+        let mut lp = Vec::new();
+        for e in std::mem::take(&mut adj_elems[p]) {
+            if status[e] != Status::Element {
+                continue;
+            }
+            for &i in &elem_vars[e] {
+                if status[i] == Status::Variable && i != p && mark[i] != stamp {
+                    mark[i] = stamp;
+                    lp.push(i);
+                }
+            }
+            status[e] = Status::Absorbed;
+            elem_vars[e] = Vec::new();
+        }
+        for i in std::mem::take(&mut adj_vars[p]) {
+            if status[i] == Status::Variable && mark[i] != stamp {
+                mark[i] = stamp;
+                lp.push(i);
+            }
+        }
+        status[p] = Status::Element;
+        let lp_weight: usize = lp.iter().map(|&i| nv[i]).sum();
+
+        // |Le \ Lp| for every element adjacent to Lp
+        for &i in &lp {
+            for &e in &adj_elems[i] {
+                if status[e] != Status::Element {
+                    continue;
+                }
+                if w_stamp[e] != stamp {
+                    w_stamp[e] = stamp;
+                    elem_vars[e].retain(|&j| status[j] == Status::Variable);
+                    w[e] = elem_vars[e].iter().map(|&j| nv[j]).sum();
+                }
+                w[e] -= nv[i];
+            }
+        }
+
+        // prune adjacency of each i in Lp and bound its degree
+        let remaining = n - eliminated;
+        for &i in &lp {
+            queue.remove(&(degree[i], i));
+
+            let mut elems = vec![p];
+            let mut elem_degree = 0;
+            for &e in &adj_elems[i] {
+                if status[e] != Status::Element || e == p {
+                    continue;
+                }
+                if w[e] == 0 {
+                    // Le is inside Lp, aggressive absorption
+                    status[e] = Status::Absorbed;
+                    elem_vars[e] = Vec::new();
+                } else {
+                    elem_degree += w[e];
+                    elems.push(e);
+                }
+            }
+            adj_elems[i] = elems;
+
+            // variables in Lp are now reached through element p
+            adj_vars[i].retain(|&j| status[j] == Status::Variable && mark[j] != stamp);
+            let var_degree: usize = adj_vars[i].iter().map(|&j| nv[j]).sum();
+
+            let external = lp_weight - nv[i];
+            degree[i] = (remaining - nv[i])
+                .min(degree[i] + external)
+                .min(var_degree + external + elem_degree);
+        }
+
+        detect_supervariables(
+            &lp,
+            &mut adj_elems,
+            &mut adj_vars,
+            &mut status,
+            &mut nv,
+            &mut members,
+            &mut degree,
+        );
+
+        for &i in &lp {
+            if status[i] == Status::Variable {
+                queue.insert((degree[i], i));
+            }
+        }
+        elem_vars[p] = lp;
+        elem_vars[p].retain(|&i| status[i] == Status::Variable);
+    }
+
+    perm
+}
+
+// merge variables in Lp whose element and variable lists are identical
+fn detect_supervariables(
+    lp: &[usize],
+    adj_elems: &mut [Vec<usize>],
+    adj_vars: &mut [Vec<usize>],
+    status: &mut [Status],
+    nv: &mut [usize],
+    members: &mut [Vec<usize>],
+    degree: &mut [usize],
+) {
+    let mut buckets: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for &i in lp {
+        // drop elements absorbed after i's list was built
+        adj_elems[i].retain(|&e| status[e] == Status::Element);
+        adj_elems[i].sort_unstable();
+        adj_vars[i].sort_unstable();
+        let hash = adj_elems[i]
+            .iter()
+            .chain(adj_vars[i].iter())
+            .fold(0usize, |h, &x| h.wrapping_add(x));
+        buckets.entry(hash).or_default().push(i);
+    }
+
+    for bucket in buckets.values() {
+        for (a, &i) in bucket.iter().enumerate() {
+            if status[i] != Status::Variable {
+                continue;
+            }
+            for &j in &bucket[a + 1..] {
+                if status[j] != Status::Variable
+                    || adj_elems[i] != adj_elems[j]
+                    || adj_vars[i] != adj_vars[j]
+                {
+                    continue;
+                }
+                // j is indistinguishable from i, fold it in
+                status[j] = Status::Merged;
+                nv[i] += nv[j];
+                degree[i] = degree[i].saturating_sub(nv[j]);
+                let moved = std::mem::take(&mut members[j]);
+                members[i].extend(moved);
+                adj_elems[j] = Vec::new();
+                adj_vars[j] = Vec::new();
+            }
+        }
+    }
+}
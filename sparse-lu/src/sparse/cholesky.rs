@@ -0,0 +1,292 @@
+use crate::sparse::permutation::{inverse_permutation, is_permutation};
+use crate::sparse::scalar::Real;
+use crate::sparse::sparse_csc::SparseCSC;
+use crate::sparse::triangular::Diag;
+use std::cmp::Ordering;
+use std::fmt;
+
+/*
+    Up-looking sparse Cholesky, P A P^T = L L^T, and LDL^T, P A P^T = L D L^T
+
+    A is symmetric and given by its lower triangle in CSC, entries above the
+    diagonal are ignored. P is an optional fill-reducing permutation, e.g.
+    from amd(), with the usual perm[k] = row/column of A placed k-th.
+
+    Row k of L comes from a sparse triangular solve with the rows above it:
+
+        L[0..k, 0..k] * L[k, 0..k]^T = A[0..k, k]
+        L[k, k] = sqrt(A[k, k] - L[k, 0..k] . L[k, 0..k])
+
+    The pattern of row k is the set of nodes on the etree paths from each
+    i < k with A[i, k] != 0 up to k (the row subtree, see symbolic.rs), so
+    the etree gives it directly without a general DFS, already in a valid
+    order. The column counts fix where every entry of L goes up front.
+
+    LDL^T is the same with a unit L and a diagonal D, no square roots, and
+    works for symmetric indefinite matrices as long as no d[k] is zero.
+*/
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CholeskyError {
+    NotSquare(usize, usize),
+    // step k, A[k, k] - L[k, 0..k] . L[k, 0..k] <= 0
+    NotPositiveDefinite(usize),
+    // d[k] == 0 in LDL^T
+    ZeroPivot(usize),
+    // perm that is not a permutation of 0..n
+    InvalidOrdering(usize),
+}
+
+impl fmt::Display for CholeskyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CholeskyError::NotSquare(nrows, ncols) => {
+                write!(
+                    f,
+                    "Cholesky requires a square matrix, got {} x {}",
+                    nrows, ncols
+                )
+            }
+            CholeskyError::NotPositiveDefinite(k) => {
+                write!(f, "matrix is not positive definite, failed at column {}", k)
+            }
+            CholeskyError::ZeroPivot(k) => write!(f, "zero pivot in column {}", k),
+            CholeskyError::InvalidOrdering(n) => {
+                write!(f, "ordering is not a permutation of 0..{}", n)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CholeskyError {}
+
+/// P A P^T = L L^T, perm[k] = row/column of A at position k.
+#[derive(Debug, Clone)]
+pub struct CholeskyFactor<T: Real> {
+    pub l: SparseCSC<T>,
+    pub perm: Vec<usize>,
+}
+
+/// P A P^T = L D L^T with L unit lower triangular, its diagonal stored.
+#[derive(Debug, Clone)]
+pub struct LdlFactors<T: Real> {
+    pub l: SparseCSC<T>,
+    pub d: Vec<T>,
+    pub perm: Vec<usize>,
+}
+
+impl<T: Real> CholeskyFactor<T> {
+    pub fn size(&self) -> usize {
+        self.perm.len()
+    }
+
+    /// Solve A x = b.
+    pub fn solve(&self, b: &[T]) -> Vec<T> {
+        assert_eq!(
+            b.len(),
+            self.size(),
+            "Right-hand side length must match matrix size"
+        );
+        let mut y: Vec<T> = self.perm.iter().map(|&i| b[i]).collect();
+        self.l.lower_solve(&mut y, Diag::NonUnit);
+        self.l.lower_transpose_solve(&mut y, Diag::NonUnit);
+        unpermute(&self.perm, &y)
+    }
+}
+
+impl<T: Real> LdlFactors<T> {
+    pub fn size(&self) -> usize {
+        self.perm.len()
+    }
+
+    /// Solve A x = b.
+    pub fn solve(&self, b: &[T]) -> Vec<T> {
+        assert_eq!(
+            b.len(),
+            self.size(),
+            "Right-hand side length must match matrix size"
+        );
+        let mut y: Vec<T> = self.perm.iter().map(|&i| b[i]).collect();
+        self.l.lower_solve(&mut y, Diag::Unit);
+        for (yk, &dk) in y.iter_mut().zip(&self.d) {
+            *yk /= dk;
+        }
+        self.l.lower_transpose_solve(&mut y, Diag::Unit);
+        unpermute(&self.perm, &y)
+    }
+}
+
+fn unpermute<T: Real>(perm: &[usize], y: &[T]) -> Vec<T> {
+    let mut x = vec![T::zero(); perm.len()];
+    for (k, &i) in perm.iter().enumerate() {
+        x[i] = y[k];
+    }
+    x
+}
+
+impl<T: Real> SparseCSC<T> {
+    /// Cholesky factor of A, lower triangle used.
+    pub fn cholesky(&self) -> Result<CholeskyFactor<T>, CholeskyError> {
+        self.cholesky_with_perm(&(0..self.ncols).collect::<Vec<_>>())
+    }
+
+    /// Cholesky factor of P A P^T, lower triangle of A used.
+    pub fn cholesky_with_perm(&self, perm: &[usize]) -> Result<CholeskyFactor<T>, CholeskyError> {
+        let (l, _) = self.up_looking(perm, false)?;
+        Ok(CholeskyFactor {
+            l,
+            perm: perm.to_vec(),
+        })
+    }
+
+    /// LDL^T factors of A, lower triangle used.
+    pub fn ldlt(&self) -> Result<LdlFactors<T>, CholeskyError> {
+        self.ldlt_with_perm(&(0..self.ncols).collect::<Vec<_>>())
+    }
+
+    /// LDL^T factors of P A P^T, lower triangle of A used.
+    pub fn ldlt_with_perm(&self, perm: &[usize]) -> Result<LdlFactors<T>, CholeskyError> {
+        let (l, d) = self.up_looking(perm, true)?;
+        Ok(LdlFactors {
+            l,
+            d,
+            perm: perm.to_vec(),
+        })
+    }
+
+    // L (and D when ldl) of P A P^T
+    fn up_looking(
+        &self,
+        perm: &[usize],
+        ldl: bool,
+    ) -> Result<(SparseCSC<T>, Vec<T>), CholeskyError> {
+        if self.nrows != self.ncols {
+            return Err(CholeskyError::NotSquare(self.nrows, self.ncols));
+        }
+        let n = self.ncols;
+        if !is_permutation(perm, n) {
+            return Err(CholeskyError::InvalidOrdering(n));
+        }
+
+        let upper = permuted_upper(self, perm);
+        let counts = upper.symbolic_counts();
+        let parent = &counts.parent;
+
+        // column j of L fills lp[j]..lp[j + 1], diagonal first, next[j] = next free slot
+        let mut lp = vec![0; n + 1];
+        for j in 0..n {
+            lp[j + 1] = lp[j] + counts.col_counts[j];
+        }
+        let mut next: Vec<usize> = lp[..n].iter().map(|&p| p + 1).collect();
+        let mut li = vec![0; lp[n]];
+        let mut lx = vec![T::zero(); lp[n]];
+        let mut d = vec![T::zero(); if ldl { n } else { 0 }];
+
+        let mut x = vec![T::zero(); n];
+        let mut marked = vec![false; n];
+        let mut pattern = vec![0; n];
+        let mut path = Vec::new();
+
+        for k in 0..n {
+            // pattern of row k in topological order, into pattern[top..n]
+            let mut top = n;
+            marked[k] = true;
+            let (start, end) = upper.get_column_range(k);
+            for p in start..end {
+                let mut i = upper.rowind[p];
+                x[i] += upper.values[p];
+                while !marked[i] {
+                    marked[i] = true;
+                    path.push(i);
+                    i = parent[i].expect("row subtree must reach its row");
+                }
+                while let Some(i) = path.pop() {
+                    top -= 1;
+                    pattern[top] = i;
+                }
+            }
+
+            let mut dk = x[k];
+            x[k] = T::zero();
+            for &j in &pattern[top..n] {
+                let xj = x[j];
+                x[j] = T::zero();
+                let lkj = if ldl { xj / d[j] } else { xj / lx[lp[j]] };
+                // Cholesky carries L[k, j] up the column, LDL^T carries L[k, j] d[j]
+                let carry = if ldl { xj } else { lkj };
+                for p in lp[j] + 1..next[j] {
+                    x[li[p]] -= lx[p] * carry;
+                }
+                dk -= lkj * carry;
+
+                li[next[j]] = k;
+                lx[next[j]] = lkj;
+                next[j] += 1;
+                marked[j] = false;
+            }
+            marked[k] = false;
+
+            li[lp[k]] = k;
+            if ldl {
+                if dk.is_zero() {
+                    return Err(CholeskyError::ZeroPivot(k));
+                }
+                d[k] = dk;
+                lx[lp[k]] = T::one();
+            } else {
+                // NaN compares as None, so it fails too instead of spreading
+                if dk.partial_cmp(&T::zero()) != Some(Ordering::Greater) {
+                    return Err(CholeskyError::NotPositiveDefinite(k));
+                }
+                lx[lp[k]] = dk.sqrt();
+            }
+        }
+
+        let l = SparseCSC {
+            nrows: n,
+            ncols: n,
+            colptr: lp,
+            rowind: li,
+            values: lx,
+        };
+        Ok((l, d))
+    }
+}
+
+// upper triangle of P A P^T from the lower triangle of A, column k = row k of the lower part
+fn permuted_upper<T: Real>(a: &SparseCSC<T>, perm: &[usize]) -> SparseCSC<T> {
+    let n = a.ncols;
+    let pinv = inverse_permutation(perm);
+    let mut entries: Vec<Vec<(usize, T)>> = vec![Vec::new(); n];
+    for j in 0..n {
+        let (start, end) = a.get_column_range(j);
+        for p in start..end {
+            let i = a.rowind[p];
+            if i < j {
+                continue;
+            }
+            let (r, c) = (pinv[i], pinv[j]);
+            let (r, c) = if r <= c { (r, c) } else { (c, r) };
+            entries[c].push((r, a.values[p]));
+        }
+    }
+
+    let mut colptr = Vec::with_capacity(n + 1);
+    let mut rowind = Vec::new();
+    let mut values = Vec::new();
+    colptr.push(0);
+    for column in entries {
+        for (i, value) in column {
+            rowind.push(i);
+            values.push(value);
+        }
+        colptr.push(rowind.len());
+    }
+    SparseCSC {
+        nrows: n,
+        ncols: n,
+        colptr,
+        rowind,
+        values,
+    }
+}
@@ -0,0 +1,59 @@
+use crate::sparse::amd::minimum_degree;
+use crate::sparse::scalar::Scalar;
+use crate::sparse::sparse_csc::SparseCSC;
+
+/*
+    Column approximate minimum degree ordering
+
+    Orders the columns of A so that the Cholesky factor of (AQ)^T (AQ), and
+    with it the LU factors of AQ under any row pivoting, stay sparse.
+
+    A^T A is never formed: row r of A makes its columns a clique in A^T A,
+    which is exactly what an element is in the AMD quotient graph. So the
+    rows become the initial elements, the columns the variables, and the
+    same minimum degree elimination runs from there.
+
+    Rows with more than max(16, 10 * sqrt(ncols)) entries are left out, as
+    COLAMD does, since one dense row turns A^T A into a full matrix and
+    would make every column look equally bad.
+*/
+
+impl<T: Scalar> SparseCSC<T> {
+    /// COLAMD column ordering, perm[k] = column of A to place k-th. A may be rectangular.
+    pub fn colamd(&self) -> Vec<usize> {
+        colamd(self.nrows, self.ncols, &self.colptr, &self.rowind)
+    }
+}
+
+pub fn colamd(nrows: usize, ncols: usize, colptr: &[usize], rowind: &[usize]) -> Vec<usize> {
+    let dense_row = 16.max((10.0 * (ncols as f32).sqrt()) as usize);
+
+    let mut rows: Vec<Vec<usize>> = vec![Vec::new(); nrows];
+    for j in 0..ncols {
+        for &i in &rowind[colptr[j]..colptr[j + 1]] {
+            rows[i].push(j);
+        }
+    }
+    for row in rows.iter_mut() {
+        row.dedup();
+        if row.len() > dense_row {
+            row.clear();
+        }
+    }
+
+    // element ids for rows start after the ncols variables
+    let mut adj_elems: Vec<Vec<usize>> = vec![Vec::new(); ncols];
+    let mut elems = Vec::with_capacity(nrows);
+    for row in rows {
+        if row.is_empty() {
+            continue;
+        }
+        let e = ncols + elems.len();
+        for &j in &row {
+            adj_elems[j].push(e);
+        }
+        elems.push(row);
+    }
+
+    minimum_degree(ncols, vec![Vec::new(); ncols], adj_elems, elems)
+}
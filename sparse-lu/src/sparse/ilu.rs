@@ -0,0 +1,263 @@
+use crate::sparse::lu::{LuError, LuFactors};
+use crate::sparse::scalar::Scalar;
+use crate::sparse::sparse_csc::{SparseCSC, transpose_compressed};
+use crate::sparse::sparse_csr::SparseCSR;
+use num_traits::Float;
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+
+/*
+    Incomplete LU preconditioners, A ~ L U without pivoting
+
+    Both work row by row in IKJ order (Saad, Iterative Methods, ch. 10). Row
+    i of A is reduced by the U rows above it,
+
+        for k < i with w[k] != 0:  w[k] /= U[k, k],  w[k+1..] -= w[k] U[k, k+1..]
+
+    and w[0..i] becomes row i of L, w[i..] row i of U.
+
+    ILU(0) keeps the pattern of A: updates landing outside it are thrown
+    away. ILUT(tol, p) lets fill in but drops every entry smaller than
+    tol * |A[i, :]|_2 (as soon as it appears in L, at the end in U) and then
+    keeps only the p largest entries of the L part and of the U part of each
+    row, the diagonal always kept.
+
+    The result is an LuFactors with p = q = identity, so it solves like a
+    full LU and can stand in for one wherever an approximate solve will do.
+    A zero or missing diagonal fails with ZeroPivot.
+*/
+
+impl<T: Scalar> SparseCSR<T> {
+    /// ILU(0): L U restricted to the pattern of A.
+    pub fn ilu0(&self) -> Result<LuFactors<T>, LuError> {
+        check_square(self.nrows, self.ncols)?;
+        let mut a = self.clone();
+        a.sort_indices();
+        ilu0_rows(a)
+    }
+
+    /// ILUT: threshold dropping relative to each row norm, at most max_fill
+    /// entries in each of the L and U parts of a row besides the diagonal.
+    pub fn ilut(&self, drop_tol: f32, max_fill: usize) -> Result<LuFactors<T>, LuError> {
+        check_square(self.nrows, self.ncols)?;
+        let mut a = self.clone();
+        a.sort_indices();
+        ilut_rows(&a, drop_tol, max_fill)
+    }
+}
+
+impl<T: Scalar> SparseCSC<T> {
+    /// ILU(0): L U restricted to the pattern of A.
+    pub fn ilu0(&self) -> Result<LuFactors<T>, LuError> {
+        check_square(self.nrows, self.ncols)?;
+        ilu0_rows(self.to_csr())
+    }
+
+    /// ILUT, see SparseCSR::ilut.
+    pub fn ilut(&self, drop_tol: f32, max_fill: usize) -> Result<LuFactors<T>, LuError> {
+        check_square(self.nrows, self.ncols)?;
+        ilut_rows(&self.to_csr(), drop_tol, max_fill)
+    }
+}
+
+fn check_square(nrows: usize, ncols: usize) -> Result<(), LuError> {
+    if nrows != ncols {
+        return Err(LuError::NotSquare(nrows, ncols));
+    }
+    Ok(())
+}
+
+// in place on the values of a, column indices sorted
+fn ilu0_rows<T: Scalar>(mut a: SparseCSR<T>) -> Result<LuFactors<T>, LuError> {
+    let n = a.nrows;
+    let mut diag = vec![0; n];
+    for (i, d) in diag.iter_mut().enumerate() {
+        let (start, end) = a.get_row_range(i);
+        *d = match a.colind[start..end].binary_search(&i) {
+            Ok(offset) => start + offset,
+            Err(_) => return Err(LuError::ZeroPivot(i)),
+        };
+    }
+
+    // pos[j] = slot of column j in the current row
+    let mut pos = vec![usize::MAX; n];
+    for i in 0..n {
+        let (start, end) = a.get_row_range(i);
+        for p in start..end {
+            pos[a.colind[p]] = p;
+        }
+        for p in start..diag[i] {
+            let k = a.colind[p];
+            let lik = a.values[p] / a.values[diag[k]];
+            a.values[p] = lik;
+            for t in diag[k] + 1..a.rowptr[k + 1] {
+                let slot = pos[a.colind[t]];
+                if slot != usize::MAX {
+                    let ukj = a.values[t];
+                    a.values[slot] -= lik * ukj;
+                }
+            }
+        }
+        for p in start..end {
+            pos[a.colind[p]] = usize::MAX;
+        }
+        if a.values[diag[i]].is_zero() {
+            return Err(LuError::ZeroPivot(i));
+        }
+    }
+
+    let mut l_rows = Vec::with_capacity(n);
+    let mut u_rows = Vec::with_capacity(n);
+    for i in 0..n {
+        let (start, end) = a.get_row_range(i);
+        let entries = || (start..end).map(|p| (a.colind[p], a.values[p]));
+        l_rows.push(entries().filter(|&(j, _)| j < i).collect());
+        u_rows.push(entries().filter(|&(j, _)| j >= i).collect());
+    }
+    Ok(factors_from_rows(l_rows, u_rows))
+}
+
+fn ilut_rows<T: Scalar>(
+    a: &SparseCSR<T>,
+    drop_tol: f32,
+    max_fill: usize,
+) -> Result<LuFactors<T>, LuError> {
+    let n = a.nrows;
+    // U rows with the diagonal first, columns increasing
+    let mut u_rows: Vec<Vec<(usize, T)>> = Vec::with_capacity(n);
+    let mut l_rows: Vec<Vec<(usize, T)>> = Vec::with_capacity(n);
+
+    let mut w = vec![T::zero(); n];
+    let mut in_w = vec![false; n];
+    let mut lower = BTreeSet::new();
+    let mut upper = Vec::new();
+    let mut l_row = Vec::new();
+
+    for i in 0..n {
+        let (start, end) = a.get_row_range(i);
+        let norm = a.values[start..end]
+            .iter()
+            .map(|v| v.modulus().powi(2))
+            .sum::<T::Real>()
+            .sqrt();
+        let tol = T::Real::from_f64(drop_tol as f64) * norm;
+
+        for p in start..end {
+            let j = a.colind[p];
+            w[j] = a.values[p];
+            in_w[j] = true;
+            if j < i {
+                lower.insert(j);
+            } else {
+                upper.push(j);
+            }
+        }
+        if !in_w[i] {
+            in_w[i] = true;
+            upper.push(i);
+        }
+
+        // lower columns in increasing order, fill below i joins the queue
+        while let Some(k) = lower.pop_first() {
+            let lik = w[k] / u_rows[k][0].1;
+            w[k] = T::zero();
+            in_w[k] = false;
+            if lik.modulus() < tol || lik.is_zero() {
+                continue;
+            }
+            l_row.push((k, lik));
+            for &(j, ukj) in &u_rows[k][1..] {
+                if !in_w[j] {
+                    in_w[j] = true;
+                    w[j] = T::zero();
+                    if j < i {
+                        lower.insert(j);
+                    } else {
+                        upper.push(j);
+                    }
+                }
+                w[j] -= lik * ukj;
+            }
+        }
+
+        let diag = w[i];
+        let mut u_row: Vec<(usize, T)> = upper
+            .iter()
+            .filter(|&&j| j != i)
+            .map(|&j| (j, w[j]))
+            .filter(|&(_, v)| v.modulus() >= tol && !v.is_zero())
+            .collect();
+        for &j in &upper {
+            w[j] = T::zero();
+            in_w[j] = false;
+        }
+        upper.clear();
+        if diag.is_zero() {
+            return Err(LuError::ZeroPivot(i));
+        }
+
+        keep_largest(&mut l_row, max_fill);
+        keep_largest(&mut u_row, max_fill);
+        u_row.insert(0, (i, diag));
+        l_rows.push(std::mem::take(&mut l_row));
+        u_rows.push(u_row);
+    }
+    Ok(factors_from_rows(l_rows, u_rows))
+}
+
+// the max_fill entries of largest magnitude, back in column order
+fn keep_largest<T: Scalar>(row: &mut Vec<(usize, T)>, max_fill: usize) {
+    if row.len() > max_fill {
+        row.select_nth_unstable_by(max_fill, |x, y| {
+            y.1.modulus()
+                .partial_cmp(&x.1.modulus())
+                .unwrap_or(Ordering::Equal)
+        });
+        row.truncate(max_fill);
+    }
+    row.sort_unstable_by_key(|entry| entry.0);
+}
+
+/*
+    L rows hold the strictly lower part, U rows the diagonal and above, both
+    sorted by column. Transposing gives CSC columns with L's unit diagonal
+    first and U's diagonal last, as the factorizations store them.
+*/
+fn factors_from_rows<T: Scalar>(
+    l_rows: Vec<Vec<(usize, T)>>,
+    u_rows: Vec<Vec<(usize, T)>>,
+) -> LuFactors<T> {
+    let n = l_rows.len();
+    let to_csc = |rows: Vec<Vec<(usize, T)>>, unit_diag: bool| {
+        let mut ptr = Vec::with_capacity(n + 1);
+        let mut ind = Vec::new();
+        let mut values = Vec::new();
+        ptr.push(0);
+        for (i, row) in rows.into_iter().enumerate() {
+            for (j, value) in row {
+                ind.push(j);
+                values.push(value);
+            }
+            if unit_diag {
+                ind.push(i);
+                values.push(T::one());
+            }
+            ptr.push(ind.len());
+        }
+        let (colptr, rowind, values) = transpose_compressed(n, &ptr, &ind, &values);
+        SparseCSC {
+            nrows: n,
+            ncols: n,
+            colptr,
+            rowind,
+            values,
+        }
+    };
+
+    LuFactors {
+        l: to_csc(l_rows, true),
+        u: to_csc(u_rows, false),
+        p: (0..n).collect(),
+        q: (0..n).collect(),
+    }
+}
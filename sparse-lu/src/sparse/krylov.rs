@@ -0,0 +1,357 @@
+use crate::sparse::lu::LuFactors;
+use crate::sparse::scalar::Real;
+use crate::sparse::sparse_csc::SparseCSC;
+use crate::sparse::sparse_csr::SparseCSR;
+use crate::sparse::sparse_matrix::SparseMatrixTrait;
+
+/*
+    Krylov iterative solvers for A x = b
+
+    cg        symmetric positive definite A (and M), preconditioned CG
+    bicgstab  general A, van der Vorst's BiCGSTAB
+    gmres     general A, GMRES(m) restarted every options.restart steps
+
+    A is anything that can apply y = A x (LinearOperator), the
+    preconditioner M ~ A anything that can apply z = M^-1 r (Preconditioner),
+    e.g. the LuFactors of an ilu0() / ilut() or of a full LU. CG applies M
+    symmetrically, BiCGSTAB and GMRES from the right, A M^-1 (M x) = b, so
+    the residual they track is the one of the original system.
+
+    All start from x = 0 and stop once |b - A x| <= tol |b|. The result
+    carries the residual norm after every iteration (entry 0 is |b|); a
+    GMRES iteration is one Arnoldi step, so its history is the estimate from
+    the Hessenberg least squares problem, exact at every restart.
+*/
+
+pub trait LinearOperator<T: Real> {
+    fn size(&self) -> usize;
+    /// y = A x, y has length size().
+    fn apply(&self, x: &[T], y: &mut [T]);
+}
+
+pub trait Preconditioner<T: Real> {
+    /// z = M^-1 r.
+    fn apply(&self, r: &[T], z: &mut [T]);
+}
+
+impl<T: Real> LinearOperator<T> for SparseCSR<T> {
+    fn size(&self) -> usize {
+        assert_eq!(self.nrows, self.ncols, "Operator must be square");
+        self.nrows
+    }
+
+    fn apply(&self, x: &[T], y: &mut [T]) {
+        self.gemv(T::one(), x, T::zero(), y);
+    }
+}
+
+impl<T: Real> LinearOperator<T> for SparseCSC<T> {
+    fn size(&self) -> usize {
+        assert_eq!(self.nrows, self.ncols, "Operator must be square");
+        self.ncols
+    }
+
+    fn apply(&self, x: &[T], y: &mut [T]) {
+        self.gemv(T::one(), x, T::zero(), y);
+    }
+}
+
+impl<T: Real> Preconditioner<T> for LuFactors<T> {
+    fn apply(&self, r: &[T], z: &mut [T]) {
+        z.copy_from_slice(&self.solve(r));
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IterativeOptions {
+    // relative residual |b - A x| / |b| to reach
+    pub tol: f32,
+    pub max_iter: usize,
+    // GMRES restart length m
+    pub restart: usize,
+}
+
+impl Default for IterativeOptions {
+    fn default() -> Self {
+        Self {
+            tol: 1e-5,
+            max_iter: 1000,
+            restart: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IterativeResult<T: Real> {
+    pub x: Vec<T>,
+    pub converged: bool,
+    pub iterations: usize,
+    // residual norm before the first and after every iteration
+    pub residuals: Vec<T>,
+}
+
+fn dot<T: Real>(x: &[T], y: &[T]) -> T {
+    x.iter().zip(y).map(|(&a, &b)| a * b).sum()
+}
+
+fn norm<T: Real>(x: &[T]) -> T {
+    dot(x, x).sqrt()
+}
+
+// y += alpha x
+fn axpy<T: Real>(alpha: T, x: &[T], y: &mut [T]) {
+    for (yi, &xi) in y.iter_mut().zip(x) {
+        *yi += alpha * xi;
+    }
+}
+
+// z = M^-1 r, or z = r without a preconditioner
+fn precondition<T: Real>(m: Option<&dyn Preconditioner<T>>, r: &[T], z: &mut [T]) {
+    match m {
+        Some(m) => m.apply(r, z),
+        None => z.copy_from_slice(r),
+    }
+}
+
+// r = b - A x
+fn residual<T: Real>(a: &impl LinearOperator<T>, x: &[T], b: &[T], r: &mut [T]) {
+    a.apply(x, r);
+    for (ri, &bi) in r.iter_mut().zip(b) {
+        *ri = bi - *ri;
+    }
+}
+
+fn check_rhs<T: Real>(a: &impl LinearOperator<T>, b: &[T]) -> usize {
+    let n = a.size();
+    assert_eq!(
+        b.len(),
+        n,
+        "Right-hand side length must match operator size"
+    );
+    n
+}
+
+/// Preconditioned conjugate gradients, A and M symmetric positive definite.
+pub fn cg<T: Real>(
+    a: &impl LinearOperator<T>,
+    b: &[T],
+    preconditioner: Option<&dyn Preconditioner<T>>,
+    options: &IterativeOptions,
+) -> IterativeResult<T> {
+    let n = check_rhs(a, b);
+    let mut x = vec![T::zero(); n];
+    let mut r = b.to_vec();
+    let target = T::from_f64(options.tol as f64) * norm(b);
+    let mut residuals = vec![norm(&r)];
+
+    let mut z = vec![T::zero(); n];
+    precondition(preconditioner, &r, &mut z);
+    let mut p = z.clone();
+    let mut ap = vec![T::zero(); n];
+    let mut rz = dot(&r, &z);
+
+    let mut iterations = 0;
+    while residuals[iterations] > target && iterations < options.max_iter {
+        a.apply(&p, &mut ap);
+        let pap = dot(&p, &ap);
+        if pap.is_zero() {
+            break;
+        }
+        let alpha = rz / pap;
+        axpy(alpha, &p, &mut x);
+        axpy(-alpha, &ap, &mut r);
+        iterations += 1;
+        residuals.push(norm(&r));
+
+        precondition(preconditioner, &r, &mut z);
+        let rz_next = dot(&r, &z);
+        let beta = rz_next / rz;
+        rz = rz_next;
+        for (pi, &zi) in p.iter_mut().zip(&z) {
+            *pi = zi + beta * *pi;
+        }
+    }
+
+    IterativeResult {
+        x,
+        converged: residuals[iterations] <= target,
+        iterations,
+        residuals,
+    }
+}
+
+/// BiCGSTAB with right preconditioning, stops early on breakdown.
+pub fn bicgstab<T: Real>(
+    a: &impl LinearOperator<T>,
+    b: &[T],
+    preconditioner: Option<&dyn Preconditioner<T>>,
+    options: &IterativeOptions,
+) -> IterativeResult<T> {
+    let n = check_rhs(a, b);
+    let mut x = vec![T::zero(); n];
+    let mut r = b.to_vec();
+    let r_hat = r.clone();
+    let target = T::from_f64(options.tol as f64) * norm(b);
+    let mut residuals = vec![norm(&r)];
+
+    let (mut rho, mut alpha, mut omega) = (T::one(), T::one(), T::one());
+    let mut v = vec![T::zero(); n];
+    let mut p = vec![T::zero(); n];
+    let mut p_hat = vec![T::zero(); n];
+    let mut s_hat = vec![T::zero(); n];
+    let mut t = vec![T::zero(); n];
+
+    let mut iterations = 0;
+    while residuals[iterations] > target && iterations < options.max_iter {
+        let rho_next = dot(&r_hat, &r);
+        if rho_next.is_zero() || omega.is_zero() {
+            break;
+        }
+        let beta = (rho_next / rho) * (alpha / omega);
+        rho = rho_next;
+        for ((pi, &ri), &vi) in p.iter_mut().zip(&r).zip(&v) {
+            *pi = ri + beta * (*pi - omega * vi);
+        }
+
+        precondition(preconditioner, &p, &mut p_hat);
+        a.apply(&p_hat, &mut v);
+        let r_hat_v = dot(&r_hat, &v);
+        if r_hat_v.is_zero() {
+            break;
+        }
+        alpha = rho / r_hat_v;
+        axpy(alpha, &p_hat, &mut x);
+        // s, the residual halfway through, reuses r
+        axpy(-alpha, &v, &mut r);
+        iterations += 1;
+        let s_norm = norm(&r);
+        if s_norm <= target {
+            residuals.push(s_norm);
+            break;
+        }
+
+        precondition(preconditioner, &r, &mut s_hat);
+        a.apply(&s_hat, &mut t);
+        let tt = dot(&t, &t);
+        omega = if tt.is_zero() {
+            T::zero()
+        } else {
+            dot(&t, &r) / tt
+        };
+        axpy(omega, &s_hat, &mut x);
+        axpy(-omega, &t, &mut r);
+        residuals.push(norm(&r));
+    }
+
+    IterativeResult {
+        x,
+        converged: residuals[iterations] <= target,
+        iterations,
+        residuals,
+    }
+}
+
+/// GMRES(m) with right preconditioning, m = options.restart.
+pub fn gmres<T: Real>(
+    a: &impl LinearOperator<T>,
+    b: &[T],
+    preconditioner: Option<&dyn Preconditioner<T>>,
+    options: &IterativeOptions,
+) -> IterativeResult<T> {
+    let n = check_rhs(a, b);
+    let m = options.restart.max(1);
+    let mut x = vec![T::zero(); n];
+    let target = T::from_f64(options.tol as f64) * norm(b);
+    let mut residuals = vec![norm(b)];
+
+    let mut r = b.to_vec();
+    let mut w = vec![T::zero(); n];
+    let mut z = vec![T::zero(); n];
+    let mut iterations = 0;
+    while iterations < options.max_iter {
+        residual(a, &x, b, &mut r);
+        let beta = norm(&r);
+        *residuals.last_mut().unwrap() = beta;
+        if beta <= target {
+            break;
+        }
+
+        // Arnoldi basis v[0..=j], Hessenberg columns h[j] already rotated
+        let mut v: Vec<Vec<T>> = vec![r.iter().map(|&ri| ri / beta).collect()];
+        let mut h: Vec<Vec<T>> = Vec::with_capacity(m);
+        let mut rotations: Vec<(T, T)> = Vec::with_capacity(m);
+        let mut g = vec![beta];
+
+        while h.len() < m && iterations < options.max_iter {
+            let j = h.len();
+            precondition(preconditioner, &v[j], &mut z);
+            a.apply(&z, &mut w);
+
+            // modified Gram-Schmidt
+            let mut column = vec![T::zero(); j + 2];
+            for (i, vi) in v.iter().enumerate() {
+                column[i] = dot(&w, vi);
+                axpy(-column[i], vi, &mut w);
+            }
+            column[j + 1] = norm(&w);
+
+            for (i, &(c, s)) in rotations.iter().enumerate() {
+                let (hi, hk) = (column[i], column[i + 1]);
+                column[i] = c * hi + s * hk;
+                column[i + 1] = c * hk - s * hi;
+            }
+            let rho = column[j].hypot(column[j + 1]);
+            let (c, s) = if rho.is_zero() {
+                (T::one(), T::zero())
+            } else {
+                (column[j] / rho, column[j + 1] / rho)
+            };
+            let h_next = column[j + 1];
+            column[j] = rho;
+            column[j + 1] = T::zero();
+            rotations.push((c, s));
+            g.push(-s * g[j]);
+            g[j] *= c;
+            h.push(column);
+
+            iterations += 1;
+            residuals.push(g[j + 1].abs());
+            if g[j + 1].abs() <= target || h_next.is_zero() {
+                break;
+            }
+            v.push(w.iter().map(|&wi| wi / h_next).collect());
+        }
+
+        // y from the triangular H y = g, then x += M^-1 V y
+        let k = h.len();
+        let mut y = g[..k].to_vec();
+        for i in (0..k).rev() {
+            let yi = if h[i][i].is_zero() {
+                T::zero()
+            } else {
+                y[i] / h[i][i]
+            };
+            y[i] = yi;
+            for (yl, &hl) in y[..i].iter_mut().zip(&h[i]) {
+                *yl -= hl * yi;
+            }
+        }
+        w.fill(T::zero());
+        for (vi, yi) in v.iter().zip(&y) {
+            axpy(*yi, vi, &mut w);
+        }
+        precondition(preconditioner, &w, &mut z);
+        axpy(T::one(), &z, &mut x);
+    }
+
+    // the last entry is the true residual of the returned x
+    residual(a, &x, b, &mut r);
+    *residuals.last_mut().unwrap() = norm(&r);
+
+    IterativeResult {
+        x,
+        converged: residuals[iterations] <= target,
+        iterations,
+        residuals,
+    }
+}
@@ -0,0 +1,365 @@
+use crate::sparse::permutation::{inverse_permutation, is_permutation};
+use crate::sparse::scalar::Scalar;
+use crate::sparse::sparse_csc::SparseCSC;
+use crate::sparse::triangular::{Diag, ReachWork, reach};
+use num_traits::Zero;
+use std::fmt;
+
+/*
+    Left-looking sparse LU (Gilbert-Peierls)
+
+    P * A * Q = L * U, L unit lower triangular, U upper triangular, both
+    stored as CSC, Q a fill-reducing column ordering picked up front and P a
+    row permutation chosen during factorization. See LuFactors for solving
+    with the result and refactor.rs for repeated factorizations of one pattern.
+
+    Column k of the factors comes from a sparse triangular solve
+        L[:, 0..k] x = A[:, q[k]]
+    entries of x in rows already pivotal form U[:, k], the pivot is picked
+    from the remaining rows and those rows divided by it form L[:, k].
+
+    The nonzero pattern of x is the set of nodes reachable from the pattern
+    of A[:, k] in the graph of L (edge j -> i for every L[i, j] != 0). A DFS
+    gives that set in topological order, so the solve only touches entries
+    that end up nonzero and the total work is proportional to the flop count.
+*/
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LuError {
+    NotSquare(usize, usize),
+    ZeroPivot(usize),
+    // matrix or factors handed to a Symbolic do not match what it analyzed
+    PatternMismatch,
+    // ColumnOrdering::Custom that is not a permutation of 0..n
+    InvalidOrdering(usize),
+}
+
+impl fmt::Display for LuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LuError::NotSquare(nrows, ncols) => {
+                write!(f, "LU requires a square matrix, got {} x {}", nrows, ncols)
+            }
+            LuError::ZeroPivot(k) => write!(f, "zero pivot in column {}", k),
+            LuError::PatternMismatch => {
+                write!(f, "matrix pattern differs from the analyzed pattern")
+            }
+            LuError::InvalidOrdering(n) => {
+                write!(f, "column ordering is not a permutation of 0..{}", n)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LuError {}
+
+/*
+    Fill-reducing column ordering Q, applied before factorization.
+    Amd orders A + A^T and suits nearly symmetric patterns, Colamd orders
+    A^T A and is the usual choice for unsymmetric matrices.
+    Custom takes a permutation with perm[k] = column of A to factor at step k.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnOrdering {
+    Natural,
+    Amd,
+    Colamd,
+    Custom(Vec<usize>),
+}
+
+/*
+    Numeric factorization strategy, all give P * A * Q = L * U with the same
+    pivoting rule.
+    LeftLooking  column at a time Gilbert-Peierls, below
+    Supernodal   panels of columns with dense block updates, supernodal.rs
+    Multifrontal dense frontal matrices along the column etree, multifrontal.rs
+
+    Supernodal and Multifrontal follow the column etree of A^T A, which fits
+    Colamd; after a symmetric ordering like Amd its fronts get much larger
+    than the factors.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LuMethod {
+    LeftLooking,
+    Supernodal,
+    Multifrontal,
+}
+
+/*
+    Threshold partial pivoting, as in SuperLU's diag_pivot_thresh.
+
+    At step k the pivot is chosen among rows not yet pivotal. The diagonal
+    entry is kept if |x[k]| >= diag_pivot_thresh * max |x[i]|, otherwise the
+    largest entry wins. 1.0 is plain partial pivoting, smaller values favour
+    the diagonal (and usually less fill), 0.0 takes any nonzero diagonal.
+    With a column ordering "diagonal" means A[q[k], q[k]], so symmetric
+    orderings like AMD keep their meaning when the diagonal is accepted.
+*/
+#[derive(Debug, Clone)]
+pub struct LuOptions {
+    pub diag_pivot_thresh: f32,
+    pub ordering: ColumnOrdering,
+    pub method: LuMethod,
+}
+
+impl Default for LuOptions {
+    fn default() -> Self {
+        Self {
+            diag_pivot_thresh: 1.0,
+            ordering: ColumnOrdering::Natural,
+            method: LuMethod::LeftLooking,
+        }
+    }
+}
+
+/*
+    Result of a factorization, P * A * Q = L * U, the same role as scipy's
+    SuperLU object.
+
+    p[k] = row of A that ended up in row k of P * A
+    q[k] = column of A that ended up in column k of A * Q
+*/
+#[derive(Debug, Clone)]
+pub struct LuFactors<T: Scalar> {
+    pub l: SparseCSC<T>,
+    pub u: SparseCSC<T>,
+    pub p: Vec<usize>,
+    pub q: Vec<usize>,
+}
+
+impl<T: Scalar> LuFactors<T> {
+    pub fn size(&self) -> usize {
+        self.p.len()
+    }
+
+    /// Solve A x = b.
+    pub fn solve(&self, b: &[T]) -> Vec<T> {
+        let n = self.size();
+        assert_eq!(b.len(), n, "Right-hand side length must match matrix size");
+
+        // L U (Q^T x) = P b
+        let mut y: Vec<T> = self.p.iter().map(|&i| b[i]).collect();
+        self.l.lower_solve(&mut y, Diag::Unit);
+        self.u.upper_solve(&mut y, Diag::NonUnit);
+
+        let mut x = vec![T::zero(); n];
+        for (k, &j) in self.q.iter().enumerate() {
+            x[j] = y[k];
+        }
+        x
+    }
+
+    /// Solve A^T x = b.
+    pub fn solve_transpose(&self, b: &[T]) -> Vec<T> {
+        let n = self.size();
+        assert_eq!(b.len(), n, "Right-hand side length must match matrix size");
+
+        // U^T L^T (P x) = Q^T b
+        let mut y: Vec<T> = self.q.iter().map(|&j| b[j]).collect();
+        self.u.upper_transpose_solve(&mut y, Diag::NonUnit);
+        self.l.lower_transpose_solve(&mut y, Diag::Unit);
+
+        let mut x = vec![T::zero(); n];
+        for (k, &i) in self.p.iter().enumerate() {
+            x[i] = y[k];
+        }
+        x
+    }
+
+    /// Solve A X = B for a dense block B with one right-hand side per column.
+    pub fn solve_many(&self, b: &[Vec<T>]) -> Vec<Vec<T>> {
+        let n = self.size();
+        assert_eq!(b.len(), n, "Right-hand side rows must match matrix size");
+        let nrhs = if n > 0 { b[0].len() } else { 0 };
+
+        let mut x = vec![vec![T::zero(); nrhs]; n];
+        let mut column = vec![T::zero(); n];
+        for j in 0..nrhs {
+            for (i, row) in b.iter().enumerate() {
+                column[i] = row[j];
+            }
+            for (i, value) in self.solve(&column).into_iter().enumerate() {
+                x[i][j] = value;
+            }
+        }
+        x
+    }
+}
+
+impl<T: Scalar> SparseCSC<T> {
+    /// Factor P * A * Q = L * U with default options.
+    pub fn lu(&self) -> Result<LuFactors<T>, LuError> {
+        self.lu_with_options(&LuOptions::default())
+    }
+
+    pub fn lu_with_options(&self, options: &LuOptions) -> Result<LuFactors<T>, LuError> {
+        if self.nrows != self.ncols {
+            return Err(LuError::NotSquare(self.nrows, self.ncols));
+        }
+        if options.method != LuMethod::LeftLooking {
+            // the other methods work from the symbolic analysis
+            return self.analyze(options)?.factor(self);
+        }
+        let q = self.column_ordering(&options.ordering)?;
+        let nnz = self.rowind.len();
+        self.lu_ordered(q, options.diag_pivot_thresh, (nnz, nnz))
+    }
+
+    pub(crate) fn column_ordering(&self, ordering: &ColumnOrdering) -> Result<Vec<usize>, LuError> {
+        let n = self.ncols;
+        match ordering {
+            ColumnOrdering::Natural => Ok((0..n).collect()),
+            ColumnOrdering::Amd => Ok(self.amd()),
+            ColumnOrdering::Colamd => Ok(self.colamd()),
+            ColumnOrdering::Custom(perm) if is_permutation(perm, n) => Ok(perm.clone()),
+            ColumnOrdering::Custom(_) => Err(LuError::InvalidOrdering(n)),
+        }
+    }
+
+    /// Factor with a fixed column ordering, capacity = expected (nnz(L), nnz(U)).
+    pub(crate) fn lu_ordered(
+        &self,
+        q: Vec<usize>,
+        diag_pivot_thresh: f32,
+        capacity: (usize, usize),
+    ) -> Result<LuFactors<T>, LuError> {
+        let n = self.ncols;
+        let thresh = T::Real::from_f64(diag_pivot_thresh as f64);
+
+        let mut lp = vec![0; n + 1];
+        let mut li: Vec<usize> = Vec::with_capacity(capacity.0);
+        let mut lx: Vec<T> = Vec::with_capacity(capacity.0);
+        let mut up = vec![0; n + 1];
+        let mut ui: Vec<usize> = Vec::with_capacity(capacity.1);
+        let mut ux: Vec<T> = Vec::with_capacity(capacity.1);
+
+        // pinv[i] = step at which row i of A was chosen as pivot
+        let mut pinv: Vec<Option<usize>> = vec![None; n];
+
+        // dense work column, only entries in the current pattern are ever nonzero
+        let mut x = vec![T::zero(); n];
+        // xi[top..n] holds the pattern of x in topological order
+        let mut xi = vec![0; n];
+        let mut work = ReachWork::new(n);
+
+        for k in 0..n {
+            lp[k] = li.len();
+            up[k] = ui.len();
+
+            let col = q[k];
+            let (a_start, a_end) = self.get_column_range(col);
+            // the pivot row j of step s has children li[lp[s] + 1..lp[s + 1]],
+            // rows that are not pivotal yet have none
+            let top = reach(
+                &li,
+                |j| match pinv[j] {
+                    Some(s) => (lp[s] + 1, lp[s + 1]),
+                    None => (0, 0),
+                },
+                &self.rowind[a_start..a_end],
+                &mut xi,
+                &mut work,
+            );
+
+            for p in a_start..a_end {
+                x[self.rowind[p]] = self.values[p];
+            }
+
+            // x = L \ A[:, col], eliminating in topological order
+            for &j in &xi[top..n] {
+                let Some(s) = pinv[j] else {
+                    continue;
+                };
+                let xj = x[j];
+                // skip the unit diagonal, stored first in each L column
+                for p in lp[s] + 1..lp[s + 1] {
+                    x[li[p]] -= lx[p] * xj;
+                }
+            }
+
+            // pick the pivot among rows that are not pivotal yet
+            let mut ipiv = None;
+            let mut max_abs = T::Real::zero();
+            for &i in &xi[top..n] {
+                if pinv[i].is_none() && x[i].modulus() > max_abs {
+                    max_abs = x[i].modulus();
+                    ipiv = Some(i);
+                }
+            }
+            let Some(mut ipiv) = ipiv else {
+                return Err(LuError::ZeroPivot(k));
+            };
+            if pinv[col].is_none() && !x[col].is_zero() && x[col].modulus() >= thresh * max_abs {
+                ipiv = col;
+            }
+
+            let pivot = x[ipiv];
+            pinv[ipiv] = Some(k);
+
+            li.push(ipiv);
+            lx.push(T::one());
+            for &i in &xi[top..n] {
+                match pinv[i] {
+                    Some(s) if s < k => {
+                        ui.push(s);
+                        ux.push(x[i]);
+                    }
+                    None => {
+                        li.push(i);
+                        lx.push(x[i] / pivot);
+                    }
+                    _ => {}
+                }
+                x[i] = T::zero();
+            }
+            ui.push(k);
+            ux.push(pivot);
+        }
+        lp[n] = li.len();
+        up[n] = ui.len();
+
+        Ok(assemble_factors(n, (lp, li, lx), (up, ui, ux), pinv, q))
+    }
+}
+
+/*
+    Wrap the arrays built by a factorization into LuFactors. L rows are still
+    rows of A, pinv maps them to pivot steps; U rows are already steps.
+*/
+pub(crate) fn assemble_factors<T: Scalar>(
+    n: usize,
+    (lp, mut li, lx): (Vec<usize>, Vec<usize>, Vec<T>),
+    (up, ui, ux): (Vec<usize>, Vec<usize>, Vec<T>),
+    pinv: Vec<Option<usize>>,
+    q: Vec<usize>,
+) -> LuFactors<T> {
+    // every row is pivotal now, renumber L rows into pivot order
+    let pinv: Vec<usize> = pinv.into_iter().map(|k| k.unwrap()).collect();
+    for i in li.iter_mut() {
+        *i = pinv[*i];
+    }
+
+    let mut l = SparseCSC {
+        nrows: n,
+        ncols: n,
+        colptr: lp,
+        rowind: li,
+        values: lx,
+    };
+    let mut u = SparseCSC {
+        nrows: n,
+        ncols: n,
+        colptr: up,
+        rowind: ui,
+        values: ux,
+    };
+    l.sort_indices();
+    u.sort_indices();
+
+    LuFactors {
+        l,
+        u,
+        p: inverse_permutation(&pinv),
+        q,
+    }
+}
@@ -1,4 +1,21 @@
+pub mod amd;
+pub mod cholesky;
+pub mod colamd;
+pub mod ilu;
+pub mod krylov;
+pub mod lu;
+pub mod multifrontal;
+pub mod permutation;
+pub mod qr;
+pub mod rcm;
+pub mod refactor;
+pub mod scalar;
 pub mod sparse_coo;
 pub mod sparse_csc;
 pub mod sparse_csr;
 pub mod sparse_matrix;
+pub mod spgemm;
+pub mod spmm;
+pub mod supernodal;
+pub mod symbolic;
+pub mod triangular;
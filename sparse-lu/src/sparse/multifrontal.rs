@@ -0,0 +1,239 @@
+use crate::sparse::lu::{LuError, LuFactors, assemble_factors};
+use crate::sparse::refactor::Symbolic;
+use crate::sparse::scalar::Scalar;
+use crate::sparse::sparse_csc::SparseCSC;
+use crate::sparse::symbolic::postorder;
+use num_traits::Zero;
+
+/*
+    Multifrontal LU along the column elimination tree
+
+    Every row of A Q lies on one path of the column etree, from its first
+    column up (its columns form a clique in A^T A). So a row can be handed
+    from a node to its parent until it is picked as a pivot, and every row
+    with a nonzero in column k has reached node k by the time k is
+    eliminated, which makes partial pivoting inside the node exact.
+
+    Nodes are the fundamental supernodes of the column etree, visited in
+    postorder. For a supernode with pivot columns f..e the frontal matrix is
+    a dense block with
+        rows: rows of A whose first column is in f..e, plus the rows left
+              over in the children's fronts
+        cols: f..e, then every other column those rows touch
+    assembled by extend-add: the children's contribution blocks are added in
+    by global row/column index, together with the original entries.
+
+    The pivot columns are eliminated densely with threshold partial pivoting
+    among the front's rows. The pivot rows are rows of U, the multipliers
+    columns of L, and the Schur complement (remaining rows x remaining
+    columns) is the contribution block passed to the parent.
+
+    The factors keep the full frontal pattern, explicit zeros included, so
+    Symbolic::refactor can reuse them.
+*/
+
+// rows x cols dense block, row-major, indexed by global row and column
+struct Contribution<T> {
+    rows: Vec<usize>,
+    cols: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl Symbolic {
+    /// Multifrontal numeric LU, same result as factor up to rounding.
+    pub fn factor_multifrontal<T: Scalar>(
+        &self,
+        a: &SparseCSC<T>,
+    ) -> Result<LuFactors<T>, LuError> {
+        self.check_pattern(a)?;
+        let n = self.size();
+        let q = &self.q;
+        let thresh = T::Real::from_f64(self.diag_pivot_thresh as f64);
+
+        // rows of A Q, columns numbered by step and in increasing order
+        let mut a_rows: Vec<Vec<(usize, T)>> = vec![Vec::new(); n];
+        for (k, &col) in q.iter().enumerate() {
+            let (start, end) = a.get_column_range(col);
+            for p in start..end {
+                a_rows[a.rowind[p]].push((k, a.values[p]));
+            }
+        }
+
+        let sup = self.counts.supernodes();
+        let nsup = sup.len() - 1;
+        let mut sup_of = vec![0; n];
+        for s in 0..nsup {
+            sup_of[sup[s]..sup[s + 1]].fill(s);
+        }
+        let sup_parent: Vec<Option<usize>> = (0..nsup)
+            .map(|s| self.counts.parent[sup[s + 1] - 1].map(|p| sup_of[p]))
+            .collect();
+
+        // each row of A starts in the front of its first column
+        let mut assigned: Vec<Vec<usize>> = vec![Vec::new(); nsup];
+        for (r, row) in a_rows.iter().enumerate() {
+            if let Some(&(k, _)) = row.first() {
+                assigned[sup_of[k]].push(r);
+            }
+        }
+
+        let mut contributions: Vec<Vec<Contribution<T>>> = (0..nsup).map(|_| Vec::new()).collect();
+        let mut pivot_rows = vec![0; n];
+        let mut l_cols: Vec<Vec<(usize, T)>> = vec![Vec::new(); n];
+        let mut u_cols: Vec<Vec<(usize, T)>> = vec![Vec::new(); n];
+        let mut pinv: Vec<Option<usize>> = vec![None; n];
+        // col_pos[j] = position of column j in the current front
+        let mut col_pos = vec![usize::MAX; n];
+        let mut pivot_row = Vec::new();
+
+        for s in postorder(&sup_parent) {
+            let (f, e) = (sup[s], sup[s + 1]);
+            let w = e - f;
+            let children = std::mem::take(&mut contributions[s]);
+
+            let mut cols: Vec<usize> = (f..e).collect();
+            for (t, &j) in cols.iter().enumerate() {
+                col_pos[j] = t;
+            }
+            let touched = assigned[s]
+                .iter()
+                .flat_map(|&r| a_rows[r].iter().map(|&(j, _)| j))
+                .chain(children.iter().flat_map(|child| child.cols.iter().copied()));
+            for j in touched {
+                if col_pos[j] == usize::MAX {
+                    col_pos[j] = cols.len();
+                    cols.push(j);
+                }
+            }
+
+            let mut rows: Vec<usize> = children
+                .iter()
+                .flat_map(|child| child.rows.iter().copied())
+                .chain(assigned[s].iter().copied())
+                .collect();
+            let (nr, nc) = (rows.len(), cols.len());
+
+            // extend-add the children, then the original entries
+            let mut front = vec![T::zero(); nr * nc];
+            let mut r0 = 0;
+            for child in &children {
+                let cw = child.cols.len();
+                for ri in 0..child.rows.len() {
+                    let dest = &mut front[(r0 + ri) * nc..(r0 + ri + 1) * nc];
+                    for (ci, &j) in child.cols.iter().enumerate() {
+                        dest[col_pos[j]] += child.values[ri * cw + ci];
+                    }
+                }
+                r0 += child.rows.len();
+            }
+            for (ri, &r) in assigned[s].iter().enumerate() {
+                for &(j, value) in &a_rows[r] {
+                    front[(r0 + ri) * nc + col_pos[j]] += value;
+                }
+            }
+
+            for c in 0..w {
+                let k = f + c;
+
+                let mut best = None;
+                let mut max_abs = T::Real::zero();
+                for ri in c..nr {
+                    if front[ri * nc + c].modulus() > max_abs {
+                        max_abs = front[ri * nc + c].modulus();
+                        best = Some(ri);
+                    }
+                }
+                let Some(mut best) = best else {
+                    return Err(LuError::ZeroPivot(k));
+                };
+                if let Some(ri) = (c..nr).find(|&ri| rows[ri] == q[k]) {
+                    let d = front[ri * nc + c];
+                    if !d.is_zero() && d.modulus() >= thresh * max_abs {
+                        best = ri;
+                    }
+                }
+
+                // move the pivot row up to position c
+                if best != c {
+                    rows.swap(best, c);
+                    let (upper, lower) = front.split_at_mut(best * nc);
+                    upper[c * nc..(c + 1) * nc].swap_with_slice(&mut lower[..nc]);
+                }
+                pinv[rows[c]] = Some(k);
+                pivot_rows[k] = rows[c];
+                let pivot = front[c * nc + c];
+
+                for t in c..nc {
+                    u_cols[cols[t]].push((k, front[c * nc + t]));
+                }
+
+                pivot_row.clear();
+                pivot_row.extend_from_slice(&front[c * nc + c + 1..(c + 1) * nc]);
+                for ri in c + 1..nr {
+                    let lv = front[ri * nc + c] / pivot;
+                    front[ri * nc + c] = lv;
+                    l_cols[k].push((rows[ri], lv));
+                    if !lv.is_zero() {
+                        let dest = &mut front[ri * nc + c + 1..(ri + 1) * nc];
+                        for (d, &u) in dest.iter_mut().zip(&pivot_row) {
+                            *d -= lv * u;
+                        }
+                    }
+                }
+            }
+
+            for &j in &cols {
+                col_pos[j] = usize::MAX;
+            }
+
+            // the Schur complement of the pivot block goes up the tree
+            if let Some(parent) = sup_parent[s]
+                && nr > w
+            {
+                let mut values = Vec::with_capacity((nr - w) * (nc - w));
+                for ri in w..nr {
+                    values.extend_from_slice(&front[ri * nc + w..(ri + 1) * nc]);
+                }
+                contributions[parent].push(Contribution {
+                    rows: rows.split_off(w),
+                    cols: cols.split_off(w),
+                    values,
+                });
+            }
+        }
+
+        let mut lp = Vec::with_capacity(n + 1);
+        let mut li = Vec::new();
+        let mut lx = Vec::new();
+        for (k, col) in l_cols.into_iter().enumerate() {
+            lp.push(li.len());
+            li.push(pivot_rows[k]);
+            lx.push(T::one());
+            for (i, value) in col {
+                li.push(i);
+                lx.push(value);
+            }
+        }
+        lp.push(li.len());
+
+        let mut up = Vec::with_capacity(n + 1);
+        let mut ui = Vec::new();
+        let mut ux = Vec::new();
+        for col in u_cols {
+            up.push(ui.len());
+            for (k, value) in col {
+                ui.push(k);
+                ux.push(value);
+            }
+        }
+        up.push(ui.len());
+
+        Ok(assemble_factors(
+            n,
+            (lp, li, lx),
+            (up, ui, ux),
+            pinv,
+            q.clone(),
+        ))
+    }
+}
@@ -0,0 +1,134 @@
+use crate::sparse::scalar::Scalar;
+use crate::sparse::sparse_coo::SparseCOO;
+use crate::sparse::sparse_csc::SparseCSC;
+use crate::sparse::sparse_csr::SparseCSR;
+
+/*
+    Row/column permutations
+
+    Permutations are stored as perm[k] = original index that moves to
+    position k, the same convention as LuFactors::p/q and the orderings.
+    For B = permute(row_perm, col_perm):
+
+        B[k][l] = A[row_perm[k]][col_perm[l]]
+
+    i.e. B = P A Q with P, Q permutation matrices. The inverse permutation
+    maps the other way, inverse[i] = position of original index i.
+*/
+
+pub fn inverse_permutation(perm: &[usize]) -> Vec<usize> {
+    let mut inverse = vec![0; perm.len()];
+    for (k, &i) in perm.iter().enumerate() {
+        inverse[i] = k;
+    }
+    inverse
+}
+
+pub fn is_permutation(perm: &[usize], n: usize) -> bool {
+    if perm.len() != n {
+        return false;
+    }
+    let mut seen = vec![false; n];
+    for &i in perm {
+        if i >= n || seen[i] {
+            return false;
+        }
+        seen[i] = true;
+    }
+    true
+}
+
+fn check_permutation(perm: &[usize], n: usize) {
+    if !is_permutation(perm, n) {
+        panic!("Not a permutation of 0..{}: {:?}", n, perm);
+    }
+}
+
+impl<T: Scalar> SparseCOO<T> {
+    pub fn permute(&self, row_perm: &[usize], col_perm: &[usize]) -> Self {
+        check_permutation(row_perm, self.nrows);
+        check_permutation(col_perm, self.ncols);
+        let row_inv = inverse_permutation(row_perm);
+        let col_inv = inverse_permutation(col_perm);
+
+        Self {
+            nrows: self.nrows,
+            ncols: self.ncols,
+            rowind: self.rowind.iter().map(|&i| row_inv[i]).collect(),
+            colind: self.colind.iter().map(|&j| col_inv[j]).collect(),
+            values: self.values.clone(),
+        }
+    }
+
+    pub fn permute_symmetric(&self, perm: &[usize]) -> Self {
+        self.permute(perm, perm)
+    }
+}
+
+impl<T: Scalar> SparseCSC<T> {
+    pub fn permute(&self, row_perm: &[usize], col_perm: &[usize]) -> Self {
+        check_permutation(row_perm, self.nrows);
+        check_permutation(col_perm, self.ncols);
+        let row_inv = inverse_permutation(row_perm);
+
+        // gather columns in their new order, renaming rows as we go
+        let mut colptr = Vec::with_capacity(self.ncols + 1);
+        let mut rowind = Vec::with_capacity(self.rowind.len());
+        let mut values = Vec::with_capacity(self.rowind.len());
+        colptr.push(0);
+        for &j in col_perm {
+            let (start, end) = self.get_column_range(j);
+            rowind.extend(self.rowind[start..end].iter().map(|&i| row_inv[i]));
+            values.extend_from_slice(&self.values[start..end]);
+            colptr.push(rowind.len());
+        }
+
+        let mut result = Self {
+            nrows: self.nrows,
+            ncols: self.ncols,
+            colptr,
+            rowind,
+            values,
+        };
+        result.sort_indices();
+        result
+    }
+
+    pub fn permute_symmetric(&self, perm: &[usize]) -> Self {
+        self.permute(perm, perm)
+    }
+}
+
+impl<T: Scalar> SparseCSR<T> {
+    pub fn permute(&self, row_perm: &[usize], col_perm: &[usize]) -> Self {
+        check_permutation(row_perm, self.nrows);
+        check_permutation(col_perm, self.ncols);
+        let col_inv = inverse_permutation(col_perm);
+
+        // gather rows in their new order, renaming columns as we go
+        let mut rowptr = Vec::with_capacity(self.nrows + 1);
+        let mut colind = Vec::with_capacity(self.colind.len());
+        let mut values = Vec::with_capacity(self.colind.len());
+        rowptr.push(0);
+        for &i in row_perm {
+            let (start, end) = self.get_row_range(i);
+            colind.extend(self.colind[start..end].iter().map(|&j| col_inv[j]));
+            values.extend_from_slice(&self.values[start..end]);
+            rowptr.push(colind.len());
+        }
+
+        let mut result = Self {
+            nrows: self.nrows,
+            ncols: self.ncols,
+            rowptr,
+            colind,
+            values,
+        };
+        result.sort_indices();
+        result
+    }
+
+    pub fn permute_symmetric(&self, perm: &[usize]) -> Self {
+        self.permute(perm, perm)
+    }
+}
@@ -0,0 +1,265 @@
+use crate::sparse::lu::{ColumnOrdering, LuError};
+use crate::sparse::scalar::Real;
+use crate::sparse::sparse_csc::SparseCSC;
+use crate::sparse::triangular::Diag;
+use std::fmt;
+
+/*
+    Sparse QR by Givens rotations, row at a time (George & Heath)
+
+    A Q = Q_r R for an m x n matrix with m >= n, R upper triangular n x n,
+    Q a fill-reducing column ordering (Colamd or Custom; Amd orders A + A^T
+    and is refused unless m == n). R has the pattern of the Cholesky factor
+    of Q^T A^T A Q, so a good ordering for A^T A is a good ordering here.
+
+    R is built as sparse rows. Each row of A Q is merged into it in order of
+    its leftmost column j:
+        - R row j empty: the row becomes R row j
+        - otherwise a rotation of (R row j, row) zeroes row[j], R row j takes
+          the fill, and the row goes on with its next leftmost column
+    A row that runs out of nonzeros has been absorbed, it only contributes to
+    the residual.
+
+    The rotations are kept, so Q_r^T b can be replayed for any b. Least
+    squares min |A x - b| is then R (Q^T x) = (Q_r^T b)[0..n], without ever
+    forming A^T A. For weighted least squares scale the rows of A and b by
+    the square roots of the weights first.
+*/
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QrError {
+    // fewer rows than columns
+    TooFewRows(usize, usize),
+    // no row of R ends up with its diagonal at column k
+    RankDeficient(usize),
+    // ColumnOrdering::Custom that is not a permutation of 0..n
+    InvalidOrdering(usize),
+    // ColumnOrdering::Amd on a matrix that is not square
+    AmdNotSquare(usize, usize),
+}
+
+impl fmt::Display for QrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QrError::TooFewRows(nrows, ncols) => {
+                write!(f, "QR requires nrows >= ncols, got {} x {}", nrows, ncols)
+            }
+            QrError::RankDeficient(k) => write!(f, "matrix is rank deficient at column {}", k),
+            QrError::InvalidOrdering(n) => {
+                write!(f, "column ordering is not a permutation of 0..{}", n)
+            }
+            QrError::AmdNotSquare(nrows, ncols) => {
+                write!(
+                    f,
+                    "AMD ordering requires a square matrix, got {} x {}",
+                    nrows, ncols
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for QrError {}
+
+// (column, value) pairs sorted by column
+type SparseRow<T> = Vec<(usize, T)>;
+
+// one step of Q_r^T, applied to a vector with one slot per row of A
+#[derive(Debug, Clone, Copy)]
+enum Step<T> {
+    // (R row pivot, A row) <- [c s; -s c] (R row pivot, A row)
+    Rotate {
+        row: usize,
+        pivot: usize,
+        c: T,
+        s: T,
+    },
+    // A row becomes R row pivot
+    Place {
+        row: usize,
+        pivot: usize,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct QrFactors<T: Real> {
+    pub r: SparseCSC<T>,
+    pub q: Vec<usize>,
+    nrows: usize,
+    steps: Vec<Step<T>>,
+}
+
+impl<T: Real> QrFactors<T> {
+    /// First n entries of Q_r^T b.
+    fn apply_qt(&self, b: &[T]) -> Vec<T> {
+        let mut z = b.to_vec();
+        let mut c = vec![T::zero(); self.q.len()];
+        for step in &self.steps {
+            match *step {
+                Step::Rotate {
+                    row,
+                    pivot,
+                    c: cos,
+                    s,
+                } => {
+                    let t = c[pivot];
+                    c[pivot] = cos * t + s * z[row];
+                    z[row] = cos * z[row] - s * t;
+                }
+                Step::Place { row, pivot } => c[pivot] = z[row],
+            }
+        }
+        c
+    }
+
+    /// x minimizing |A x - b|.
+    pub fn solve_least_squares(&self, b: &[T]) -> Vec<T> {
+        assert_eq!(
+            b.len(),
+            self.nrows,
+            "Right-hand side length must match rows"
+        );
+        let mut y = self.apply_qt(b);
+        self.r.upper_solve(&mut y, Diag::NonUnit);
+
+        let mut x = vec![T::zero(); y.len()];
+        for (k, &j) in self.q.iter().enumerate() {
+            x[j] = y[k];
+        }
+        x
+    }
+}
+
+impl<T: Real> SparseCSC<T> {
+    /// QR with natural column order.
+    pub fn qr(&self) -> Result<QrFactors<T>, QrError> {
+        self.qr_with_ordering(&ColumnOrdering::Natural)
+    }
+
+    pub fn qr_with_ordering(&self, ordering: &ColumnOrdering) -> Result<QrFactors<T>, QrError> {
+        let (m, n) = (self.nrows, self.ncols);
+        if m < n {
+            return Err(QrError::TooFewRows(m, n));
+        }
+        if *ordering == ColumnOrdering::Amd && m != n {
+            return Err(QrError::AmdNotSquare(m, n));
+        }
+        let q = match self.column_ordering(ordering) {
+            Ok(q) => q,
+            Err(LuError::InvalidOrdering(n)) => return Err(QrError::InvalidOrdering(n)),
+            Err(err) => unreachable!("column_ordering only rejects a Custom ordering: {}", err),
+        };
+
+        // rows of A Q, columns numbered by position in q and increasing
+        let mut a_rows: Vec<SparseRow<T>> = vec![Vec::new(); m];
+        for (k, &col) in q.iter().enumerate() {
+            let (start, end) = self.get_column_range(col);
+            for p in start..end {
+                if !self.values[p].is_zero() {
+                    a_rows[self.rowind[p]].push((k, self.values[p]));
+                }
+            }
+        }
+        let mut order: Vec<usize> = (0..m).filter(|&i| !a_rows[i].is_empty()).collect();
+        order.sort_by_key(|&i| a_rows[i][0].0);
+
+        let mut r_rows: Vec<SparseRow<T>> = vec![Vec::new(); n];
+        let mut steps = Vec::new();
+        for i in order {
+            let mut row = std::mem::take(&mut a_rows[i]);
+            while let Some(&(j, b)) = row.first() {
+                if r_rows[j].is_empty() {
+                    r_rows[j] = row;
+                    steps.push(Step::Place { row: i, pivot: j });
+                    break;
+                }
+                let a = r_rows[j][0].1;
+                let rho = a.hypot(b);
+                let (c, s) = (a / rho, b / rho);
+                let (new_r, new_row) = rotate(&r_rows[j], &row, c, s);
+                r_rows[j] = new_r;
+                row = new_row;
+                steps.push(Step::Rotate {
+                    row: i,
+                    pivot: j,
+                    c,
+                    s,
+                });
+            }
+        }
+
+        if let Some(k) = r_rows.iter().position(|row| row.is_empty()) {
+            return Err(QrError::RankDeficient(k));
+        }
+
+        // R rows to CSC
+        let mut colptr = vec![0; n + 1];
+        for row in &r_rows {
+            for &(j, _) in row {
+                colptr[j + 1] += 1;
+            }
+        }
+        for j in 0..n {
+            colptr[j + 1] += colptr[j];
+        }
+        let mut next = colptr.clone();
+        let mut rowind = vec![0; colptr[n]];
+        let mut values = vec![T::zero(); colptr[n]];
+        for (i, row) in r_rows.iter().enumerate() {
+            for &(j, value) in row {
+                rowind[next[j]] = i;
+                values[next[j]] = value;
+                next[j] += 1;
+            }
+        }
+
+        Ok(QrFactors {
+            r: SparseCSC {
+                nrows: n,
+                ncols: n,
+                colptr,
+                rowind,
+                values,
+            },
+            q,
+            nrows: m,
+            steps,
+        })
+    }
+}
+
+/*
+    [c s; -s c] applied to two sparse rows with the same leftmost column,
+    merged by column. The second row loses its leftmost entry and any exact
+    zeros; the first keeps its full pattern.
+*/
+fn rotate<T: Real>(
+    r: &[(usize, T)],
+    row: &[(usize, T)],
+    c: T,
+    s: T,
+) -> (SparseRow<T>, SparseRow<T>) {
+    let mut new_r = Vec::with_capacity(r.len() + row.len());
+    let mut new_row = Vec::with_capacity(r.len() + row.len());
+    let (mut p, mut t) = (0, 0);
+    while p < r.len() || t < row.len() {
+        let jr = r.get(p).map_or(usize::MAX, |e| e.0);
+        let jw = row.get(t).map_or(usize::MAX, |e| e.0);
+        let j = jr.min(jw);
+        let x = if jr == j { r[p].1 } else { T::zero() };
+        let y = if jw == j { row[t].1 } else { T::zero() };
+        if jr == j {
+            p += 1;
+        }
+        if jw == j {
+            t += 1;
+        }
+
+        new_r.push((j, c * x + s * y));
+        let rest = c * y - s * x;
+        if j != r[0].0 && !rest.is_zero() {
+            new_row.push((j, rest));
+        }
+    }
+    (new_r, new_row)
+}
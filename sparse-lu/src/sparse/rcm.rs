@@ -0,0 +1,180 @@
+use crate::sparse::amd::symmetric_adjacency;
+use crate::sparse::permutation::inverse_permutation;
+use crate::sparse::scalar::Scalar;
+use crate::sparse::sparse_csc::SparseCSC;
+use crate::sparse::sparse_csr::SparseCSR;
+use std::collections::VecDeque;
+
+/*
+    Reverse Cuthill-McKee ordering
+
+    Breadth-first search over the graph of A + A^T, visiting neighbours in
+    order of increasing degree, then reversed. Nodes end up close to the
+    nodes they connect to, which keeps nonzeros near the diagonal.
+
+    Each connected component starts from a pseudo-peripheral node (George &
+    Liu): repeatedly BFS and jump to a low-degree node on the last level
+    until the number of levels stops growing.
+
+    bandwidth = max |i - j| over nonzeros
+    profile   = sum over rows i of i - (first column j <= i with a nonzero)
+    both measured on A + A^T, so they describe the symmetric envelope.
+*/
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BandStats {
+    pub bandwidth: usize,
+    pub profile: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct RcmReport {
+    pub perm: Vec<usize>,
+    pub before: BandStats,
+    pub after: BandStats,
+}
+
+impl RcmReport {
+    pub fn print(&self) {
+        println!("RCM ordering:");
+        println!(
+            "bandwidth: {} -> {}",
+            self.before.bandwidth, self.after.bandwidth
+        );
+        println!("profile: {} -> {}", self.before.profile, self.after.profile);
+    }
+}
+
+impl<T: Scalar> SparseCSR<T> {
+    /// RCM ordering, perm[k] = row/column of A placed k-th.
+    pub fn rcm(&self) -> RcmReport {
+        assert_eq!(self.nrows, self.ncols);
+        rcm_report(self.nrows, &self.rowptr, &self.colind)
+    }
+
+    pub fn band_stats(&self) -> BandStats {
+        assert_eq!(self.nrows, self.ncols);
+        let identity: Vec<usize> = (0..self.nrows).collect();
+        band_stats(self.nrows, &self.rowptr, &self.colind, &identity)
+    }
+}
+
+impl<T: Scalar> SparseCSC<T> {
+    /// RCM ordering, perm[k] = row/column of A placed k-th.
+    pub fn rcm(&self) -> RcmReport {
+        assert_eq!(self.nrows, self.ncols);
+        rcm_report(self.ncols, &self.colptr, &self.rowind)
+    }
+
+    pub fn band_stats(&self) -> BandStats {
+        assert_eq!(self.nrows, self.ncols);
+        let identity: Vec<usize> = (0..self.ncols).collect();
+        band_stats(self.ncols, &self.colptr, &self.rowind, &identity)
+    }
+}
+
+fn rcm_report(n: usize, ptr: &[usize], ind: &[usize]) -> RcmReport {
+    let perm = rcm(n, ptr, ind);
+    let identity: Vec<usize> = (0..n).collect();
+    RcmReport {
+        before: band_stats(n, ptr, ind, &identity),
+        after: band_stats(n, ptr, ind, &perm),
+        perm,
+    }
+}
+
+pub fn rcm(n: usize, ptr: &[usize], ind: &[usize]) -> Vec<usize> {
+    let mut adj = symmetric_adjacency(n, ptr, ind);
+    let degree: Vec<usize> = adj.iter().map(|list| list.len()).collect();
+    for list in adj.iter_mut() {
+        list.sort_by_key(|&j| (degree[j], j));
+    }
+
+    let mut order = Vec::with_capacity(n);
+    let mut visited = vec![false; n];
+    let mut by_degree: Vec<usize> = (0..n).collect();
+    by_degree.sort_by_key(|&i| (degree[i], i));
+
+    for &seed in &by_degree {
+        if visited[seed] {
+            continue;
+        }
+        let start = pseudo_peripheral_node(&adj, &degree, seed);
+
+        visited[start] = true;
+        let mut queue = VecDeque::from([start]);
+        while let Some(i) = queue.pop_front() {
+            order.push(i);
+            for &j in &adj[i] {
+                if !visited[j] {
+                    visited[j] = true;
+                    queue.push_back(j);
+                }
+            }
+        }
+    }
+
+    order.reverse();
+    order
+}
+
+// BFS levels from start, only within start's component
+fn level_structure(adj: &[Vec<usize>], start: usize) -> Vec<Vec<usize>> {
+    let mut seen = vec![false; adj.len()];
+    seen[start] = true;
+    let mut levels = vec![vec![start]];
+    loop {
+        let mut next = Vec::new();
+        for &i in levels.last().unwrap() {
+            for &j in &adj[i] {
+                if !seen[j] {
+                    seen[j] = true;
+                    next.push(j);
+                }
+            }
+        }
+        if next.is_empty() {
+            return levels;
+        }
+        levels.push(next);
+    }
+}
+
+fn pseudo_peripheral_node(adj: &[Vec<usize>], degree: &[usize], seed: usize) -> usize {
+    let mut node = seed;
+    let mut levels = level_structure(adj, node);
+    loop {
+        let candidate = *levels
+            .last()
+            .unwrap()
+            .iter()
+            .min_by_key(|&&i| (degree[i], i))
+            .unwrap();
+        let candidate_levels = level_structure(adj, candidate);
+        if candidate_levels.len() <= levels.len() {
+            return node;
+        }
+        node = candidate;
+        levels = candidate_levels;
+    }
+}
+
+// stats of P A P^T where perm[k] = node placed k-th
+fn band_stats(n: usize, ptr: &[usize], ind: &[usize], perm: &[usize]) -> BandStats {
+    let inverse = inverse_permutation(perm);
+
+    // first[i] = leftmost column in row i of the permuted A + A^T
+    let mut first: Vec<usize> = (0..n).collect();
+    let mut bandwidth = 0;
+    for j in 0..n {
+        for &i in &ind[ptr[j]..ptr[j + 1]] {
+            let (a, b) = (inverse[i], inverse[j]);
+            let (lo, hi) = if a < b { (a, b) } else { (b, a) };
+            bandwidth = bandwidth.max(hi - lo);
+            first[hi] = first[hi].min(lo);
+        }
+    }
+    let profile = first.iter().enumerate().map(|(i, &f)| i - f).sum();
+
+    BandStats { bandwidth, profile }
+}
@@ -0,0 +1,153 @@
+use crate::sparse::lu::{LuError, LuFactors, LuMethod, LuOptions};
+use crate::sparse::permutation::inverse_permutation;
+use crate::sparse::scalar::Scalar;
+use crate::sparse::sparse_csc::SparseCSC;
+use crate::sparse::symbolic::FactorCounts;
+
+/*
+    Split analysis / factorization for matrices that keep their pattern
+
+    analyze   ordering and symbolic counts, depends only on the pattern
+    factor    full numeric LU with pivoting, using the stored ordering and
+              the method picked in LuOptions
+    refactor  new values, same pivot order and L/U patterns as a previous
+              factor, no graph search and no pivot search
+
+    Newton iterations change the Jacobian values but not its pattern, so the
+    usual sequence is analyze once, factor once, then refactor every step.
+    Refactoring keeps the old pivot order, so if the values move far enough
+    to make a pivot tiny, call factor again to repivot.
+
+    Column k of the refactored factors is the same left-looking step as in
+    lu.rs, but the pattern of L[:, 0..k] \ A[:, q[k]] is already known: it is
+    U[:, k] followed by L[:, k], and with rows in pivot order, eliminating
+    the U rows in increasing order is a valid topological order.
+*/
+
+#[derive(Debug, Clone)]
+pub struct Symbolic {
+    pub q: Vec<usize>,
+    // column etree and counts of A Q, bounds on the columns of L and U
+    pub counts: FactorCounts,
+    pub diag_pivot_thresh: f32,
+    pub method: LuMethod,
+    // pattern of the analyzed A, checked on every factor/refactor
+    colptr: Vec<usize>,
+    rowind: Vec<usize>,
+}
+
+impl<T: Scalar> SparseCSC<T> {
+    /// Ordering and symbolic analysis for factor/refactor.
+    pub fn analyze(&self, options: &LuOptions) -> Result<Symbolic, LuError> {
+        if self.nrows != self.ncols {
+            return Err(LuError::NotSquare(self.nrows, self.ncols));
+        }
+        let q = self.column_ordering(&options.ordering)?;
+        let identity: Vec<usize> = (0..self.nrows).collect();
+        let counts = self.permute(&identity, &q).column_symbolic_counts();
+
+        Ok(Symbolic {
+            q,
+            counts,
+            diag_pivot_thresh: options.diag_pivot_thresh,
+            method: options.method,
+            colptr: self.colptr.clone(),
+            rowind: self.rowind.clone(),
+        })
+    }
+}
+
+impl Symbolic {
+    pub fn size(&self) -> usize {
+        self.q.len()
+    }
+
+    /// Numeric LU with threshold pivoting, P * A * Q = L * U, using the analyzed method.
+    pub fn factor<T: Scalar>(&self, a: &SparseCSC<T>) -> Result<LuFactors<T>, LuError> {
+        match self.method {
+            LuMethod::LeftLooking => self.factor_left_looking(a),
+            LuMethod::Supernodal => self.factor_supernodal(a),
+            LuMethod::Multifrontal => self.factor_multifrontal(a),
+        }
+    }
+
+    pub fn factor_left_looking<T: Scalar>(
+        &self,
+        a: &SparseCSC<T>,
+    ) -> Result<LuFactors<T>, LuError> {
+        self.check_pattern(a)?;
+        let l_bound = self.counts.nnz();
+        let u_bound = self.counts.row_counts.iter().sum();
+        a.lu_ordered(self.q.clone(), self.diag_pivot_thresh, (l_bound, u_bound))
+    }
+
+    /// Recompute the values of factors from a, keeping its P, Q and patterns.
+    /// On an error factors are left as they were.
+    pub fn refactor<T: Scalar>(
+        &self,
+        factors: &mut LuFactors<T>,
+        a: &SparseCSC<T>,
+    ) -> Result<(), LuError> {
+        self.check_pattern(a)?;
+        if factors.q != self.q {
+            return Err(LuError::PatternMismatch);
+        }
+
+        let n = self.size();
+        let pinv = inverse_permutation(&factors.p);
+        let LuFactors { l, u, .. } = factors;
+        // written to factors only once every pivot is known to be nonzero
+        let mut l_values = l.values.clone();
+        let mut u_values = u.values.clone();
+        let mut x = vec![T::zero(); n];
+
+        for k in 0..n {
+            let (a_start, a_end) = a.get_column_range(self.q[k]);
+            for p in a_start..a_end {
+                x[pinv[a.rowind[p]]] = a.values[p];
+            }
+
+            // U[:, k] holds rows j < k sorted, the diagonal last
+            let (u_start, u_end) = u.get_column_range(k);
+            let rows = &u.rowind[u_start..u_end - 1];
+            for (&j, value) in rows.iter().zip(&mut u_values[u_start..u_end - 1]) {
+                let xj = x[j];
+                *value = xj;
+                x[j] = T::zero();
+                let (l_start, l_end) = l.get_column_range(j);
+                let below = &l.rowind[l_start + 1..l_end];
+                for (&i, &lij) in below.iter().zip(&l_values[l_start + 1..l_end]) {
+                    x[i] -= lij * xj;
+                }
+            }
+
+            let pivot = x[k];
+            x[k] = T::zero();
+            if pivot.is_zero() {
+                return Err(LuError::ZeroPivot(k));
+            }
+            u_values[u_end - 1] = pivot;
+
+            // L[:, k] holds the unit diagonal first, then rows below k
+            let (l_start, l_end) = l.get_column_range(k);
+            let below = &l.rowind[l_start + 1..l_end];
+            for (&i, value) in below.iter().zip(&mut l_values[l_start + 1..l_end]) {
+                *value = x[i] / pivot;
+                x[i] = T::zero();
+            }
+        }
+        l.values = l_values;
+        u.values = u_values;
+        Ok(())
+    }
+
+    pub(crate) fn check_pattern<T: Scalar>(&self, a: &SparseCSC<T>) -> Result<(), LuError> {
+        if a.nrows != a.ncols {
+            return Err(LuError::NotSquare(a.nrows, a.ncols));
+        }
+        if a.ncols != self.size() || a.colptr != self.colptr || a.rowind != self.rowind {
+            return Err(LuError::PatternMismatch);
+        }
+        Ok(())
+    }
+}
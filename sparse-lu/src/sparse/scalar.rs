@@ -0,0 +1,97 @@
+use num_complex::Complex;
+use num_traits::{Float, NumAssign};
+use std::fmt::Debug;
+use std::iter::Sum;
+use std::ops::Neg;
+
+/*
+    Element types of the sparse formats
+
+    Scalar is what a matrix can hold: f32, f64, Complex<f32> and
+    Complex<f64>. Arithmetic comes from num_traits (T::zero(), T::one(),
+    +=, ...); on top of that every scalar knows its real type, its modulus
+    |x| and its conjugate, which is all pivoting and solves need.
+
+    Real is the subset with an ordering and the usual float functions
+    (sqrt, hypot, epsilon via num_traits::Float), required by algorithms
+    that compare values directly: Cholesky, QR and the Krylov solvers.
+*/
+
+pub trait Scalar:
+    NumAssign + Copy + Debug + Neg<Output = Self> + Sum + Send + Sync + 'static
+{
+    type Real: Real;
+
+    /// |x|, the absolute value or the complex modulus.
+    fn modulus(self) -> Self::Real;
+    fn conj(self) -> Self;
+    fn from_real(re: Self::Real) -> Self;
+    fn from_f64(x: f64) -> Self;
+    /// Uniform in [0, 1), both parts for complex scalars.
+    fn random(rng: &mut fastrand::Rng) -> Self;
+}
+
+pub trait Real: Scalar<Real = Self> + Float {}
+
+impl Scalar for f32 {
+    type Real = f32;
+
+    fn modulus(self) -> f32 {
+        self.abs()
+    }
+    fn conj(self) -> f32 {
+        self
+    }
+    fn from_real(re: f32) -> f32 {
+        re
+    }
+    fn from_f64(x: f64) -> f32 {
+        x as f32
+    }
+    fn random(rng: &mut fastrand::Rng) -> f32 {
+        rng.f32()
+    }
+}
+
+impl Scalar for f64 {
+    type Real = f64;
+
+    fn modulus(self) -> f64 {
+        self.abs()
+    }
+    fn conj(self) -> f64 {
+        self
+    }
+    fn from_real(re: f64) -> f64 {
+        re
+    }
+    fn from_f64(x: f64) -> f64 {
+        x
+    }
+    fn random(rng: &mut fastrand::Rng) -> f64 {
+        rng.f64()
+    }
+}
+
+impl Real for f32 {}
+impl Real for f64 {}
+
+impl<R: Real> Scalar for Complex<R> {
+    type Real = R;
+
+    fn modulus(self) -> R {
+        self.re.hypot(self.im)
+    }
+    fn conj(self) -> Self {
+        Complex::conj(&self)
+    }
+    fn from_real(re: R) -> Self {
+        Complex::new(re, R::zero())
+    }
+    fn from_f64(x: f64) -> Self {
+        Complex::new(R::from_f64(x), R::zero())
+    }
+    fn random(rng: &mut fastrand::Rng) -> Self {
+        Complex::new(R::random(rng), R::random(rng))
+    }
+}
@@ -1,23 +1,28 @@
-use crate::sparse::{sparse_csc::SparseCSC, sparse_matrix::SparseMatrixTrait};
+use crate::sparse::{
+    scalar::Scalar,
+    sparse_csc::SparseCSC,
+    sparse_matrix::{SparseMatrixTrait, gemv_prologue},
+};
 use rand::seq::index::sample;
 use std::{collections::HashMap, iter::repeat_with};
 
-pub struct SparseCOO {
+#[derive(Debug, Clone)]
+pub struct SparseCOO<T: Scalar> {
     pub nrows: usize,
     pub ncols: usize,
     pub rowind: Vec<usize>, // length = nnz
     pub colind: Vec<usize>, // length = nnz
-    pub values: Vec<f32>,   // length = nnz
+    pub values: Vec<T>,     // length = nnz
 }
 
-impl SparseMatrixTrait for SparseCOO {
+impl<T: Scalar> SparseMatrixTrait<T> for SparseCOO<T> {
     // WARNING: get is O(n)
-    fn get(&self, i: usize, j: usize) -> f32 {
+    fn get(&self, i: usize, j: usize) -> T {
         self.check_bounds(i, j);
         let index = self.get_container_index(i, j);
         match index {
             Some(index) => self.values[index],
-            None => 0.0,
+            None => T::zero(),
         }
     }
     fn size(&self) -> (usize, usize) {
@@ -27,7 +32,7 @@ impl SparseMatrixTrait for SparseCOO {
         self.rowind.len()
     }
     // WARNING: set is O(n)
-    fn set(&mut self, i: usize, j: usize, value: f32) {
+    fn set(&mut self, i: usize, j: usize, value: T) {
         self.check_bounds(i, j);
         let index = self.get_container_index(i, j);
         match index {
@@ -58,7 +63,7 @@ impl SparseMatrixTrait for SparseCOO {
 
         let rowind: Vec<_> = flat_indices.iter().map(|x| x % nrows).collect();
         let colind: Vec<_> = flat_indices.iter().map(|x| x / nrows).collect();
-        let values: Vec<f32> = repeat_with(|| rng.f32()).take(nnz).collect();
+        let values: Vec<T> = repeat_with(|| T::random(&mut rng)).take(nnz).collect();
 
         Self {
             nrows,
@@ -68,7 +73,7 @@ impl SparseMatrixTrait for SparseCOO {
             values,
         }
     }
-    fn from_dense(dense: Vec<Vec<f32>>) -> Self {
+    fn from_dense(dense: Vec<Vec<T>>) -> Self {
         let nrows = dense.len();
         let ncols = dense[0].len();
         let mut rowind = Vec::new();
@@ -76,7 +81,7 @@ impl SparseMatrixTrait for SparseCOO {
         let mut values = Vec::new();
         for (i, row) in dense.iter().enumerate().take(nrows) {
             for (j, value) in row.iter().enumerate().take(ncols) {
-                if *value != 0.0 {
+                if !value.is_zero() {
                     rowind.push(i);
                     colind.push(j);
                     values.push(*value);
@@ -92,23 +97,34 @@ impl SparseMatrixTrait for SparseCOO {
         }
     }
 
-    fn to_dense(&self) -> Vec<Vec<f32>> {
-        let mut dense = vec![vec![0.0; self.ncols]; self.nrows];
+    fn to_dense(&self) -> Vec<Vec<T>> {
+        let mut dense = vec![vec![T::zero(); self.ncols]; self.nrows];
         for i in 0..self.nnz() {
             dense[self.rowind[i]][self.colind[i]] = self.values[i];
         }
         dense
     }
+    fn gemv(&self, alpha: T, x: &[T], beta: T, y: &mut [T]) {
+        gemv_prologue((self.nrows, self.ncols), x, beta, y);
+        for k in 0..self.nnz() {
+            y[self.rowind[k]] += alpha * self.values[k] * x[self.colind[k]];
+        }
+    }
+    fn gemv_transpose(&self, alpha: T, x: &[T], beta: T, y: &mut [T]) {
+        gemv_prologue((self.ncols, self.nrows), x, beta, y);
+        for k in 0..self.nnz() {
+            y[self.colind[k]] += alpha * self.values[k] * x[self.rowind[k]];
+        }
+    }
 }
 
-impl SparseCOO {
+impl<T: Scalar> SparseCOO<T> {
     fn get_container_index(&self, i: usize, j: usize) -> Option<usize> {
         self.check_bounds(i, j);
         self.rowind
             .iter()
             .zip(self.colind.iter())
             .position(|(x, y)| *x == i && *y == j)
-            .map(|x| x)
     }
 
     fn check_bounds(&self, i: usize, j: usize) -> bool {
@@ -132,7 +148,7 @@ impl SparseCOO {
         let target_cols = other.ncols;
         // self.print();
         // other.print();
-        let mut other_row_map: HashMap<usize, Vec<(usize, f32)>> = HashMap::new();
+        let mut other_row_map: HashMap<usize, Vec<(usize, T)>> = HashMap::new();
 
         for i in 0..other.nnz() {
             other_row_map
@@ -141,7 +157,7 @@ impl SparseCOO {
                 .push((other.colind[i], other.values[i]));
         }
 
-        let mut result_map: HashMap<usize, f32> = HashMap::new();
+        let mut result_map: HashMap<usize, T> = HashMap::new();
 
         for i in 0..self.nnz() {
             let row = self.rowind[i];
@@ -150,8 +166,8 @@ impl SparseCOO {
             for (other_row, other_value) in other_row_map.get(&col).unwrap_or(&Vec::new()) {
                 let e = result_map
                     .entry(row * target_cols + *other_row)
-                    .or_insert(0.0);
-                *e += value * other_value;
+                    .or_insert(T::zero());
+                *e += value * *other_value;
             }
         }
 
@@ -160,8 +176,8 @@ impl SparseCOO {
         Self::from_flat_map(self.nrows, other.ncols, result_map)
     }
 
-    fn from_flat_map(nrows: usize, ncols: usize, map: HashMap<usize, f32>) -> Self {
-        let (flat_indexes, values): (Vec<usize>, Vec<f32>) = map.into_iter().unzip();
+    fn from_flat_map(nrows: usize, ncols: usize, map: HashMap<usize, T>) -> Self {
+        let (flat_indexes, values): (Vec<usize>, Vec<T>) = map.into_iter().unzip();
         let rowind: Vec<_> = flat_indexes.iter().map(|x| x / ncols).collect();
         let colind: Vec<_> = flat_indexes.iter().map(|x| x % ncols).collect();
 
@@ -182,7 +198,7 @@ impl SparseCOO {
         nrows: usize,
         ncols: usize,
         flat_indices: Vec<usize>,
-        values: Vec<f32>,
+        values: Vec<T>,
     ) -> Self {
         let rowind: Vec<_> = flat_indices.iter().map(|x| x / ncols).collect();
         let colind: Vec<_> = flat_indices.iter().map(|x| x % ncols).collect();
@@ -195,7 +211,7 @@ impl SparseCOO {
         }
     }
 
-    pub fn to_csc(&self) -> SparseCSC {
+    pub fn to_csc(&self) -> SparseCSC<T> {
         let flat_indices: Vec<usize> = self
             .rowind
             .iter()
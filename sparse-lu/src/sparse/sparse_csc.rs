@@ -1,5 +1,7 @@
+use crate::sparse::scalar::Scalar;
 use crate::sparse::sparse_coo::SparseCOO;
-use crate::sparse::sparse_matrix::SparseMatrixTrait;
+use crate::sparse::sparse_csr::SparseCSR;
+use crate::sparse::sparse_matrix::{SparseMatrixTrait, gemv_prologue};
 use std::collections::HashSet;
 
 /*
@@ -12,21 +14,22 @@ use std::collections::HashSet;
     to get number of nonzeros in column j, use colptr[j+1] - colptr[j]
 */
 
-pub struct SparseCSC {
+#[derive(Debug, Clone)]
+pub struct SparseCSC<T: Scalar> {
     pub nrows: usize,
     pub ncols: usize,
     pub colptr: Vec<usize>, // length = ncols + 1
     pub rowind: Vec<usize>, // length = nnz
-    pub values: Vec<f32>,   // length = nnz
+    pub values: Vec<T>,     // length = nnz
 }
 
-impl SparseMatrixTrait for SparseCSC {
-    fn get(&self, i: usize, j: usize) -> f32 {
+impl<T: Scalar> SparseMatrixTrait<T> for SparseCSC<T> {
+    fn get(&self, i: usize, j: usize) -> T {
         self.check_bounds(i, j);
         let index = self.get_container_index(i, j);
         match index {
             Some(index) => self.values[index],
-            None => 0.0,
+            None => T::zero(),
         }
     }
     fn size(&self) -> (usize, usize) {
@@ -35,7 +38,7 @@ impl SparseMatrixTrait for SparseCSC {
     fn nnz(&self) -> usize {
         self.rowind.len()
     }
-    fn set(&mut self, i: usize, j: usize, value: f32) {
+    fn set(&mut self, i: usize, j: usize, value: T) {
         self.check_bounds(i, j);
         let index = self.get_container_index(i, j);
         match index {
@@ -87,7 +90,7 @@ impl SparseMatrixTrait for SparseCSC {
                 current_col += 1;
             }
             rowind.push(i);
-            values.push(rng.f32());
+            values.push(T::random(&mut rng));
         }
 
         while current_col < ncols {
@@ -103,7 +106,7 @@ impl SparseMatrixTrait for SparseCSC {
             values,
         }
     }
-    fn from_dense(dense: Vec<Vec<f32>>) -> Self {
+    fn from_dense(dense: Vec<Vec<T>>) -> Self {
         let nrows = dense.len();
         let ncols = dense[0].len();
         let mut colptr = vec![0; ncols + 1];
@@ -111,9 +114,9 @@ impl SparseMatrixTrait for SparseCSC {
         let mut values = Vec::new();
 
         for i in 0..ncols {
-            for j in 0..nrows {
-                let value = dense[j][i];
-                if value != 0.0 {
+            for (j, row) in dense.iter().enumerate() {
+                let value = row[i];
+                if !value.is_zero() {
                     rowind.push(j);
                     values.push(value);
                 }
@@ -129,20 +132,42 @@ impl SparseMatrixTrait for SparseCSC {
             values,
         }
     }
-    fn to_dense(&self) -> Vec<Vec<f32>> {
-        let mut dense = vec![vec![0.0; self.ncols]; self.nrows];
+    fn to_dense(&self) -> Vec<Vec<T>> {
+        let mut dense = vec![vec![T::zero(); self.ncols]; self.nrows];
         let mut col = 0;
         for i in 0..self.nnz() {
-            if i >= self.colptr[col + 1] {
+            while i >= self.colptr[col + 1] {
                 col += 1;
             }
             dense[self.rowind[i]][col] = self.values[i];
         }
         dense
     }
+    fn gemv(&self, alpha: T, x: &[T], beta: T, y: &mut [T]) {
+        gemv_prologue((self.nrows, self.ncols), x, beta, y);
+        for (j, &xj) in x.iter().enumerate() {
+            let scaled = alpha * xj;
+            if scaled.is_zero() {
+                continue;
+            }
+            for p in self.colptr[j]..self.colptr[j + 1] {
+                y[self.rowind[p]] += self.values[p] * scaled;
+            }
+        }
+    }
+    fn gemv_transpose(&self, alpha: T, x: &[T], beta: T, y: &mut [T]) {
+        gemv_prologue((self.ncols, self.nrows), x, beta, y);
+        for (j, yj) in y.iter_mut().enumerate() {
+            let (start, end) = (self.colptr[j], self.colptr[j + 1]);
+            let sum: T = (start..end)
+                .map(|p| self.values[p] * x[self.rowind[p]])
+                .sum();
+            *yj += alpha * sum;
+        }
+    }
 }
 
-impl SparseCSC {
+impl<T: Scalar> SparseCSC<T> {
     fn get_container_index(&self, i: usize, j: usize) -> Option<usize> {
         // binary search column
         let start = self.colptr[j];
@@ -183,7 +208,7 @@ impl SparseCSC {
         nrows: usize,
         ncols: usize,
         flat_indices: Vec<usize>,
-        flat_values: Vec<f32>,
+        flat_values: Vec<T>,
     ) -> Self {
         assert_eq!(flat_indices.len(), flat_values.len());
 
@@ -195,7 +220,7 @@ impl SparseCSC {
 
         flat_pairs.sort_unstable_by_key(|pair| pair.0);
 
-        let (sorted_flat_indices, sorted_flat_values): (Vec<usize>, Vec<f32>) =
+        let (sorted_flat_indices, sorted_flat_values): (Vec<usize>, Vec<T>) =
             flat_pairs.into_iter().unzip();
 
         let nnz = sorted_flat_indices.len();
@@ -230,12 +255,12 @@ impl SparseCSC {
         }
     }
 
-    pub fn to_coo(&self) -> SparseCOO {
+    pub fn to_coo(&self) -> SparseCOO<T> {
         let mut col = 0;
         let mut flat_indices = Vec::with_capacity(self.nnz());
 
         for i in 0..self.nnz() {
-            if i >= self.colptr[col + 1] {
+            while i >= self.colptr[col + 1] {
                 col += 1;
             }
             flat_indices.push(self.rowind[i] * self.ncols + col);
@@ -244,6 +269,24 @@ impl SparseCSC {
         SparseCOO::from_flat_indices(self.nrows, self.ncols, flat_indices, self.values.clone())
     }
 
+    /// Same matrix in CSR, column indices sorted within each row.
+    pub fn to_csr(&self) -> SparseCSR<T> {
+        let (rowptr, colind, values) =
+            transpose_compressed(self.nrows, &self.colptr, &self.rowind, &self.values);
+        SparseCSR {
+            nrows: self.nrows,
+            ncols: self.ncols,
+            rowptr,
+            colind,
+            values,
+        }
+    }
+
+    // sort row indices within each column so get() can binary search
+    pub fn sort_indices(&mut self) {
+        sort_compressed(&self.colptr, &mut self.rowind, &mut self.values);
+    }
+
     pub fn nonzero_columns(&self) -> Vec<usize> {
         let mut nonzero_columns = Vec::new();
         for i in 0..self.ncols {
@@ -263,5 +306,57 @@ pub fn flat_index_to_column_major(flat_index: usize, nrows: usize, ncols: usize)
 
     let row = flat_index / ncols;
     let col = flat_index % ncols;
-    return col * nrows + row;
+    col * nrows + row
+}
+
+// sort ind (and values along with it) within each segment ptr[k]..ptr[k + 1]
+pub fn sort_compressed<T: Copy>(ptr: &[usize], ind: &mut [usize], values: &mut [T]) {
+    let mut segment: Vec<(usize, T)> = Vec::new();
+    for k in 0..ptr.len() - 1 {
+        let (start, end) = (ptr[k], ptr[k + 1]);
+        if ind[start..end].is_sorted() {
+            continue;
+        }
+        segment.clear();
+        segment.extend((start..end).map(|p| (ind[p], values[p])));
+        segment.sort_unstable_by_key(|pair| pair.0);
+        for (p, (i, v)) in (start..end).zip(segment.iter()) {
+            ind[p] = *i;
+            values[p] = *v;
+        }
+    }
+}
+
+/*
+    Counting-sort transpose of compressed storage: segments of ptr/ind become
+    the minor dimension and vice versa, so CSC arrays turn into the CSR arrays
+    of the same matrix and back. Indices come out sorted within each segment.
+*/
+pub fn transpose_compressed<T: Scalar>(
+    n_minor: usize,
+    ptr: &[usize],
+    ind: &[usize],
+    values: &[T],
+) -> (Vec<usize>, Vec<usize>, Vec<T>) {
+    let nnz = ptr[ptr.len() - 1];
+    let mut t_ptr = vec![0; n_minor + 1];
+    for &i in &ind[..nnz] {
+        t_ptr[i + 1] += 1;
+    }
+    for i in 0..n_minor {
+        t_ptr[i + 1] += t_ptr[i];
+    }
+
+    let mut next = t_ptr.clone();
+    let mut t_ind = vec![0; nnz];
+    let mut t_values = vec![T::zero(); nnz];
+    for k in 0..ptr.len() - 1 {
+        for p in ptr[k]..ptr[k + 1] {
+            let slot = next[ind[p]];
+            t_ind[slot] = k;
+            t_values[slot] = values[p];
+            next[ind[p]] += 1;
+        }
+    }
+    (t_ptr, t_ind, t_values)
 }
@@ -1,6 +1,7 @@
+use crate::sparse::scalar::Scalar;
 use crate::sparse::sparse_coo::SparseCOO;
-use crate::sparse::sparse_csc::SparseCSC;
-use crate::sparse::sparse_matrix::SparseMatrixTrait;
+use crate::sparse::sparse_csc::{SparseCSC, sort_compressed, transpose_compressed};
+use crate::sparse::sparse_matrix::{SparseMatrixTrait, gemv_prologue};
 use std::collections::HashSet;
 
 /*
@@ -15,21 +16,22 @@ use std::collections::HashSet;
     NOTE: not needed for LU, but CSR x CSR should be the most efficent multiplication format
 */
 
-pub struct SparseCSR {
+#[derive(Debug, Clone)]
+pub struct SparseCSR<T: Scalar> {
     pub nrows: usize,
     pub ncols: usize,
     pub rowptr: Vec<usize>, // length = nrows + 1
     pub colind: Vec<usize>, // length = nnz
-    pub values: Vec<f32>,   // length = nnz
+    pub values: Vec<T>,     // length = nnz
 }
 
-impl SparseMatrixTrait for SparseCSR {
-    fn get(&self, i: usize, j: usize) -> f32 {
+impl<T: Scalar> SparseMatrixTrait<T> for SparseCSR<T> {
+    fn get(&self, i: usize, j: usize) -> T {
         self.check_bounds(i, j);
         let index = self.get_container_index(i, j);
         match index {
             Some(index) => self.values[index],
-            None => 0.0,
+            None => T::zero(),
         }
     }
     fn size(&self) -> (usize, usize) {
@@ -38,7 +40,7 @@ impl SparseMatrixTrait for SparseCSR {
     fn nnz(&self) -> usize {
         self.colind.len()
     }
-    fn set(&mut self, i: usize, j: usize, value: f32) {
+    fn set(&mut self, i: usize, j: usize, value: T) {
         self.check_bounds(i, j);
         let index = self.get_container_index(i, j);
         match index {
@@ -90,7 +92,7 @@ impl SparseMatrixTrait for SparseCSR {
                 current_row += 1;
             }
             colind.push(col);
-            values.push(rng.f32());
+            values.push(T::random(&mut rng));
         }
 
         while current_row < nrows {
@@ -106,17 +108,16 @@ impl SparseMatrixTrait for SparseCSR {
             values,
         }
     }
-    fn from_dense(dense: Vec<Vec<f32>>) -> Self {
+    fn from_dense(dense: Vec<Vec<T>>) -> Self {
         let nrows = dense.len();
         let ncols = dense[0].len();
         let mut rowptr = vec![0; nrows + 1];
         let mut colind = Vec::new();
         let mut values = Vec::new();
 
-        for i in 0..nrows {
-            for j in 0..ncols {
-                let value = dense[i][j];
-                if value != 0.0 {
+        for (i, row) in dense.iter().enumerate() {
+            for (j, &value) in row.iter().enumerate().take(ncols) {
+                if !value.is_zero() {
                     colind.push(j);
                     values.push(value);
                 }
@@ -132,20 +133,42 @@ impl SparseMatrixTrait for SparseCSR {
             values,
         }
     }
-    fn to_dense(&self) -> Vec<Vec<f32>> {
-        let mut dense = vec![vec![0.0; self.ncols]; self.nrows];
+    fn to_dense(&self) -> Vec<Vec<T>> {
+        let mut dense = vec![vec![T::zero(); self.ncols]; self.nrows];
         let mut row = 0;
         for i in 0..self.nnz() {
-            if i >= self.rowptr[row + 1] {
+            while i >= self.rowptr[row + 1] {
                 row += 1;
             }
             dense[row][self.colind[i]] = self.values[i];
         }
         dense
     }
+    fn gemv(&self, alpha: T, x: &[T], beta: T, y: &mut [T]) {
+        gemv_prologue((self.nrows, self.ncols), x, beta, y);
+        for (i, yi) in y.iter_mut().enumerate() {
+            let (start, end) = (self.rowptr[i], self.rowptr[i + 1]);
+            let sum: T = (start..end)
+                .map(|p| self.values[p] * x[self.colind[p]])
+                .sum();
+            *yi += alpha * sum;
+        }
+    }
+    fn gemv_transpose(&self, alpha: T, x: &[T], beta: T, y: &mut [T]) {
+        gemv_prologue((self.ncols, self.nrows), x, beta, y);
+        for (i, &xi) in x.iter().enumerate() {
+            let scaled = alpha * xi;
+            if scaled.is_zero() {
+                continue;
+            }
+            for p in self.rowptr[i]..self.rowptr[i + 1] {
+                y[self.colind[p]] += self.values[p] * scaled;
+            }
+        }
+    }
 }
 
-impl SparseCSR {
+impl<T: Scalar> SparseCSR<T> {
     fn get_container_index(&self, i: usize, j: usize) -> Option<usize> {
         // binary search column
         let start = self.rowptr[i];
@@ -181,12 +204,17 @@ impl SparseCSR {
         (self.rowptr[i], self.rowptr[i + 1])
     }
 
+    // sort column indices within each row so get() can binary search
+    pub fn sort_indices(&mut self) {
+        sort_compressed(&self.rowptr, &mut self.colind, &mut self.values);
+    }
+
     // TODO: take optional flat_values
     pub fn from_flat_indices(
         nrows: usize,
         ncols: usize,
         flat_indices: Vec<usize>,
-        flat_values: Vec<f32>,
+        flat_values: Vec<T>,
     ) -> Self {
         assert_eq!(flat_indices.len(), flat_values.len());
 
@@ -197,7 +225,7 @@ impl SparseCSR {
 
         flat_pairs.sort_unstable_by_key(|pair| pair.0);
 
-        let (sorted_flat_indices, sorted_flat_values): (Vec<usize>, Vec<f32>) =
+        let (sorted_flat_indices, sorted_flat_values): (Vec<usize>, Vec<T>) =
             flat_pairs.into_iter().unzip();
 
         let nnz = sorted_flat_indices.len();
@@ -208,7 +236,7 @@ impl SparseCSR {
         let mut values = Vec::with_capacity(nnz);
 
         let mut current_row: usize = 0;
-        for flat_index in sorted_flat_indices {
+        for (flat_index, value) in sorted_flat_indices.into_iter().zip(sorted_flat_values) {
             let row = flat_index / ncols;
             let col = flat_index % ncols;
             while row > current_row {
@@ -216,7 +244,7 @@ impl SparseCSR {
                 current_row += 1;
             }
             colind.push(col);
-            values.push(sorted_flat_values[flat_index]);
+            values.push(value);
         }
 
         while current_row < nrows {
@@ -233,12 +261,12 @@ impl SparseCSR {
         }
     }
 
-    pub fn to_flat_indices(&self) -> (Vec<usize>, Vec<f32>) {
+    pub fn to_flat_indices(&self) -> (Vec<usize>, Vec<T>) {
         let mut row = 0;
         let mut flat_indices = Vec::with_capacity(self.nnz());
 
         for i in 0..self.nnz() {
-            if i >= self.rowptr[row + 1] {
+            while i >= self.rowptr[row + 1] {
                 row += 1;
             }
             flat_indices.push(row * self.ncols + self.colind[i]);
@@ -247,28 +275,41 @@ impl SparseCSR {
         (flat_indices, self.values.clone())
     }
 
-    pub fn to_coo(&self) -> SparseCOO {
+    /// Same matrix in CSC, row indices sorted within each column.
+    pub fn to_csc(&self) -> SparseCSC<T> {
+        let (colptr, rowind, values) =
+            transpose_compressed(self.ncols, &self.rowptr, &self.colind, &self.values);
+        SparseCSC {
+            nrows: self.nrows,
+            ncols: self.ncols,
+            colptr,
+            rowind,
+            values,
+        }
+    }
+
+    pub fn to_coo(&self) -> SparseCOO<T> {
         let (flat_indices, values) = self.to_flat_indices();
 
         SparseCOO::from_flat_indices(self.nrows, self.ncols, flat_indices, values)
     }
 
     /*
-    
+
     a b c      1 2 3   = a1 + b4 + c7, a2 + b5 + c8, a3 + b6 + c9
     d e f   x  4 5 6   = d1 + e4 + f7, d2 + e5 + f8, d3 + e6 + f9
     g h i      7 8 9   = g1 + h4 + i7, g2 + h5 + i8, g3 + h6 + i9
-    
+
     */
 
-    pub fn multiply_to_flat_csr(&self, other: &SparseCSR) -> (Vec<usize>, Vec<f32>) {
+    pub fn multiply_to_flat_csr(&self, other: &SparseCSR<T>) -> (Vec<usize>, Vec<T>) {
         assert_eq!(self.ncols, other.nrows);
         let target_cols = other.ncols;
 
         let mut result_flat_indices = Vec::new();
         let mut result_values = Vec::new();
 
-        let mut acc_row = vec![0.0; target_cols];
+        let mut acc_row = vec![T::zero(); target_cols];
         let mut seen_cols: Vec<usize> = Vec::new();
 
         for row in 0..self.nrows {
@@ -282,7 +323,7 @@ impl SparseCSR {
                     let b_col = other.colind[b_r_ptr];
                     let other_value = other.values[b_r_ptr];
 
-                    if acc_row[b_col] == 0.0 {
+                    if acc_row[b_col].is_zero() {
                         seen_cols.push(b_col);
                     }
 
@@ -293,22 +334,20 @@ impl SparseCSR {
             for col in &seen_cols {
                 result_flat_indices.push(row * target_cols + col);
                 result_values.push(acc_row[*col]);
-                acc_row[*col] = 0.0;
+                acc_row[*col] = T::zero();
             }
 
             seen_cols.clear();
         }
-        
+
         // println!("result_flat_indices: {:?}", result_flat_indices);
         // println!("result_values: {:?}", result_values);
-        
 
         (result_flat_indices, result_values)
     }
 
-    pub fn multiply_csr(&self, other: &SparseCSR) -> SparseCSR {
-        let (flat_indices, values) = self.multiply_to_flat_csr(other);
-        SparseCSR::from_flat_indices(self.nrows, other.ncols, flat_indices, values)
+    // two-phase Gustavson product, see spgemm.rs
+    pub fn multiply_csr(&self, other: &SparseCSR<T>) -> SparseCSR<T> {
+        self.spgemm(other)
     }
-
 }
@@ -1,13 +1,48 @@
-// too lazy to support generic types
-pub trait SparseMatrixTrait {
-    fn get(&self, i: usize, j: usize) -> f32;
+use crate::sparse::scalar::Scalar;
+
+// T is the element type, see scalar.rs
+pub trait SparseMatrixTrait<T: Scalar> {
+    fn get(&self, i: usize, j: usize) -> T;
     fn size(&self) -> (usize, usize);
     fn nnz(&self) -> usize;
-    fn set(&mut self, i: usize, j: usize, value: f32);
+    fn set(&mut self, i: usize, j: usize, value: T);
     fn new(rows: usize, cols: usize) -> Self;
     fn random(rows: usize, cols: usize, density: f32) -> Self;
-    fn from_dense(dense: Vec<Vec<f32>>) -> Self;
-    fn to_dense(&self) -> Vec<Vec<f32>>;
+    fn from_dense(dense: Vec<Vec<T>>) -> Self;
+    fn to_dense(&self) -> Vec<Vec<T>>;
+
+    /// y = alpha * A * x + beta * y, y is overwritten when beta == 0.
+    fn gemv(&self, alpha: T, x: &[T], beta: T, y: &mut [T]);
+    /// y = alpha * A^T * x + beta * y, y is overwritten when beta == 0.
+    fn gemv_transpose(&self, alpha: T, x: &[T], beta: T, y: &mut [T]);
+
+    /// A * x.
+    fn matvec(&self, x: &[T]) -> Vec<T> {
+        let mut y = vec![T::zero(); self.size().0];
+        self.gemv(T::one(), x, T::zero(), &mut y);
+        y
+    }
+    /// A^T * x.
+    fn matvec_transpose(&self, x: &[T]) -> Vec<T> {
+        let mut y = vec![T::zero(); self.size().1];
+        self.gemv_transpose(T::one(), x, T::zero(), &mut y);
+        y
+    }
+}
+
+/*
+    Shared start of every gemv: check the lengths against the (possibly
+    transposed) shape and apply beta, so the format only has to add
+    alpha * A * x. beta == 0 clears y, NaNs in it included, as in BLAS.
+*/
+pub(crate) fn gemv_prologue<T: Scalar>(shape: (usize, usize), x: &[T], beta: T, y: &mut [T]) {
+    assert_eq!(x.len(), shape.1, "x length must match matrix columns");
+    assert_eq!(y.len(), shape.0, "y length must match matrix rows");
+    if beta.is_zero() {
+        y.fill(T::zero());
+    } else if !beta.is_one() {
+        y.iter_mut().for_each(|yi| *yi *= beta);
+    }
 }
 
 //TODO: create sparseMatrix class that handles conversion between different sparse matrix formats
@@ -0,0 +1,369 @@
+use crate::sparse::scalar::Scalar;
+use crate::sparse::sparse_csc::{SparseCSC, transpose_compressed};
+use crate::sparse::sparse_csr::SparseCSR;
+
+/*
+    Sparse matrix products
+
+    CSR x CSR, two-phase Gustavson. Row i of C is the sum of the rows
+    B[k, :] scaled by A[i, k]. The symbolic phase only merges patterns: a
+    marker array collects the distinct columns of each row, which gives
+    rowptr and, after sorting each row, colind. The numeric phase scatters
+    every product A[i, k] B[k, j] straight into the slot of (i, j), found
+    through a column -> slot map for the current row, so the values come
+    out in sorted order with no flat index round trip.
+
+    The pattern is structural: entries that cancel to zero are kept. That
+    is what lets one SpgemmSymbolic be reused for any A and B with the same
+    patterns, e.g. a Jacobian product rebuilt every Newton iteration.
+
+    The other format pairs pick the product form their storage gives:
+        CSC x CSC  Gustavson by columns, C[:, j] = sum A[:, k] B[k, j],
+                   the same kernel with the roles of A and B swapped
+        CSC x CSR  outer products, C = sum_k A[:, k] B[k, :]; partial
+                   products are bucketed by row, then each row is summed
+        CSR x CSC  inner products, C[i, j] = A[i, :] . B[:, j], when the
+                   m x n output is no bigger than the inputs; otherwise
+                   every pair would be tried, so B is transposed to rows
+                   once (a counting sort, O(nnz)) and Gustavson runs
+*/
+
+#[derive(Debug, Clone)]
+pub struct SpgemmSymbolic {
+    pub nrows: usize,
+    pub ncols: usize,
+    pub rowptr: Vec<usize>,
+    pub colind: Vec<usize>,
+    // shapes and nnz of A and B, checked on reuse
+    a_shape: (usize, usize, usize),
+    b_shape: (usize, usize, usize),
+}
+
+// segment s of a CSR or CSC matrix is ind/values[ptr[s]..ptr[s + 1]]
+#[derive(Clone, Copy)]
+struct Compressed<'a, T> {
+    ptr: &'a [usize],
+    ind: &'a [usize],
+    values: &'a [T],
+}
+
+impl<'a, T> Compressed<'a, T> {
+    fn segments(&self) -> usize {
+        self.ptr.len() - 1
+    }
+
+    fn segment(&self, s: usize) -> (&'a [usize], &'a [T]) {
+        let (start, end) = (self.ptr[s], self.ptr[s + 1]);
+        (&self.ind[start..end], &self.values[start..end])
+    }
+}
+
+impl<T: Scalar> SparseCSR<T> {
+    fn compressed(&self) -> Compressed<'_, T> {
+        Compressed {
+            ptr: &self.rowptr,
+            ind: &self.colind,
+            values: &self.values,
+        }
+    }
+}
+
+impl<T: Scalar> SparseCSC<T> {
+    fn compressed(&self) -> Compressed<'_, T> {
+        Compressed {
+            ptr: &self.colptr,
+            ind: &self.rowind,
+            values: &self.values,
+        }
+    }
+}
+
+/*
+    Gustavson pattern: output segment s is the union of the b segments named
+    by the indices of a segment s, sorted. n_out is the index range of b.
+*/
+fn gustavson_pattern<T>(
+    a: Compressed<T>,
+    b: Compressed<T>,
+    n_out: usize,
+) -> (Vec<usize>, Vec<usize>) {
+    let mut ptr = Vec::with_capacity(a.segments() + 1);
+    let mut ind = Vec::new();
+    // mark[j] == s once index j is in segment s
+    let mut mark = vec![usize::MAX; n_out];
+
+    ptr.push(0);
+    for s in 0..a.segments() {
+        let start = ind.len();
+        for &k in a.segment(s).0 {
+            for &j in b.segment(k).0 {
+                if mark[j] != s {
+                    mark[j] = s;
+                    ind.push(j);
+                }
+            }
+        }
+        ind[start..].sort_unstable();
+        ptr.push(ind.len());
+    }
+    (ptr, ind)
+}
+
+// Gustavson values on a pattern from gustavson_pattern
+fn gustavson_values<T: Scalar>(
+    a: Compressed<T>,
+    b: Compressed<T>,
+    (ptr, ind): (&[usize], &[usize]),
+    n_out: usize,
+    values: &mut [T],
+) {
+    values.fill(T::zero());
+    // slot[j] = position of index j in the current output segment
+    let mut slot = vec![0; n_out];
+    for s in 0..a.segments() {
+        let (start, end) = (ptr[s], ptr[s + 1]);
+        for p in start..end {
+            slot[ind[p]] = p;
+        }
+        let (a_ind, a_values) = a.segment(s);
+        for (&k, &a_sk) in a_ind.iter().zip(a_values) {
+            let (b_ind, b_values) = b.segment(k);
+            for (&j, &b_kj) in b_ind.iter().zip(b_values) {
+                let q = slot[j];
+                assert!(
+                    (start..end).contains(&q) && ind[q] == j,
+                    "Matrices must have the patterns the symbolic product was built from"
+                );
+                values[q] += a_sk * b_kj;
+            }
+        }
+    }
+}
+
+fn gustavson<T: Scalar>(
+    a: Compressed<T>,
+    b: Compressed<T>,
+    n_out: usize,
+) -> (Vec<usize>, Vec<usize>, Vec<T>) {
+    let (ptr, ind) = gustavson_pattern(a, b, n_out);
+    let mut values = vec![T::zero(); ind.len()];
+    gustavson_values(a, b, (&ptr, &ind), n_out, &mut values);
+    (ptr, ind, values)
+}
+
+fn check_dimensions(a_ncols: usize, b_nrows: usize) {
+    assert_eq!(
+        a_ncols, b_nrows,
+        "Matrix dimensions must be compatible for multiplication"
+    );
+}
+
+impl<T: Scalar> SparseCSR<T> {
+    /// Pattern of self * other, reusable while the patterns stay fixed.
+    pub fn spgemm_symbolic(&self, other: &SparseCSR<T>) -> SpgemmSymbolic {
+        check_dimensions(self.ncols, other.nrows);
+        let (rowptr, colind) =
+            gustavson_pattern(self.compressed(), other.compressed(), other.ncols);
+        SpgemmSymbolic {
+            nrows: self.nrows,
+            ncols: other.ncols,
+            rowptr,
+            colind,
+            a_shape: (self.nrows, self.ncols, self.values.len()),
+            b_shape: (other.nrows, other.ncols, other.values.len()),
+        }
+    }
+
+    /// self * other with sorted column indices, explicit zeros kept.
+    pub fn spgemm(&self, other: &SparseCSR<T>) -> SparseCSR<T> {
+        self.spgemm_symbolic(other).numeric(self, other)
+    }
+
+    /// self * other for a CSC right-hand side, result in CSR.
+    pub fn multiply_csc(&self, other: &SparseCSC<T>) -> SparseCSR<T> {
+        check_dimensions(self.ncols, other.nrows);
+        let (m, n) = (self.nrows, other.ncols);
+        if m * n > self.values.len() + other.values.len() {
+            let (rowptr, colind, values) =
+                transpose_compressed(other.nrows, &other.colptr, &other.rowind, &other.values);
+            let b_rows = Compressed {
+                ptr: &rowptr,
+                ind: &colind,
+                values: &values,
+            };
+            let (rowptr, colind, values) = gustavson(self.compressed(), b_rows, n);
+            return SparseCSR {
+                nrows: m,
+                ncols: n,
+                rowptr,
+                colind,
+                values,
+            };
+        }
+
+        // inner products against a scattered copy of each row of A
+        let mut rowptr = Vec::with_capacity(m + 1);
+        let mut colind = Vec::new();
+        let mut values = Vec::new();
+        let mut w = vec![T::zero(); self.ncols];
+        let mut mark = vec![usize::MAX; self.ncols];
+        rowptr.push(0);
+        for i in 0..m {
+            let (a_ind, a_values) = self.compressed().segment(i);
+            for (&k, &a_ik) in a_ind.iter().zip(a_values) {
+                w[k] = a_ik;
+                mark[k] = i;
+            }
+            for j in 0..n {
+                let (b_ind, b_values) = other.compressed().segment(j);
+                let mut hit = false;
+                let mut sum = T::zero();
+                for (&k, &b_kj) in b_ind.iter().zip(b_values) {
+                    if mark[k] == i {
+                        hit = true;
+                        sum += w[k] * b_kj;
+                    }
+                }
+                if hit {
+                    colind.push(j);
+                    values.push(sum);
+                }
+            }
+            rowptr.push(colind.len());
+        }
+        SparseCSR {
+            nrows: m,
+            ncols: n,
+            rowptr,
+            colind,
+            values,
+        }
+    }
+}
+
+impl<T: Scalar> SparseCSC<T> {
+    /// self * other by columns, sorted row indices, explicit zeros kept.
+    pub fn multiply_csc(&self, other: &SparseCSC<T>) -> SparseCSC<T> {
+        check_dimensions(self.ncols, other.nrows);
+        // C^T = B^T A^T, and CSC arrays are the CSR arrays of the transpose
+        let (colptr, rowind, values) = gustavson(other.compressed(), self.compressed(), self.nrows);
+        SparseCSC {
+            nrows: self.nrows,
+            ncols: other.ncols,
+            colptr,
+            rowind,
+            values,
+        }
+    }
+
+    /// self * other for a CSR right-hand side as a sum of outer products, result in CSR.
+    pub fn multiply_csr(&self, other: &SparseCSR<T>) -> SparseCSR<T> {
+        check_dimensions(self.ncols, other.nrows);
+        let (m, n) = (self.nrows, other.ncols);
+        let (a, b) = (self.compressed(), other.compressed());
+
+        // partial products of each row, A[i, k] B[k, :] for every k
+        let mut bucket_ptr = vec![0; m + 1];
+        for k in 0..a.segments() {
+            let row_len = b.segment(k).0.len();
+            for &i in a.segment(k).0 {
+                bucket_ptr[i + 1] += row_len;
+            }
+        }
+        for i in 0..m {
+            bucket_ptr[i + 1] += bucket_ptr[i];
+        }
+        let mut next = bucket_ptr.clone();
+        let mut bucket = vec![(0, T::zero()); bucket_ptr[m]];
+        for k in 0..a.segments() {
+            let (b_ind, b_values) = b.segment(k);
+            let (a_ind, a_values) = a.segment(k);
+            for (&i, &a_ik) in a_ind.iter().zip(a_values) {
+                for (&j, &b_kj) in b_ind.iter().zip(b_values) {
+                    bucket[next[i]] = (j, a_ik * b_kj);
+                    next[i] += 1;
+                }
+            }
+        }
+
+        // sum each row's products, k increasing within a row as in Gustavson
+        let mut rowptr = Vec::with_capacity(m + 1);
+        let mut colind = Vec::new();
+        let mut values = Vec::new();
+        let mut w = vec![T::zero(); n];
+        let mut mark = vec![usize::MAX; n];
+        rowptr.push(0);
+        for i in 0..m {
+            let start = colind.len();
+            for &(j, product) in &bucket[bucket_ptr[i]..bucket_ptr[i + 1]] {
+                if mark[j] != i {
+                    mark[j] = i;
+                    w[j] = T::zero();
+                    colind.push(j);
+                }
+                w[j] += product;
+            }
+            colind[start..].sort_unstable();
+            values.extend(colind[start..].iter().map(|&j| w[j]));
+            rowptr.push(colind.len());
+        }
+        SparseCSR {
+            nrows: m,
+            ncols: n,
+            rowptr,
+            colind,
+            values,
+        }
+    }
+}
+
+impl SpgemmSymbolic {
+    pub fn nnz(&self) -> usize {
+        self.colind.len()
+    }
+
+    /// A * B on the stored pattern.
+    pub fn numeric<T: Scalar>(&self, a: &SparseCSR<T>, b: &SparseCSR<T>) -> SparseCSR<T> {
+        let mut c = SparseCSR {
+            nrows: self.nrows,
+            ncols: self.ncols,
+            rowptr: self.rowptr.clone(),
+            colind: self.colind.clone(),
+            values: vec![T::zero(); self.nnz()],
+        };
+        self.numeric_values(a, b, &mut c.values);
+        c
+    }
+
+    /*
+        A * B into the values of c, which must carry this pattern (a previous
+        numeric() result), without allocating.
+    */
+    pub fn numeric_into<T: Scalar>(
+        &self,
+        a: &SparseCSR<T>,
+        b: &SparseCSR<T>,
+        c: &mut SparseCSR<T>,
+    ) {
+        assert!(
+            c.rowptr == self.rowptr && c.colind == self.colind,
+            "Output must have the symbolic pattern"
+        );
+        self.numeric_values(a, b, &mut c.values);
+    }
+
+    fn numeric_values<T: Scalar>(&self, a: &SparseCSR<T>, b: &SparseCSR<T>, values: &mut [T]) {
+        assert!(
+            (a.nrows, a.ncols, a.values.len()) == self.a_shape
+                && (b.nrows, b.ncols, b.values.len()) == self.b_shape,
+            "Matrices must have the patterns the symbolic product was built from"
+        );
+        gustavson_values(
+            a.compressed(),
+            b.compressed(),
+            (&self.rowptr, &self.colind),
+            self.ncols,
+            values,
+        );
+    }
+}
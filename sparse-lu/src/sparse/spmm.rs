@@ -0,0 +1,97 @@
+use crate::sparse::scalar::Scalar;
+use crate::sparse::sparse_csc::SparseCSC;
+use crate::sparse::sparse_csr::SparseCSR;
+use crate::sparse::sparse_matrix::SparseMatrixTrait;
+
+/*
+    Sparse times dense block, C = A * B
+
+    B is a flat nrows(B) x ncols buffer in either layout and C comes back in
+    the same layout:
+        RowMajor  B[i, j] = b[i * ncols + j]
+        ColMajor  B[i, j] = b[j * nrows + i]
+
+    Row-major blocks are worked on a whole row at a time (each nonzero of A
+    scales one contiguous row of B into a row of C), column-major ones a
+    column at a time with gemv, so the inner loops always run over
+    contiguous memory.
+*/
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DenseLayout {
+    RowMajor,
+    ColMajor,
+}
+
+fn check_dense<T>(a_ncols: usize, b: &[T], ncols: usize) {
+    assert_eq!(
+        b.len(),
+        a_ncols * ncols,
+        "Dense block must have one row per matrix column"
+    );
+}
+
+// C column by column, each a gemv on the matching column of B
+fn mul_columns<T: Scalar>(a: &impl SparseMatrixTrait<T>, b: &[T], ncols: usize) -> Vec<T> {
+    let (m, n) = a.size();
+    let mut c = vec![T::zero(); m * ncols];
+    for j in 0..ncols {
+        a.gemv(
+            T::one(),
+            &b[j * n..(j + 1) * n],
+            T::zero(),
+            &mut c[j * m..(j + 1) * m],
+        );
+    }
+    c
+}
+
+// c_row += alpha * b_row
+fn axpy_row<T: Scalar>(alpha: T, b_row: &[T], c_row: &mut [T]) {
+    for (c, b) in c_row.iter_mut().zip(b_row) {
+        *c += alpha * *b;
+    }
+}
+
+impl<T: Scalar> SparseCSR<T> {
+    /// A * B for a dense block B with ncols columns, C in the layout of B.
+    pub fn mul_dense(&self, b: &[T], ncols: usize, layout: DenseLayout) -> Vec<T> {
+        check_dense(self.ncols, b, ncols);
+        match layout {
+            DenseLayout::ColMajor => mul_columns(self, b, ncols),
+            DenseLayout::RowMajor => {
+                let mut c = vec![T::zero(); self.nrows * ncols];
+                for (i, c_row) in c.chunks_exact_mut(ncols.max(1)).enumerate() {
+                    let (start, end) = self.get_row_range(i);
+                    for p in start..end {
+                        let j = self.colind[p];
+                        axpy_row(self.values[p], &b[j * ncols..(j + 1) * ncols], c_row);
+                    }
+                }
+                c
+            }
+        }
+    }
+}
+
+impl<T: Scalar> SparseCSC<T> {
+    /// A * B for a dense block B with ncols columns, C in the layout of B.
+    pub fn mul_dense(&self, b: &[T], ncols: usize, layout: DenseLayout) -> Vec<T> {
+        check_dense(self.ncols, b, ncols);
+        match layout {
+            DenseLayout::ColMajor => mul_columns(self, b, ncols),
+            DenseLayout::RowMajor => {
+                let mut c = vec![T::zero(); self.nrows * ncols];
+                for j in 0..self.ncols {
+                    let b_row = &b[j * ncols..(j + 1) * ncols];
+                    let (start, end) = self.get_column_range(j);
+                    for p in start..end {
+                        let i = self.rowind[p];
+                        axpy_row(self.values[p], b_row, &mut c[i * ncols..(i + 1) * ncols]);
+                    }
+                }
+                c
+            }
+        }
+    }
+}
@@ -0,0 +1,363 @@
+use crate::sparse::lu::{LuError, LuFactors, assemble_factors};
+use crate::sparse::refactor::Symbolic;
+use crate::sparse::scalar::Scalar;
+use crate::sparse::sparse_csc::SparseCSC;
+use crate::sparse::triangular::{ReachWork, reach};
+use num_traits::Zero;
+use std::cmp::Ordering;
+
+/*
+    Supernodal left-looking LU
+
+    Same factorization as lu.rs (P * A * Q = L * U, threshold partial
+    pivoting), but columns are taken a panel at a time. Panels are the
+    supernodes of the column etree of A Q (FactorCounts::supernodes),
+    consecutive columns expected to share one pattern below the diagonal.
+
+    L is kept per supernode as a dense column-major block over one row list,
+    the supernode's pivot rows first, then the rows below:
+
+        rows   | 1          |
+               | l  1       |
+               | l  l  1    |
+               | l  l  l    |   one row list for the whole block
+               | l  l  l    |
+
+    For each panel of w columns of A Q:
+        - a DFS per column in the graph of L gives its pattern, the panel
+          works on their union
+        - every earlier supernode S with a pivot row in the pattern updates the
+          panel with dense kernels: a unit lower solve against its diagonal
+          block gives the U rows, then L_below * (those rows) is subtracted
+          from the panel, w columns at a time
+        - the panel is factored densely with pivoting among the rows not yet
+          pivotal, right-looking within the panel
+        - the exact pattern of each column is worked out and the panel is cut
+          into supernodes where the patterns stop nesting, so stored supernodes
+          hold no padding (padding would grow every later pattern)
+
+    The panel lives in a dense n x w array indexed by row, only rows of the
+    current pattern are ever nonzero.
+*/
+
+// wider supernodes are split, this bounds the work array at n * MAX_SUPERNODE_WIDTH
+const MAX_SUPERNODE_WIDTH: usize = 32;
+
+struct SupernodalL<T: Scalar> {
+    // supernode s is steps first[s]..first[s + 1]
+    first: Vec<usize>,
+    // rows of supernode s: rows[rowptr[s]..rowptr[s + 1]], pivot rows first
+    rowptr: Vec<usize>,
+    rows: Vec<usize>,
+    // dense column-major block of supernode s, values[valptr[s]..valptr[s + 1]]
+    valptr: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T: Scalar> SupernodalL<T> {
+    fn width(&self, s: usize) -> usize {
+        self.first[s + 1] - self.first[s]
+    }
+
+    /*
+        Apply supernode s to the panel x (row i at x[i * w..(i + 1) * w]).
+        y holds its pivot rows while they are solved.
+    */
+    fn update_panel(&self, s: usize, x: &mut [T], w: usize, y: &mut Vec<T>) {
+        let rows = &self.rows[self.rowptr[s]..self.rowptr[s + 1]];
+        let block = &self.values[self.valptr[s]..self.valptr[s + 1]];
+        let nr = rows.len();
+        let ws = self.width(s);
+
+        y.clear();
+        for &i in &rows[..ws] {
+            y.extend_from_slice(&x[i * w..(i + 1) * w]);
+        }
+        dense_unit_lower_solve(block, nr, ws, y, w);
+        for (c, &i) in rows[..ws].iter().enumerate() {
+            x[i * w..(i + 1) * w].copy_from_slice(&y[c * w..(c + 1) * w]);
+        }
+
+        for (r, &i) in rows.iter().enumerate().skip(ws) {
+            let xi = &mut x[i * w..(i + 1) * w];
+            for c in 0..ws {
+                let lv = block[c * nr + r];
+                if !lv.is_zero() {
+                    axpy(xi, -lv, &y[c * w..(c + 1) * w]);
+                }
+            }
+        }
+    }
+
+    /*
+        New supernode for panel columns c0..c0 + pivots.len(), rows are its
+        pivot rows then below, the L pattern of its last column.
+    */
+    fn push(&mut self, pivots: &[usize], below: &[usize], x: &[T], w: usize, c0: usize) {
+        let rows_start = self.rows.len();
+        self.rows.extend_from_slice(pivots);
+        self.rows.extend_from_slice(below);
+        let rows = &self.rows[rows_start..];
+        for c in 0..pivots.len() {
+            for (r, &i) in rows.iter().enumerate() {
+                self.values.push(match r.cmp(&c) {
+                    Ordering::Less => T::zero(),
+                    Ordering::Equal => T::one(),
+                    Ordering::Greater => x[i * w + c0 + c],
+                });
+            }
+        }
+        self.first.push(self.first.last().unwrap() + pivots.len());
+        self.rowptr.push(self.rows.len());
+        self.valptr.push(self.values.len());
+    }
+
+    fn into_csc_arrays(self, n: usize) -> (Vec<usize>, Vec<usize>, Vec<T>) {
+        let mut lp = Vec::with_capacity(n + 1);
+        let mut li = Vec::new();
+        let mut lx = Vec::new();
+        for s in 0..self.first.len() - 1 {
+            let rows = &self.rows[self.rowptr[s]..self.rowptr[s + 1]];
+            let block = &self.values[self.valptr[s]..self.valptr[s + 1]];
+            let nr = rows.len();
+            for c in 0..self.width(s) {
+                lp.push(li.len());
+                li.extend_from_slice(&rows[c..]);
+                lx.push(T::one());
+                lx.extend_from_slice(&block[c * nr + c + 1..(c + 1) * nr]);
+            }
+        }
+        lp.push(li.len());
+        (lp, li, lx)
+    }
+}
+
+impl Symbolic {
+    /// Supernodal numeric LU, same result as factor up to rounding.
+    pub fn factor_supernodal<T: Scalar>(&self, a: &SparseCSC<T>) -> Result<LuFactors<T>, LuError> {
+        self.check_pattern(a)?;
+        let n = self.size();
+        let q = &self.q;
+        let thresh = T::Real::from_f64(self.diag_pivot_thresh as f64);
+
+        let mut panels = Vec::new();
+        for bounds in self.counts.supernodes().windows(2) {
+            panels.extend((bounds[0]..bounds[1]).step_by(MAX_SUPERNODE_WIDTH));
+        }
+        panels.push(n);
+
+        let mut l = SupernodalL {
+            first: vec![0],
+            rowptr: vec![0],
+            rows: Vec::new(),
+            valptr: vec![0],
+            values: Vec::new(),
+        };
+        // supernode of each step
+        let mut sup_of = vec![0; n];
+        let mut up = vec![0; n + 1];
+        let mut ui: Vec<usize> = Vec::with_capacity(self.counts.row_counts.iter().sum());
+        let mut ux: Vec<T> = Vec::with_capacity(ui.capacity());
+
+        let mut pinv: Vec<Option<usize>> = vec![None; n];
+        let mut x = vec![T::zero(); n * MAX_SUPERNODE_WIDTH.min(n.max(1))];
+        let mut xi = vec![0; n];
+        let mut work = ReachWork::new(n);
+        let mut mark = vec![0; n];
+        let mut stamp = 0;
+        let mut y = Vec::new();
+        let mut pivot_row = Vec::with_capacity(MAX_SUPERNODE_WIDTH);
+
+        for bounds in panels.windows(2) {
+            let f = bounds[0];
+            let w = bounds[1] - f;
+
+            // exact pattern of each column and their union
+            let mut col_patterns: Vec<Vec<usize>> = Vec::with_capacity(w);
+            let mut pattern = Vec::new();
+            stamp += 1;
+            for &col in &q[f..f + w] {
+                let (start, end) = a.get_column_range(col);
+                // pivot row j of step k has children below it in its supernode
+                let top = reach(
+                    &l.rows,
+                    |j| match pinv[j] {
+                        Some(k) => {
+                            let t = sup_of[k];
+                            (l.rowptr[t] + k - l.first[t] + 1, l.rowptr[t + 1])
+                        }
+                        None => (0, 0),
+                    },
+                    &a.rowind[start..end],
+                    &mut xi,
+                    &mut work,
+                );
+                for &i in &xi[top..n] {
+                    if mark[i] != stamp {
+                        mark[i] = stamp;
+                        pattern.push(i);
+                    }
+                }
+                col_patterns.push(xi[top..n].to_vec());
+            }
+
+            for (c, &col) in q[f..f + w].iter().enumerate() {
+                let (start, end) = a.get_column_range(col);
+                for p in start..end {
+                    x[a.rowind[p] * w + c] = a.values[p];
+                }
+            }
+
+            // earlier supernodes in increasing order, a valid topological order
+            let mut updates: Vec<usize> = pattern
+                .iter()
+                .filter_map(|&j| pinv[j].map(|k| sup_of[k]))
+                .collect();
+            updates.sort_unstable();
+            updates.dedup();
+            for &t in &updates {
+                l.update_panel(t, &mut x, w, &mut y);
+            }
+
+            // dense factorization of the panel, rows outside a column's pattern are zero
+            let mut candidates: Vec<usize> = pattern
+                .iter()
+                .copied()
+                .filter(|&j| pinv[j].is_none())
+                .collect();
+            let mut pivots: Vec<usize> = Vec::with_capacity(w);
+            for c in 0..w {
+                let k = f + c;
+                let col = q[k];
+
+                let mut best = None;
+                let mut max_abs = T::Real::zero();
+                for (t, &i) in candidates.iter().enumerate() {
+                    if x[i * w + c].modulus() > max_abs {
+                        max_abs = x[i * w + c].modulus();
+                        best = Some(t);
+                    }
+                }
+                let Some(mut best) = best else {
+                    return Err(LuError::ZeroPivot(k));
+                };
+                if let Some(t) = candidates.iter().position(|&i| i == col) {
+                    let d = x[col * w + c];
+                    if !d.is_zero() && d.modulus() >= thresh * max_abs {
+                        best = t;
+                    }
+                }
+
+                let ipiv = candidates.swap_remove(best);
+                pinv[ipiv] = Some(k);
+                pivots.push(ipiv);
+                let pivot = x[ipiv * w + c];
+
+                // multipliers stay in column c, the rest of the panel is updated
+                pivot_row.clear();
+                pivot_row.extend_from_slice(&x[ipiv * w + c + 1..(ipiv + 1) * w]);
+                for &i in &candidates {
+                    let lv = x[i * w + c] / pivot;
+                    x[i * w + c] = lv;
+                    if !lv.is_zero() {
+                        axpy(&mut x[i * w + c + 1..(i + 1) * w], -lv, &pivot_row);
+                    }
+                }
+            }
+
+            /*
+                Exact structure of column c: its reach plus the L columns of
+                earlier panel columns whose pivot row it contains. Only these
+                entries are stored, padding would feed the next searches.
+            */
+            let mut l_structs: Vec<Vec<usize>> = Vec::with_capacity(w);
+            for (c, mut col_pattern) in col_patterns.into_iter().enumerate() {
+                let k = f + c;
+                stamp += 1;
+                for &i in &col_pattern {
+                    mark[i] = stamp;
+                }
+                for c0 in 0..c {
+                    if mark[pivots[c0]] == stamp {
+                        for &i in &l_structs[c0] {
+                            if mark[i] != stamp {
+                                mark[i] = stamp;
+                                col_pattern.push(i);
+                            }
+                        }
+                    }
+                }
+
+                up[k] = ui.len();
+                let mut l_struct = Vec::new();
+                for &i in &col_pattern {
+                    match pinv[i] {
+                        Some(t) if t < k => {
+                            ui.push(t);
+                            ux.push(x[i * w + c]);
+                        }
+                        Some(t) if t == k => {}
+                        _ => l_struct.push(i),
+                    }
+                }
+                ui.push(k);
+                ux.push(x[pivots[c] * w + c]);
+                l_structs.push(l_struct);
+            }
+
+            // split the panel where column c + 1 is not column c minus its pivot row
+            let mut c0 = 0;
+            for c in 1..=w {
+                let extends = c < w && {
+                    stamp += 1;
+                    for &i in &l_structs[c - 1] {
+                        mark[i] = stamp;
+                    }
+                    l_structs[c].len() + 1 == l_structs[c - 1].len()
+                        && mark[pivots[c]] == stamp
+                        && l_structs[c].iter().all(|&i| mark[i] == stamp)
+                };
+                if !extends {
+                    let t = l.first.len() - 1;
+                    sup_of[f + c0..f + c].fill(t);
+                    l.push(&pivots[c0..c], &l_structs[c - 1], &x, w, c0);
+                    c0 = c;
+                }
+            }
+
+            for &i in &pattern {
+                x[i * w..(i + 1) * w].fill(T::zero());
+            }
+        }
+        up[n] = ui.len();
+
+        let l_arrays = l.into_csc_arrays(n);
+        Ok(assemble_factors(
+            n,
+            l_arrays,
+            (up, ui, ux),
+            pinv,
+            self.q.clone(),
+        ))
+    }
+}
+
+// solve L11 Y = Y in place, L11 the unit lower ws x ws top of a column-major block, Y row-major ws x w
+fn dense_unit_lower_solve<T: Scalar>(block: &[T], nr: usize, ws: usize, y: &mut [T], w: usize) {
+    for c in 0..ws {
+        let (solved, rest) = y.split_at_mut((c + 1) * w);
+        let yc = &solved[c * w..];
+        for (r, yr) in rest.chunks_exact_mut(w).take(ws - c - 1).enumerate() {
+            let lv = block[c * nr + c + 1 + r];
+            if !lv.is_zero() {
+                axpy(yr, -lv, yc);
+            }
+        }
+    }
+}
+
+fn axpy<T: Scalar>(y: &mut [T], alpha: T, x: &[T]) {
+    for (yi, &xi) in y.iter_mut().zip(x) {
+        *yi += alpha * xi;
+    }
+}
@@ -0,0 +1,246 @@
+use crate::sparse::amd::symmetric_adjacency;
+use crate::sparse::scalar::Scalar;
+use crate::sparse::sparse_csc::SparseCSC;
+
+/*
+    Symbolic analysis: elimination tree, postorder and factor counts
+
+    The elimination tree of a symmetric matrix has parent[j] = row of the
+    first off-diagonal nonzero in column j of its Cholesky factor L. Row i
+    of L is the set of nodes on the tree paths from each k with A[i, k] != 0
+    (k < i) up to i, the "row subtree", so counting how often each node is
+    visited gives the column counts of L without computing L.
+
+    For unsymmetric LU the same is done on A^T A without forming it (the
+    column elimination tree). Every row r of A makes its columns a clique in
+    A^T A, and all of them lie on the tree path from its first column, so
+    that column alone stands in for the whole row. With R the Cholesky
+    factor of A^T A, for any row pivoting P A = L U (George & Ng):
+
+        struct(L) is contained in struct(R^T), struct(U) in struct(R)
+
+    so col_counts bounds the columns of L and row_counts the columns of U.
+    For a symmetric matrix factored without pivoting the counts are exact.
+*/
+
+#[derive(Debug, Clone)]
+pub struct FactorCounts {
+    pub parent: Vec<Option<usize>>,
+    pub post: Vec<usize>,
+    // nonzeros in each column / row of the Cholesky factor, diagonal included
+    pub col_counts: Vec<usize>,
+    pub row_counts: Vec<usize>,
+}
+
+impl FactorCounts {
+    /// Nonzeros of the Cholesky factor, diagonal included.
+    pub fn nnz(&self) -> usize {
+        self.col_counts.iter().sum()
+    }
+
+    /// Predicted fill of an LU bounded by these counts, on top of the nnz of A.
+    pub fn lu_fill(&self, a_nnz: usize) -> usize {
+        // L and U both hold the diagonal
+        (2 * self.nnz() - self.col_counts.len()).saturating_sub(a_nnz)
+    }
+
+    /*
+        Fundamental supernodes: j and j + 1 share one when j + 1 is the parent
+        of j, j is its only child, and column j + 1 of the factor is column j
+        minus its diagonal. Returned like a colptr, supernode s is columns
+        sup[s]..sup[s + 1].
+    */
+    pub fn supernodes(&self) -> Vec<usize> {
+        let n = self.col_counts.len();
+        let mut children = vec![0; n];
+        for p in self.parent.iter().flatten() {
+            children[*p] += 1;
+        }
+
+        let mut sup = vec![0];
+        for (j, &child_count) in children.iter().enumerate().skip(1) {
+            let extends = self.parent[j - 1] == Some(j)
+                && child_count == 1
+                && self.col_counts[j - 1] == self.col_counts[j] + 1;
+            if !extends {
+                sup.push(j);
+            }
+        }
+        if n > 0 {
+            sup.push(n);
+        }
+        sup
+    }
+
+    pub fn print(&self) {
+        println!("Symbolic factorization:");
+        println!("n: {}", self.col_counts.len());
+        println!("nnz(L): {}", self.nnz());
+        println!("parent: {:?}", self.parent);
+        println!("post: {:?}", self.post);
+        println!("col_counts: {:?}", self.col_counts);
+        println!("row_counts: {:?}", self.row_counts);
+    }
+}
+
+impl<T: Scalar> SparseCSC<T> {
+    /// Elimination tree of A + A^T.
+    pub fn etree(&self) -> Vec<Option<usize>> {
+        assert_eq!(self.nrows, self.ncols);
+        let below = lower_neighbours(self);
+        etree(self.ncols, |k, visit| {
+            for &i in &below[k] {
+                visit(i);
+            }
+        })
+    }
+
+    /// Elimination tree of A^T A, A may be rectangular.
+    pub fn column_etree(&self) -> Vec<Option<usize>> {
+        let first = first_column_of_rows(self);
+        etree(self.ncols, |k, visit| {
+            let (start, end) = self.get_column_range(k);
+            for &r in &self.rowind[start..end] {
+                visit(first[r]);
+            }
+        })
+    }
+
+    /// Etree, postorder and Cholesky counts of A + A^T.
+    pub fn symbolic_counts(&self) -> FactorCounts {
+        let parent = self.etree();
+        let below = lower_neighbours(self);
+        factor_counts(parent, |i, visit| {
+            for &k in &below[i] {
+                visit(k);
+            }
+        })
+    }
+
+    /// Column etree, postorder and counts of R, the Cholesky factor of A^T A.
+    pub fn column_symbolic_counts(&self) -> FactorCounts {
+        let parent = self.column_etree();
+        let first = first_column_of_rows(self);
+        factor_counts(parent, |i, visit| {
+            let (start, end) = self.get_column_range(i);
+            for &r in &self.rowind[start..end] {
+                visit(first[r]);
+            }
+        })
+    }
+}
+
+/*
+    Liu's algorithm with path compression. For each k, sources(k, visit)
+    calls visit(i) for nodes i that are connected to k in the graph being
+    eliminated; only i < k matter.
+*/
+fn etree(n: usize, sources: impl Fn(usize, &mut dyn FnMut(usize))) -> Vec<Option<usize>> {
+    let mut parent = vec![None; n];
+    // ancestor[i] = some ancestor of i seen so far, shortcuts the climb
+    let mut ancestor: Vec<Option<usize>> = vec![None; n];
+
+    for k in 0..n {
+        sources(k, &mut |start| {
+            let mut i = start;
+            while i < k {
+                let next = ancestor[i];
+                ancestor[i] = Some(k);
+                match next {
+                    Some(next) => i = next,
+                    None => {
+                        parent[i] = Some(k);
+                        break;
+                    }
+                }
+            }
+        });
+    }
+    parent
+}
+
+/*
+    Row subtree traversal: row i of L holds every node on the paths from the
+    sources k < i up to i. Takes O(nnz(L)).
+*/
+fn factor_counts(
+    parent: Vec<Option<usize>>,
+    sources: impl Fn(usize, &mut dyn FnMut(usize)),
+) -> FactorCounts {
+    let n = parent.len();
+    let mut col_counts = vec![1; n];
+    let mut row_counts = vec![1; n];
+    // mark[j] == i once j has been counted in row i
+    let mut mark = vec![usize::MAX; n];
+
+    for i in 0..n {
+        mark[i] = i;
+        sources(i, &mut |k| {
+            let mut j = k;
+            while j < i && mark[j] != i {
+                mark[j] = i;
+                col_counts[j] += 1;
+                row_counts[i] += 1;
+                j = parent[j].expect("row subtree must reach its row");
+            }
+        });
+    }
+
+    FactorCounts {
+        post: postorder(&parent),
+        parent,
+        col_counts,
+        row_counts,
+    }
+}
+
+/// Postorder of a forest, children before parents, children in increasing order.
+pub fn postorder(parent: &[Option<usize>]) -> Vec<usize> {
+    let n = parent.len();
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); n];
+    let mut roots = Vec::new();
+    for (j, p) in parent.iter().enumerate() {
+        match p {
+            Some(p) => children[*p].push(j),
+            None => roots.push(j),
+        }
+    }
+
+    let mut post = Vec::with_capacity(n);
+    // (node, index of next child to visit)
+    let mut stack: Vec<(usize, usize)> = Vec::new();
+    for root in roots {
+        stack.push((root, 0));
+        while let Some((j, next)) = stack.last_mut() {
+            if let Some(&child) = children[*j].get(*next) {
+                *next += 1;
+                stack.push((child, 0));
+            } else {
+                post.push(*j);
+                stack.pop();
+            }
+        }
+    }
+    post
+}
+
+// for each column k, the nodes i < k adjacent to k in A + A^T
+fn lower_neighbours<T: Scalar>(a: &SparseCSC<T>) -> Vec<Vec<usize>> {
+    let mut adj = symmetric_adjacency(a.ncols, &a.colptr, &a.rowind);
+    for (k, list) in adj.iter_mut().enumerate() {
+        list.retain(|&i| i < k);
+    }
+    adj
+}
+
+// first[r] = smallest column with a nonzero in row r (n for empty rows)
+fn first_column_of_rows<T: Scalar>(a: &SparseCSC<T>) -> Vec<usize> {
+    let mut first = vec![a.ncols; a.nrows];
+    for j in (0..a.ncols).rev() {
+        let (start, end) = a.get_column_range(j);
+        for &r in &a.rowind[start..end] {
+            first[r] = j;
+        }
+    }
+    first
+}
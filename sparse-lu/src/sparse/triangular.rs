@@ -0,0 +1,332 @@
+use crate::sparse::scalar::Scalar;
+use crate::sparse::sparse_csc::SparseCSC;
+use crate::sparse::sparse_csr::SparseCSR;
+
+/*
+    Sparse triangular solves, in place on a dense x.
+
+    Column storage solves by scattering: once x[j] is known, column j updates
+    every x[i] below (lower) or above (upper) it.
+    Row storage solves by dot products: x[i] is finished after reading row i.
+
+    The transpose of a CSC matrix has the same arrays as a CSR matrix, so the
+    transposed solves of one format are the plain solves of the other.
+
+    The matrix must be triangular; entries may be in any order within a
+    column/row, the diagonal is found by index.
+*/
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Diag {
+    Unit,
+    NonUnit,
+}
+
+impl<T: Scalar> SparseCSC<T> {
+    /// Forward substitution, solves L x = b with b passed in x.
+    pub fn lower_solve(&self, x: &mut [T], diag: Diag) {
+        assert_eq!(self.nrows, self.ncols);
+        assert_eq!(x.len(), self.ncols);
+        column_solve(
+            &self.colptr,
+            &self.rowind,
+            &self.values,
+            x,
+            diag,
+            0..self.ncols,
+        );
+    }
+
+    /// Backward substitution, solves U x = b with b passed in x.
+    pub fn upper_solve(&self, x: &mut [T], diag: Diag) {
+        assert_eq!(self.nrows, self.ncols);
+        assert_eq!(x.len(), self.ncols);
+        column_solve(
+            &self.colptr,
+            &self.rowind,
+            &self.values,
+            x,
+            diag,
+            (0..self.ncols).rev(),
+        );
+    }
+
+    /// Solves L^T x = b, L lower triangular.
+    pub fn lower_transpose_solve(&self, x: &mut [T], diag: Diag) {
+        assert_eq!(self.nrows, self.ncols);
+        assert_eq!(x.len(), self.ncols);
+        row_solve(
+            &self.colptr,
+            &self.rowind,
+            &self.values,
+            x,
+            diag,
+            (0..self.ncols).rev(),
+        );
+    }
+
+    /// Solves U^T x = b, U upper triangular.
+    pub fn upper_transpose_solve(&self, x: &mut [T], diag: Diag) {
+        assert_eq!(self.nrows, self.ncols);
+        assert_eq!(x.len(), self.ncols);
+        row_solve(
+            &self.colptr,
+            &self.rowind,
+            &self.values,
+            x,
+            diag,
+            0..self.ncols,
+        );
+    }
+
+    /*
+        x[j] contributes to x[i] iff T[i, j] != 0, so the pattern of x is
+        everything reachable from the pattern of b in the graph of T, and a DFS
+        postorder (reversed) is a valid elimination order. The same code works
+        for either triangle, only the direction of the edges differs.
+    */
+    /// T x = b for a sparse b and T lower or upper triangular, returns the
+    /// pattern of x in topological order and its values. Entries of b with
+    /// the same index are summed.
+    pub fn triangular_solve_sparse(
+        &self,
+        b_pattern: &[usize],
+        b_values: &[T],
+        diag: Diag,
+    ) -> (Vec<usize>, Vec<T>) {
+        assert_eq!(self.nrows, self.ncols);
+        assert_eq!(b_pattern.len(), b_values.len());
+        let n = self.ncols;
+
+        let mut xi = vec![0; n];
+        let mut work = ReachWork::new(n);
+        let top = reach(
+            &self.rowind,
+            |j| self.get_column_range(j),
+            b_pattern,
+            &mut xi,
+            &mut work,
+        );
+
+        let mut x = vec![T::zero(); n];
+        for (&i, &value) in b_pattern.iter().zip(b_values.iter()) {
+            x[i] += value;
+        }
+
+        for &j in &xi[top..n] {
+            let (start, end) = self.get_column_range(j);
+            if diag == Diag::NonUnit {
+                x[j] /= column_diagonal(&self.rowind, &self.values, start, end, j);
+            }
+            let xj = x[j];
+            for p in start..end {
+                let i = self.rowind[p];
+                if i != j {
+                    x[i] -= self.values[p] * xj;
+                }
+            }
+        }
+
+        let pattern = xi[top..n].to_vec();
+        let values = pattern.iter().map(|&i| x[i]).collect();
+        (pattern, values)
+    }
+}
+
+impl<T: Scalar> SparseCSR<T> {
+    /// Forward substitution, solves L x = b with b passed in x.
+    pub fn lower_solve(&self, x: &mut [T], diag: Diag) {
+        assert_eq!(self.nrows, self.ncols);
+        assert_eq!(x.len(), self.nrows);
+        row_solve(
+            &self.rowptr,
+            &self.colind,
+            &self.values,
+            x,
+            diag,
+            0..self.nrows,
+        );
+    }
+
+    /// Backward substitution, solves U x = b with b passed in x.
+    pub fn upper_solve(&self, x: &mut [T], diag: Diag) {
+        assert_eq!(self.nrows, self.ncols);
+        assert_eq!(x.len(), self.nrows);
+        row_solve(
+            &self.rowptr,
+            &self.colind,
+            &self.values,
+            x,
+            diag,
+            (0..self.nrows).rev(),
+        );
+    }
+
+    /// Solves L^T x = b, L lower triangular.
+    pub fn lower_transpose_solve(&self, x: &mut [T], diag: Diag) {
+        assert_eq!(self.nrows, self.ncols);
+        assert_eq!(x.len(), self.nrows);
+        column_solve(
+            &self.rowptr,
+            &self.colind,
+            &self.values,
+            x,
+            diag,
+            (0..self.nrows).rev(),
+        );
+    }
+
+    /// Solves U^T x = b, U upper triangular.
+    pub fn upper_transpose_solve(&self, x: &mut [T], diag: Diag) {
+        assert_eq!(self.nrows, self.ncols);
+        assert_eq!(x.len(), self.nrows);
+        column_solve(
+            &self.rowptr,
+            &self.colind,
+            &self.values,
+            x,
+            diag,
+            0..self.nrows,
+        );
+    }
+}
+
+// ptr/ind/values describe columns, order must visit column j after every column it depends on
+fn column_solve<T: Scalar>(
+    ptr: &[usize],
+    ind: &[usize],
+    values: &[T],
+    x: &mut [T],
+    diag: Diag,
+    order: impl Iterator<Item = usize>,
+) {
+    for j in order {
+        let (start, end) = (ptr[j], ptr[j + 1]);
+        if diag == Diag::NonUnit {
+            x[j] /= column_diagonal(ind, values, start, end, j);
+        }
+        let xj = x[j];
+        for p in start..end {
+            let i = ind[p];
+            if i != j {
+                x[i] -= values[p] * xj;
+            }
+        }
+    }
+}
+
+// ptr/ind/values describe rows, order must visit row i after every row it depends on
+fn row_solve<T: Scalar>(
+    ptr: &[usize],
+    ind: &[usize],
+    values: &[T],
+    x: &mut [T],
+    diag: Diag,
+    order: impl Iterator<Item = usize>,
+) {
+    for i in order {
+        let mut sum = x[i];
+        let mut d = T::zero();
+        for p in ptr[i]..ptr[i + 1] {
+            let j = ind[p];
+            if j == i {
+                d = values[p];
+            } else {
+                sum -= values[p] * x[j];
+            }
+        }
+        x[i] = match diag {
+            Diag::Unit => sum,
+            Diag::NonUnit => {
+                if d.is_zero() {
+                    panic!("Zero diagonal entry: ({}, {})", i, i);
+                }
+                sum / d
+            }
+        };
+    }
+}
+
+fn column_diagonal<T: Scalar>(
+    ind: &[usize],
+    values: &[T],
+    start: usize,
+    end: usize,
+    j: usize,
+) -> T {
+    let d = (start..end)
+        .find(|&p| ind[p] == j)
+        .map_or(T::zero(), |p| values[p]);
+    if d.is_zero() {
+        panic!("Zero diagonal entry: ({}, {})", j, j);
+    }
+    d
+}
+
+pub(crate) struct ReachWork {
+    marked: Vec<bool>,
+    // next child to visit for each node on the DFS stack
+    next: Vec<usize>,
+    stack: Vec<usize>,
+}
+
+impl ReachWork {
+    pub(crate) fn new(n: usize) -> Self {
+        Self {
+            marked: vec![false; n],
+            next: vec![0; n],
+            stack: Vec::with_capacity(n),
+        }
+    }
+}
+
+/*
+    Nodes reachable from b_pattern in a graph stored like a CSC matrix: the
+    children of node j are ind[children(j).0..children(j).1]. Written into
+    xi[top..n] in topological order, returns top. Leaves work cleared.
+*/
+pub(crate) fn reach(
+    ind: &[usize],
+    children: impl Fn(usize) -> (usize, usize),
+    b_pattern: &[usize],
+    xi: &mut [usize],
+    work: &mut ReachWork,
+) -> usize {
+    let n = xi.len();
+    let mut top = n;
+
+    for &start in b_pattern {
+        if work.marked[start] {
+            continue;
+        }
+        work.marked[start] = true;
+        work.next[start] = children(start).0;
+        work.stack.push(start);
+
+        while let Some(&j) = work.stack.last() {
+            let (_, end) = children(j);
+            let mut descended = false;
+            while work.next[j] < end {
+                let i = ind[work.next[j]];
+                work.next[j] += 1;
+                if !work.marked[i] {
+                    work.marked[i] = true;
+                    work.next[i] = children(i).0;
+                    work.stack.push(i);
+                    descended = true;
+                    break;
+                }
+            }
+            if !descended {
+                work.stack.pop();
+                top -= 1;
+                xi[top] = j;
+            }
+        }
+    }
+
+    for &i in &xi[top..n] {
+        work.marked[i] = false;
+    }
+    top
+}
@@ -1,6 +1,26 @@
+pub mod amd_tests;
+pub mod cholesky_tests;
+pub mod colamd_tests;
 pub mod conversion_tests;
+pub mod fast_decoupled_tests;
+pub mod grid_tests;
+pub mod ilu_tests;
+pub mod krylov_tests;
+pub mod lu_tests;
+pub mod multifrontal_tests;
 pub mod multiplication_tests;
+pub mod permutation_tests;
+pub mod powerflow_tests;
+pub mod psse_tests;
+pub mod qr_tests;
+pub mod rcm_tests;
+pub mod refactor_tests;
 pub mod sparse_coo_tests;
 pub mod sparse_csc_tests;
 pub mod sparse_trait_tests;
+pub mod spgemm_tests;
+pub mod spmm_tests;
+pub mod supernodal_tests;
+pub mod symbolic_tests;
 pub mod test_utils;
+pub mod triangular_tests;
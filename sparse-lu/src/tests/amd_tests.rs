@@ -0,0 +1,82 @@
+use crate::sparse::lu::ColumnOrdering;
+use crate::sparse::scalar::Real;
+use crate::sparse::{
+    sparse_csc::SparseCSC, sparse_csr::SparseCSR, sparse_matrix::SparseMatrixTrait,
+};
+use crate::tests::test_utils::{
+    assert_permutation, dense_diag_dominant, dense_from_f64, dense_grid_laplacian,
+    get_dense_simple, lu_fill, scalar_tests,
+};
+
+fn test_amd_simple<T: Real>() {
+    let perm = SparseCSC::from_dense(get_dense_simple::<T>()).amd();
+    assert_permutation(&perm, 3);
+}
+
+fn test_amd_diagonal<T: Real>() {
+    let dense = dense_from_f64::<T>(&[
+        vec![1.0, 0.0, 0.0],
+        vec![0.0, 2.0, 0.0],
+        vec![0.0, 0.0, 3.0],
+    ]);
+    assert_permutation(&SparseCSC::from_dense(dense).amd(), 3);
+}
+
+fn stress_test_amd_is_permutation<T: Real>() {
+    for density in [0.02, 0.1, 0.5] {
+        let dense = dense_diag_dominant::<T>(60, density);
+        assert_permutation(&SparseCSC::from_dense(dense.clone()).amd(), 60);
+        assert_permutation(&SparseCSR::from_dense(dense).amd(), 60);
+    }
+}
+
+fn test_amd_csc_csr_agree<T: Real>() {
+    // A + A^T is the same graph whichever way A is compressed
+    let dense = dense_diag_dominant::<T>(40, 0.05);
+    assert_eq!(
+        SparseCSC::from_dense(dense.clone()).amd(),
+        SparseCSR::from_dense(dense).amd()
+    );
+}
+
+fn test_amd_arrow<T: Real>() {
+    // node 0 is connected to everything, eliminating it first fills the whole matrix
+    let n = 20;
+    let mut dense = vec![vec![T::zero(); n]; n];
+    for (i, row) in dense.iter_mut().enumerate() {
+        row[i] = T::from_f64(n as f64);
+        row[0] = T::one();
+    }
+    dense[0] = vec![T::one(); n];
+    dense[0][0] = T::from_f64(n as f64);
+    let a = SparseCSC::from_dense(dense);
+
+    let perm = a.amd();
+    assert_permutation(&perm, n);
+    // once only the hub and one leaf are left they tie
+    assert!(perm[n - 2..].contains(&0));
+
+    assert_eq!(lu_fill(&a, ColumnOrdering::Amd), a.nnz());
+    assert_eq!(lu_fill(&a, ColumnOrdering::Natural), n * n);
+}
+
+fn test_amd_reduces_fill_on_grid<T: Real>() {
+    let a = SparseCSC::from_dense(dense_grid_laplacian::<T>(12));
+    let natural = lu_fill(&a, ColumnOrdering::Natural);
+    let amd = lu_fill(&a, ColumnOrdering::Amd);
+    assert!(
+        amd < natural,
+        "amd fill {} >= natural fill {}",
+        amd,
+        natural
+    );
+}
+
+scalar_tests! {
+    fn test_amd_simple;
+    fn test_amd_diagonal;
+    fn stress_test_amd_is_permutation;
+    fn test_amd_csc_csr_agree;
+    fn test_amd_arrow;
+    fn test_amd_reduces_fill_on_grid;
+}
@@ -0,0 +1,160 @@
+use crate::sparse::cholesky::CholeskyError;
+use crate::sparse::scalar::Real;
+use crate::sparse::{sparse_csc::SparseCSC, sparse_matrix::SparseMatrixTrait};
+use crate::tests::test_utils::{
+    assert_dense_close, assert_vec_close, dense_from_f64, dense_grid_laplacian,
+    dense_matrix_multiply, dense_random_floats, dense_symmetric, dense_transpose, scalar_tests,
+    vec_from_f64,
+};
+
+fn lower_triangle<T: Real>(dense: &[Vec<T>]) -> SparseCSC<T> {
+    let lower: Vec<Vec<T>> = dense
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            row.iter()
+                .enumerate()
+                .map(|(j, &v)| if j <= i { v } else { T::zero() })
+                .collect()
+        })
+        .collect();
+    SparseCSC::from_dense(lower)
+}
+
+fn permuted<T: Real>(dense: &[Vec<T>], perm: &[usize]) -> Vec<Vec<T>> {
+    perm.iter()
+        .map(|&i| perm.iter().map(|&j| dense[i][j]).collect())
+        .collect()
+}
+
+fn check_solve<T: Real>(dense: &[Vec<T>], solve: impl Fn(&[T]) -> Vec<T>) {
+    let x = dense_random_floats::<T>(1, dense.len()).remove(0);
+    let column: Vec<Vec<T>> = x.iter().map(|v| vec![*v]).collect();
+    let b: Vec<T> = dense_matrix_multiply(dense, &column)
+        .into_iter()
+        .map(|row| row[0])
+        .collect();
+    assert_vec_close(&solve(&b), &x, 1e-3);
+}
+
+fn stress_test_cholesky<T: Real>() {
+    for dense in [dense_symmetric::<T>(40, 0.1), dense_grid_laplacian::<T>(6)] {
+        let a = lower_triangle(&dense);
+        let factor = a.cholesky().unwrap();
+        let l = factor.l.to_dense();
+
+        // L L^T = A, L lower triangular
+        for (i, row) in l.iter().enumerate() {
+            assert!(row[i + 1..].iter().all(|&v| v.is_zero()));
+        }
+        let product = dense_matrix_multiply(&l, &dense_transpose(&l));
+        assert_dense_close(&product, &dense, 1e-3);
+        check_solve(&dense, |b| factor.solve(b));
+    }
+}
+
+fn test_cholesky_fill_matches_counts<T: Real>() {
+    let dense = dense_grid_laplacian::<T>(5);
+    let a = lower_triangle(&dense);
+    let factor = a.cholesky().unwrap();
+    assert_eq!(factor.l.nnz(), a.symbolic_counts().nnz());
+}
+
+fn test_cholesky_with_perm<T: Real>() {
+    let dense = dense_grid_laplacian::<T>(7);
+    let a = lower_triangle(&dense);
+    let perm = a.amd();
+    let factor = a.cholesky_with_perm(&perm).unwrap();
+
+    let l = factor.l.to_dense();
+    let product = dense_matrix_multiply(&l, &dense_transpose(&l));
+    assert_dense_close(&product, &permuted(&dense, &perm), 1e-3);
+    assert!(factor.l.nnz() < a.cholesky().unwrap().l.nnz());
+    check_solve(&dense, |b| factor.solve(b));
+}
+
+fn test_cholesky_ignores_upper_triangle<T: Real>() {
+    let dense = dense_symmetric::<T>(20, 0.2);
+    let full = SparseCSC::from_dense(dense.clone());
+    let lower = lower_triangle(&dense);
+    assert_dense_close(
+        &full.cholesky().unwrap().l.to_dense(),
+        &lower.cholesky().unwrap().l.to_dense(),
+        0.0,
+    );
+}
+
+fn test_cholesky_not_positive_definite<T: Real>() {
+    let a = lower_triangle(&[vec![1.0, 2.0], vec![2.0, 1.0]]);
+    assert_eq!(
+        a.cholesky().unwrap_err(),
+        CholeskyError::NotPositiveDefinite(1)
+    );
+
+    let a = SparseCSC::from_dense(dense_from_f64::<T>(&[
+        vec![1.0, 0.0, 0.0],
+        vec![0.0, 1.0, 0.0],
+    ]));
+    assert_eq!(a.cholesky().unwrap_err(), CholeskyError::NotSquare(2, 3));
+
+    let a = lower_triangle(&dense_from_f64::<T>(&[vec![4.0, 2.0], vec![2.0, f64::NAN]]));
+    assert_eq!(
+        a.cholesky().unwrap_err(),
+        CholeskyError::NotPositiveDefinite(1)
+    );
+}
+
+fn test_cholesky_invalid_ordering<T: Real>() {
+    let a = lower_triangle(&dense_from_f64::<T>(&[vec![4.0, 2.0], vec![2.0, 3.0]]));
+    for perm in [vec![0, 0], vec![0], vec![1, 2]] {
+        assert_eq!(
+            a.cholesky_with_perm(&perm).unwrap_err(),
+            CholeskyError::InvalidOrdering(2)
+        );
+        assert_eq!(
+            a.ldlt_with_perm(&perm).unwrap_err(),
+            CholeskyError::InvalidOrdering(2)
+        );
+    }
+}
+
+fn stress_test_ldlt<T: Real>() {
+    let dense = dense_symmetric::<T>(30, 0.15);
+    let a = lower_triangle(&dense);
+    let perm = a.amd();
+    let factors = a.ldlt_with_perm(&perm).unwrap();
+
+    // L D L^T = P A P^T with a unit L
+    let mut ld = factors.l.to_dense();
+    for (i, row) in ld.iter_mut().enumerate() {
+        assert_eq!(row[i], T::one());
+        for (value, d) in row.iter_mut().zip(&factors.d) {
+            *value *= *d;
+        }
+    }
+    let product = dense_matrix_multiply(&ld, &dense_transpose(&factors.l.to_dense()));
+    assert_dense_close(&product, &permuted(&dense, &perm), 1e-3);
+    check_solve(&dense, |b| factors.solve(b));
+}
+
+fn test_ldlt_indefinite<T: Real>() {
+    // not positive definite, but LDL^T needs no square roots
+    let dense = dense_from_f64::<T>(&[vec![1.0, 2.0], vec![2.0, 1.0]]);
+    let factors = lower_triangle(&dense).ldlt().unwrap();
+    assert_vec_close(&factors.d, &vec_from_f64::<T>(&[1.0, -3.0]), 1e-6);
+    check_solve(&dense, |b| factors.solve(b));
+
+    let singular = lower_triangle(&[vec![1.0, 1.0], vec![1.0, 1.0]]);
+    assert_eq!(singular.ldlt().unwrap_err(), CholeskyError::ZeroPivot(1));
+}
+
+scalar_tests! {
+    fn stress_test_cholesky;
+    fn test_cholesky_fill_matches_counts;
+    fn test_cholesky_with_perm;
+    fn test_cholesky_ignores_upper_triangle;
+    fn test_cholesky_not_positive_definite;
+    fn test_cholesky_invalid_ordering;
+    fn stress_test_ldlt;
+    fn test_ldlt_indefinite;
+}
@@ -0,0 +1,96 @@
+use crate::sparse::lu::{ColumnOrdering, LuOptions};
+use crate::sparse::scalar::Real;
+use crate::sparse::{sparse_csc::SparseCSC, sparse_matrix::SparseMatrixTrait};
+use crate::tests::test_utils::{
+    assert_dense_close, assert_permutation, dense_diag_dominant, dense_grid_laplacian,
+    dense_matrix_multiply, dense_random_floats, get_dense_simple, lu_fill, scalar_tests,
+};
+
+fn test_colamd_simple<T: Real>() {
+    let perm = SparseCSC::from_dense(get_dense_simple::<T>()).colamd();
+    assert_permutation(&perm, 3);
+}
+
+fn stress_test_colamd_is_permutation<T: Real>() {
+    for density in [0.02, 0.1, 0.5] {
+        let dense = dense_diag_dominant::<T>(60, density);
+        assert_permutation(&SparseCSC::from_dense(dense).colamd(), 60);
+    }
+}
+
+fn test_colamd_rectangular<T: Real>() {
+    let mut dense = dense_diag_dominant::<T>(30, 0.05);
+    dense.extend(dense_diag_dominant::<T>(30, 0.05));
+    let a = SparseCSC::from_dense(dense);
+    assert_eq!(a.size(), (60, 30));
+    assert_permutation(&a.colamd(), 30);
+}
+
+fn test_colamd_empty_and_dense_rows<T: Real>() {
+    let n = 40;
+    let mut dense = dense_diag_dominant::<T>(n, 0.02);
+    // an empty row, and a full row that would make A^T A dense
+    dense.push(vec![T::zero(); n]);
+    dense.push(vec![T::one(); n]);
+    assert_permutation(&SparseCSC::from_dense(dense).colamd(), n);
+}
+
+fn test_colamd_lu<T: Real>() {
+    let dense = dense_random_floats::<T>(25, 25);
+    let options = LuOptions {
+        ordering: ColumnOrdering::Colamd,
+        ..Default::default()
+    };
+    let factors = SparseCSC::from_dense(dense.clone())
+        .lu_with_options(&options)
+        .unwrap();
+
+    let permuted: Vec<Vec<T>> = factors
+        .p
+        .iter()
+        .map(|&i| factors.q.iter().map(|&j| dense[i][j]).collect())
+        .collect();
+    let product = dense_matrix_multiply(&factors.l.to_dense(), &factors.u.to_dense());
+    assert_dense_close(&product, &permuted, 1e-2);
+}
+
+fn test_colamd_reduces_fill_on_grid<T: Real>() {
+    let a = SparseCSC::from_dense(dense_grid_laplacian::<T>(12));
+    let natural = lu_fill(&a, ColumnOrdering::Natural);
+    let colamd = lu_fill(&a, ColumnOrdering::Colamd);
+    assert!(
+        colamd < natural,
+        "colamd fill {} >= natural fill {}",
+        colamd,
+        natural
+    );
+}
+
+fn test_colamd_arrow<T: Real>() {
+    // column 0 and row 0 are full, factoring column 0 first fills everything.
+    // Row 0 alone would make A^T A full, n is large enough for it to be
+    // dropped as a dense row, after which column 0 clearly goes last.
+    let n = 120;
+    let mut dense = vec![vec![T::zero(); n]; n];
+    for (i, row) in dense.iter_mut().enumerate() {
+        row[i] = T::from_f64(n as f64);
+        row[0] = T::one();
+    }
+    dense[0] = vec![T::one(); n];
+    dense[0][0] = T::from_f64(n as f64);
+    let a = SparseCSC::from_dense(dense);
+
+    assert_permutation(&a.colamd(), n);
+    assert_eq!(lu_fill(&a, ColumnOrdering::Colamd), a.nnz());
+    assert_eq!(lu_fill(&a, ColumnOrdering::Natural), n * n);
+}
+
+scalar_tests! {
+    fn test_colamd_simple;
+    fn stress_test_colamd_is_permutation;
+    fn test_colamd_rectangular;
+    fn test_colamd_empty_and_dense_rows;
+    fn test_colamd_lu;
+    fn test_colamd_reduces_fill_on_grid;
+    fn test_colamd_arrow;
+}
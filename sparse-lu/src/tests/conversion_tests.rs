@@ -1,51 +1,91 @@
-use crate::tests::test_utils::get_dense_simple;
+use crate::sparse::scalar::Real;
+use crate::tests::test_utils::{dense_from_f64, get_dense_simple, scalar_tests, vec_from_f64};
 
 use crate::sparse::{
     sparse_coo::SparseCOO, sparse_csc::SparseCSC, sparse_csr::SparseCSR,
     sparse_matrix::SparseMatrixTrait,
 };
 
-#[test]
-fn test_coo_to_csc() {
-    let dense_simple = get_dense_simple();
+fn test_coo_to_csc<T: Real>() {
+    let dense_simple = get_dense_simple::<T>();
     let sparse_coo = SparseCOO::from_dense(dense_simple.clone());
     let sparse_csc = sparse_coo.to_csc();
     assert_eq!(sparse_csc.to_dense(), dense_simple);
 }
 
-#[test]
-fn test_csc_to_coo() {
-    let dense_simple = get_dense_simple();
+fn test_csc_to_coo<T: Real>() {
+    let dense_simple = get_dense_simple::<T>();
     let sparse_csc = SparseCSC::from_dense(dense_simple.clone());
     let sparse_coo = sparse_csc.to_coo();
     assert_eq!(sparse_coo.to_dense(), dense_simple);
 }
 
-#[test]
-fn stress_test_coo_to_csc() {
-    let sparse_coo = SparseCOO::random(30, 32, 0.2);
+fn test_csc_empty_columns<T: Real>() {
+    // an entry after a run of empty columns lands in its own column
+    let dense = dense_from_f64::<T>(&[
+        vec![0.0, 1.0, 0.0, 0.0, 2.0, 0.0],
+        vec![0.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+        vec![0.0, 3.0, 0.0, 0.0, 0.0, 4.0],
+    ]);
+    let sparse_csc = SparseCSC::from_dense(dense.clone());
+    assert_eq!(sparse_csc.colptr, vec![0, 0, 2, 2, 2, 3, 4]);
+    assert_eq!(sparse_csc.to_dense(), dense);
+    assert_eq!(sparse_csc.to_coo().to_dense(), dense);
+}
+
+fn stress_test_coo_to_csc<T: Real>() {
+    let sparse_coo = SparseCOO::<T>::random(30, 32, 0.2);
     let sparse_csc = sparse_coo.to_csc();
     assert_eq!(sparse_csc.to_dense(), sparse_coo.to_dense());
 }
 
-#[test]
-fn stress_test_csc_to_coo() {
-    let sparse_csc = SparseCSC::random(30, 32, 0.2);
+fn stress_test_csc_to_coo<T: Real>() {
+    let sparse_csc = SparseCSC::<T>::random(30, 32, 0.2);
     let sparse_coo = sparse_csc.to_coo();
     assert_eq!(sparse_coo.to_dense(), sparse_csc.to_dense());
 }
 
-#[test]
-fn test_csr_to_coo() {
-    let dense_simple = get_dense_simple();
+fn test_csr_to_coo<T: Real>() {
+    let dense_simple = get_dense_simple::<T>();
     let sparse_csr = SparseCSR::from_dense(dense_simple.clone());
     let sparse_coo = sparse_csr.to_coo();
     assert_eq!(sparse_coo.to_dense(), dense_simple);
 }
 
-#[test]
-fn stress_test_csr_to_coo() {
-    let sparse_csr = SparseCSR::random(30, 32, 0.2);
+fn stress_test_csr_to_coo<T: Real>() {
+    let sparse_csr = SparseCSR::<T>::random(30, 32, 0.2);
     let sparse_coo = sparse_csr.to_coo();
     assert_eq!(sparse_coo.to_dense(), sparse_csr.to_dense());
 }
+
+fn stress_test_csc_to_csr_and_back<T: Real>() {
+    let sparse_csc = SparseCSC::<T>::random(30, 32, 0.2);
+    let sparse_csr = sparse_csc.to_csr();
+    assert_eq!(sparse_csr.to_dense(), sparse_csc.to_dense());
+    assert!((0..sparse_csr.nrows).all(|i| {
+        let (start, end) = sparse_csr.get_row_range(i);
+        sparse_csr.colind[start..end].is_sorted()
+    }));
+    assert_eq!(sparse_csr.to_csc().to_dense(), sparse_csc.to_dense());
+}
+
+fn test_csr_from_unsorted_flat_indices<T: Real>() {
+    // values follow their indices through the sort
+    let sparse_csr =
+        SparseCSR::from_flat_indices(3, 4, vec![11, 1, 6], vec_from_f64::<T>(&[3.0, 1.0, 2.0]));
+    assert_eq!(sparse_csr.rowptr, vec![0, 1, 2, 3]);
+    assert_eq!(sparse_csr.colind, vec![1, 2, 3]);
+    assert_eq!(sparse_csr.values, vec_from_f64::<T>(&[1.0, 2.0, 3.0]));
+}
+
+scalar_tests! {
+    fn test_coo_to_csc;
+    fn test_csc_to_coo;
+    fn test_csc_empty_columns;
+    fn stress_test_coo_to_csc;
+    fn stress_test_csc_to_coo;
+    fn test_csr_to_coo;
+    fn stress_test_csr_to_coo;
+    fn stress_test_csc_to_csr_and_back;
+    fn test_csr_from_unsorted_flat_indices;
+}
@@ -0,0 +1,131 @@
+use crate::grid::case::{BusType, Case, Shunt};
+use crate::grid::fast_decoupled::{FastDecoupledMethod, FastDecoupledResult};
+use crate::grid::powerflow::{PowerFlowError, PowerFlowOptions};
+use crate::sparse::sparse_matrix::SparseMatrixTrait;
+use crate::tests::powerflow_tests::{load_bus_case, raw_case};
+
+const METHODS: [FastDecoupledMethod; 2] = [FastDecoupledMethod::Xb, FastDecoupledMethod::Bx];
+
+// one entry per half iteration plus the starting point, the last P half
+// possibly without its Q half
+fn assert_half_iterations(result: &FastDecoupledResult) {
+    let halves = result.mismatches.len() - 1;
+    assert!(halves == 2 * result.iterations || halves + 1 == 2 * result.iterations);
+}
+
+fn assert_matches_newton(case: &Case, result: &FastDecoupledResult) {
+    let newton = case
+        .newton_power_flow(&PowerFlowOptions::default())
+        .unwrap();
+    for k in 0..case.buses.len() {
+        assert!((result.vm[k] - newton.vm[k]).abs() < 1e-7);
+        assert!((result.va[k] - newton.va[k]).abs() < 1e-5);
+    }
+}
+
+#[test]
+fn test_fast_decoupled_matrices() {
+    // r / x of 0.5, charging and a shunt at the load bus, off-nominal tap
+    let mut case = load_bus_case();
+    case.branches[0].r = 0.05;
+    case.branches[0].b = 0.04;
+    case.branches[0].tap = 1.05;
+    case.shunts.push(Shunt {
+        bus: 2,
+        g: 0.0,
+        b: 10.0,
+        in_service: true,
+    });
+    let full = 0.1 / (0.05 * 0.05 + 0.1 * 0.1);
+
+    // only bus 2 is PQ, so both matrices are 1 x 1
+    let (b_prime, b_double_prime) = case
+        .fast_decoupled_matrices(FastDecoupledMethod::Xb)
+        .unwrap();
+    assert!((b_prime.get(0, 0) - 10.0).abs() < 1e-12);
+    assert!((b_double_prime.get(0, 0) - (full - 0.02 - 0.1)).abs() < 1e-12);
+
+    let (b_prime, b_double_prime) = case
+        .fast_decoupled_matrices(FastDecoupledMethod::Bx)
+        .unwrap();
+    assert!((b_prime.get(0, 0) - full).abs() < 1e-12);
+    assert!((b_double_prime.get(0, 0) - (10.0 - 0.02 - 0.1)).abs() < 1e-12);
+
+    // a PV bus has an angle but no magnitude
+    case.buses[1].bus_type = BusType::Pv;
+    let (b_prime, b_double_prime) = case
+        .fast_decoupled_matrices(FastDecoupledMethod::Xb)
+        .unwrap();
+    assert_eq!((b_prime.nrows, b_double_prime.nrows), (1, 0));
+}
+
+#[test]
+fn test_fast_decoupled_two_bus() {
+    let mut case = load_bus_case();
+    case.branches[0].r = 0.02;
+    for method in METHODS {
+        let result = case
+            .fast_decoupled_power_flow(method, &PowerFlowOptions::default())
+            .unwrap();
+        assert!(result.converged, "{:?}", method);
+        assert_half_iterations(&result);
+        assert_matches_newton(&case, &result);
+    }
+}
+
+#[test]
+fn test_fast_decoupled_errors() {
+    let mut case = load_bus_case();
+    case.buses[0].bus_type = BusType::Pq;
+    let err = case
+        .fast_decoupled_power_flow(FastDecoupledMethod::Xb, &PowerFlowOptions::default())
+        .unwrap_err();
+    assert_eq!(err, PowerFlowError::NoSlack);
+
+    let options = PowerFlowOptions {
+        max_iter: 3,
+        ..Default::default()
+    };
+    let result = load_bus_case()
+        .fast_decoupled_power_flow(FastDecoupledMethod::Bx, &options)
+        .unwrap();
+    assert!(!result.converged);
+    assert_eq!(result.iterations, 3);
+    assert_eq!(result.mismatches.len(), 7);
+}
+
+#[test]
+fn test_fast_decoupled_hawaii() {
+    let case = raw_case("Hawaii40_20231026");
+    for method in METHODS {
+        let result = case
+            .fast_decoupled_power_flow(method, &PowerFlowOptions::default())
+            .unwrap();
+        assert!(result.converged, "{:?}", method);
+        assert!(result.iterations <= 15);
+        assert_half_iterations(&result);
+
+        // the first P half cuts the P mismatch, the Q half after it the Q
+        // mismatch the angle change left
+        let (p0, _) = result.mismatches[0];
+        let (p1, q1) = result.mismatches[1];
+        let (_, q2) = result.mismatches[2];
+        assert!(p1 < 0.1 * p0 && q2 < 0.2 * q1);
+
+        assert_matches_newton(&case, &result);
+    }
+}
+
+#[test]
+fn test_fast_decoupled_texas() {
+    let case = raw_case("Texas7k_20210804");
+    for method in METHODS {
+        let result = case
+            .fast_decoupled_power_flow(method, &PowerFlowOptions::default())
+            .unwrap();
+        assert!(result.converged, "{:?}", method);
+        assert!(result.iterations <= 20);
+        assert_half_iterations(&result);
+        assert_matches_newton(&case, &result);
+    }
+}
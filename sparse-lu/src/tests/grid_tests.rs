@@ -0,0 +1,249 @@
+use crate::grid::case::{Branch, Bus, BusType, Case, CaseError, Shunt};
+use crate::sparse::sparse_matrix::SparseMatrixTrait;
+use crate::tests::test_utils::data_path;
+use num_complex::Complex;
+use std::fs;
+
+// the CSVs of a case under data/ as they are, read_dir refuses their islands
+pub fn csv_case(name: &str) -> Case {
+    let read = |file: &str| fs::read_to_string(data_path(name).join(file)).unwrap();
+    Case::from_csv(
+        &read("bus.csv"),
+        &read("branch.csv"),
+        &read("shunt.csv"),
+        &read("load.csv"),
+        &read("gen.csv"),
+    )
+    .unwrap()
+}
+
+fn bus(number: usize) -> Bus {
+    Bus {
+        number,
+        name: format!("BUS{}", number),
+        base_kv: 138.0,
+        bus_type: BusType::Pq,
+        vm: 1.0,
+        va: 0.0,
+    }
+}
+
+fn branch(from: usize, to: usize, r: f64, x: f64, b: f64) -> Branch {
+    Branch {
+        from,
+        to,
+        circuit: "1".to_string(),
+        r,
+        x,
+        b,
+        tap: 1.0,
+        shift: 0.0,
+        in_service: true,
+    }
+}
+
+fn two_bus_case(branch: Branch) -> Case {
+    Case {
+        base_mva: 100.0,
+        buses: vec![bus(10), bus(20)],
+        branches: vec![branch],
+        shunts: vec![],
+        loads: vec![],
+        generators: vec![],
+    }
+}
+
+fn assert_complex_close(a: Complex<f64>, b: Complex<f64>) {
+    assert!((a - b).norm() < 1e-10, "{} vs {}", a, b);
+}
+
+fn ybus_times(case: &Case, v: &[Complex<f64>]) -> Vec<Complex<f64>> {
+    case.ybus()
+        .to_dense()
+        .iter()
+        .map(|row| row.iter().zip(v).map(|(&y, &v)| y * v).sum())
+        .collect()
+}
+
+#[test]
+fn test_ybus_line() {
+    let case = two_bus_case(branch(20, 10, 0.01, 0.1, 0.04));
+    let ybus = case.ybus();
+    let y = Complex::new(0.01, 0.1).inv();
+    let charging = Complex::new(0.0, 0.02);
+
+    assert_eq!(ybus.nnz(), 4);
+    assert_complex_close(ybus.get(0, 0), y + charging);
+    assert_complex_close(ybus.get(1, 1), y + charging);
+    assert_complex_close(ybus.get(0, 1), -y);
+    assert_complex_close(ybus.get(1, 0), -y);
+}
+
+#[test]
+fn test_ybus_transformer() {
+    let mut transformer = branch(10, 20, 0.0, 0.05, 0.0);
+    transformer.tap = 1.05;
+    transformer.shift = 30.0;
+    let case = two_bus_case(transformer);
+    let ybus = case.ybus();
+    assert_ne!(ybus.get(0, 1), ybus.get(1, 0));
+
+    // no current flows when Vf is exactly t Vt
+    let t = Complex::from_polar(1.05, 30f64.to_radians());
+    let vt = Complex::from_polar(0.98, -0.2);
+    for current in ybus_times(&case, &[t * vt, vt]) {
+        assert_complex_close(current, Complex::new(0.0, 0.0));
+    }
+}
+
+#[test]
+fn test_ybus_status_and_shunts() {
+    let mut case = two_bus_case(branch(10, 20, 0.01, 0.1, 0.0));
+    let mut parallel = branch(10, 20, 0.02, 0.2, 0.0);
+    parallel.in_service = false;
+    case.branches.push(parallel);
+    case.shunts = vec![
+        Shunt {
+            bus: 20,
+            g: 5.0,
+            b: 20.0,
+            in_service: true,
+        },
+        Shunt {
+            bus: 10,
+            g: 0.0,
+            b: 50.0,
+            in_service: false,
+        },
+    ];
+
+    let ybus = case.ybus();
+    let y = Complex::new(0.01, 0.1).inv();
+    assert_complex_close(ybus.get(0, 0), y);
+    assert_complex_close(ybus.get(0, 1), -y);
+    assert_complex_close(ybus.get(1, 1), y + Complex::new(0.05, 0.2));
+}
+
+#[test]
+fn test_read_hawaii() {
+    let case = csv_case("Hawaii40_20231026");
+    assert_eq!(case.buses.len(), 37);
+    assert_eq!(case.branches.len(), 77);
+    assert!(case.shunts.is_empty());
+    let slack = case
+        .buses
+        .iter()
+        .filter(|bus| bus.bus_type == BusType::Slack);
+    assert_eq!(slack.count(), 1);
+    assert_eq!(case.buses[0].name, "ALOHA138");
+    assert!((case.branches[0].b - 8.46e-3).abs() < 1e-12);
+    assert_eq!((case.loads.len(), case.generators.len()), (27, 45));
+    assert_eq!((case.loads[0].bus, case.loads[0].pd), (2, 60.7));
+    let generator = &case.generators[0];
+    assert_eq!((generator.qmax, generator.qmin), (0.8, -0.5));
+    assert!(generator.in_service);
+
+    let ybus = case.ybus();
+    assert_eq!(ybus.size(), (37, 37));
+    let dense = ybus.to_dense();
+    for (i, row) in dense.iter().enumerate() {
+        for (j, &value) in row.iter().enumerate() {
+            assert_eq!(value, dense[j][i]);
+        }
+    }
+
+    // at a flat start only the line charging draws current
+    let mut charging = vec![Complex::new(0.0, 0.0); 37];
+    let index = case.bus_index();
+    for branch in &case.branches {
+        charging[index[&branch.from]] += Complex::new(0.0, branch.b / 2.0);
+        charging[index[&branch.to]] += Complex::new(0.0, branch.b / 2.0);
+    }
+    let flat = vec![Complex::new(1.0, 0.0); 37];
+    for (current, expected) in ybus_times(&case, &flat).into_iter().zip(charging) {
+        assert_complex_close(current, expected);
+    }
+}
+
+#[test]
+fn test_read_texas_shunts() {
+    let case = csv_case("Texas7k_20210804");
+    assert_eq!(case.buses.len(), 6717);
+    assert_eq!(case.branches.len(), 7173);
+    assert_eq!(case.shunts.len(), 205);
+
+    let shunt = &case.shunts[0];
+    assert_eq!((shunt.bus, shunt.g, shunt.b), (110126, 0.0, 38.42));
+    let k = case.bus_index()[&shunt.bus];
+    let without_shunts = Case {
+        shunts: vec![],
+        ..case.clone()
+    };
+    assert_complex_close(
+        case.ybus().get(k, k) - without_shunts.ybus().get(k, k),
+        Complex::new(0.0, 0.3842),
+    );
+}
+
+#[test]
+fn test_case_errors() {
+    let bus = "BusNum,Name,BaseKV,Type,Area,Zone,Owner,Vm,Va\n\
+               1,A,138.0,3,1,1,1,1.0,0.0\n\
+               2,B,138.0,1,1,1,1,1.0,-2.5\n";
+    let branch = "FromBus,ToBus,CircuitID,R,X,B,RateA,RateB,RateC,GI,BI,GJ,BJ,Status\n\
+                  1,2,1,0.01,0.1,0.02,0,0,0,0,0,0,0,1\n";
+    let shunt = "BusNum,ID,Status,G,B\n";
+    let load = "BusNum,ID,Status,Area,Zone,Pd,Qd\n2,1,1,1,1,50.0,10.0\n";
+    let generator = "BusNum,ID,Pg,Qg,Qmax,Qmin,Vset,RegBus,MBase,Status\n\
+                     1,1,50.0,5.0,30.0,-30.0,1.0,0,100.0,1\n";
+    let from_csv =
+        |bus: &str, branch: &str, shunt: &str| Case::from_csv(bus, branch, shunt, load, generator);
+    let case = from_csv(bus, branch, shunt).unwrap();
+    assert_eq!(case.buses[1].va, -2.5);
+    assert_eq!((case.loads[0].pd, case.generators[0].pg), (50.0, 50.0));
+
+    let bad_value = bus.replace("-2.5", "-2.5x");
+    let err = from_csv(&bad_value, branch, shunt).unwrap_err();
+    assert!(matches!(err, CaseError::Parse("bus.csv", 3, "Va", ref text) if text == "-2.5x"));
+    assert_eq!(err.to_string(), "bus.csv line 3: invalid Va \"-2.5x\"");
+
+    let bad_type = bus.replace(",3,", ",7,");
+    let err = from_csv(&bad_type, branch, shunt).unwrap_err();
+    assert!(matches!(err, CaseError::Parse("bus.csv", 2, "Type", _)));
+
+    let unknown = branch.replace("1,2,1,", "1,3,1,");
+    let err = from_csv(bus, &unknown, shunt).unwrap_err();
+    assert!(matches!(err, CaseError::UnknownBus("branch.csv", 2, 3)));
+
+    let err = from_csv(bus, branch, "BusNum,G,B\n").unwrap_err();
+    assert!(matches!(
+        err,
+        CaseError::MissingColumn("shunt.csv", "Status")
+    ));
+
+    let unknown_load = load.replace("2,1,1,", "9,1,1,");
+    let err = Case::from_csv(bus, branch, shunt, &unknown_load, generator).unwrap_err();
+    assert!(matches!(err, CaseError::UnknownBus("load.csv", 2, 9)));
+
+    let err = Case::read_dir(data_path("missing")).unwrap_err();
+    assert!(matches!(err, CaseError::Io(..)));
+}
+
+#[test]
+fn test_islands() {
+    let mut case = two_bus_case(branch(10, 20, 0.01, 0.1, 0.0));
+    assert_eq!(case.islands(), vec![vec![0, 1]]);
+
+    case.branches[0].in_service = false;
+    assert_eq!(case.islands(), vec![vec![0], vec![1]]);
+
+    case.buses[1].bus_type = BusType::Isolated;
+    assert_eq!(case.islands(), vec![vec![0]]);
+
+    // without transformers the shipped CSVs split into islands without a slack bus
+    for (name, islands) in [("Hawaii40_20231026", 2), ("Texas7k_20210804", 1094)] {
+        assert_eq!(csv_case(name).islands().len(), islands);
+        let err = Case::read_dir(data_path(name)).unwrap_err();
+        assert!(matches!(err, CaseError::Islanded(n) if n == islands));
+    }
+}
@@ -0,0 +1,131 @@
+use crate::sparse::lu::LuError;
+use crate::sparse::scalar::Real;
+use crate::sparse::{
+    sparse_csc::SparseCSC, sparse_csr::SparseCSR, sparse_matrix::SparseMatrixTrait,
+};
+use crate::tests::test_utils::{
+    assert_dense_close, assert_lu_factors, dense_diag_dominant, dense_from_f64,
+    dense_grid_laplacian, dense_matrix_multiply, scalar_tests,
+};
+
+fn dense_tridiagonal<T: Real>(n: usize) -> Vec<Vec<T>> {
+    let mut dense = vec![vec![T::zero(); n]; n];
+    for i in 0..n {
+        dense[i][i] = T::from_f64(3.0 + (i % 3) as f64);
+        if i + 1 < n {
+            dense[i][i + 1] = T::from_f64(-1.0);
+            dense[i + 1][i] = T::from_f64(0.5 * (i % 4) as f64 - 1.0);
+        }
+    }
+    dense
+}
+
+fn residual_norm<T: Real>(dense: &[Vec<T>], x: &[T], b: &[T]) -> T {
+    dense
+        .iter()
+        .zip(b)
+        .map(|(row, &bi)| row.iter().zip(x).map(|(&a, &xj)| a * xj).sum::<T>() - bi)
+        .map(|r| r * r)
+        .sum::<T>()
+        .sqrt()
+}
+
+fn test_ilu0_is_exact_without_fill<T: Real>() {
+    // no fill for a tridiagonal matrix, so ILU(0) is the full LU
+    let dense = dense_tridiagonal::<T>(40);
+    let factors = SparseCSR::from_dense(dense.clone()).ilu0().unwrap();
+    assert_lu_factors(&dense, &factors);
+}
+
+fn test_ilu0_keeps_pattern<T: Real>() {
+    let dense = dense_grid_laplacian::<T>(6);
+    let factors = SparseCSC::from_dense(dense.clone()).ilu0().unwrap();
+    let l = factors.l.to_dense();
+    let u = factors.u.to_dense();
+    let product = dense_matrix_multiply(&l, &u);
+
+    for i in 0..dense.len() {
+        for j in 0..dense.len() {
+            if dense[i][j].is_zero() {
+                assert_eq!(if i > j { l[i][j] } else { u[i][j] }, T::zero());
+            } else {
+                // L U matches A on its pattern
+                assert!((product[i][j] - dense[i][j]).abs() < T::from_f64(1e-4));
+            }
+        }
+    }
+}
+
+fn test_ilu_csr_and_csc_agree<T: Real>() {
+    let dense = dense_diag_dominant::<T>(30, 0.2);
+    let csr = SparseCSR::from_dense(dense.clone());
+    let csc = SparseCSC::from_dense(dense);
+
+    let (a, b) = (csr.ilu0().unwrap(), csc.ilu0().unwrap());
+    assert_dense_close(&a.l.to_dense(), &b.l.to_dense(), 1e-6);
+    assert_dense_close(&a.u.to_dense(), &b.u.to_dense(), 1e-6);
+
+    let (a, b) = (csr.ilut(1e-2, 5).unwrap(), csc.ilut(1e-2, 5).unwrap());
+    assert_dense_close(&a.l.to_dense(), &b.l.to_dense(), 1e-6);
+    assert_dense_close(&a.u.to_dense(), &b.u.to_dense(), 1e-6);
+}
+
+fn test_ilut_without_dropping_is_lu<T: Real>() {
+    let dense = dense_diag_dominant::<T>(30, 0.15);
+    let factors = SparseCSC::from_dense(dense.clone()).ilut(0.0, 30).unwrap();
+    assert_lu_factors(&dense, &factors);
+}
+
+fn test_ilut_max_fill<T: Real>() {
+    let dense = dense_diag_dominant::<T>(40, 0.3);
+    let max_fill = 3;
+    let factors = SparseCSR::from_dense(dense).ilut(1e-3, max_fill).unwrap();
+    let l = factors.l.to_csr();
+    let u = factors.u.to_csr();
+    for i in 0..40 {
+        // plus the diagonal
+        assert!(l.num_nnz_in_row(i) <= max_fill + 1);
+        assert!(u.num_nnz_in_row(i) <= max_fill + 1);
+        assert_eq!(l.get(i, i), T::one());
+    }
+}
+
+fn test_ilu_preconditions<T: Real>() {
+    // one preconditioned step, x = M^-1 b, already beats x = 0
+    let dense = dense_grid_laplacian::<T>(10);
+    let a = SparseCSR::from_dense(dense.clone());
+    let b: Vec<T> = (0..100)
+        .map(|i| T::from_f64(1.0 + (i % 7) as f64))
+        .collect();
+    let b_norm = residual_norm(&dense, &[T::zero(); 100], &b);
+
+    let ilu0 = residual_norm(&dense, &a.ilu0().unwrap().solve(&b), &b);
+    let ilut = residual_norm(&dense, &a.ilut(1e-3, 10).unwrap().solve(&b), &b);
+    assert!(ilu0 < b_norm, "{:?} vs {:?}", ilu0, b_norm);
+    assert!(ilut < ilu0, "{:?} vs {:?}", ilut, ilu0);
+}
+
+fn test_ilu_errors<T: Real>() {
+    let rectangular = SparseCSR::<T>::random(4, 5, 0.5);
+    assert_eq!(rectangular.ilu0().unwrap_err(), LuError::NotSquare(4, 5));
+
+    let dense = dense_from_f64::<T>(&[
+        vec![2.0, 1.0, 0.0],
+        vec![1.0, 0.0, 1.0],
+        vec![0.0, 1.0, 2.0],
+    ]);
+    let a = SparseCSC::from_dense(dense);
+    assert_eq!(a.ilu0().unwrap_err(), LuError::ZeroPivot(1));
+    // the update 0 - 1/2 * 1 fills the diagonal in
+    assert!(a.ilut(0.0, 3).is_ok());
+}
+
+scalar_tests! {
+    fn test_ilu0_is_exact_without_fill;
+    fn test_ilu0_keeps_pattern;
+    fn test_ilu_csr_and_csc_agree;
+    fn test_ilut_without_dropping_is_lu;
+    fn test_ilut_max_fill;
+    fn test_ilu_preconditions;
+    fn test_ilu_errors;
+}
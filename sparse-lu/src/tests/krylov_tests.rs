@@ -0,0 +1,131 @@
+use crate::sparse::krylov::{
+    IterativeOptions, IterativeResult, LinearOperator, bicgstab, cg, gmres,
+};
+use crate::sparse::scalar::Real;
+use crate::sparse::{
+    sparse_csc::SparseCSC, sparse_csr::SparseCSR, sparse_matrix::SparseMatrixTrait,
+};
+use crate::tests::test_utils::{
+    assert_vec_close, dense_diag_dominant, dense_grid_laplacian, dense_random_floats, scalar_tests,
+};
+
+// b = A x for a random x
+fn rhs<T: Real>(a: &impl LinearOperator<T>) -> (Vec<T>, Vec<T>) {
+    let x = dense_random_floats::<T>(1, a.size()).remove(0);
+    let mut b = vec![T::zero(); a.size()];
+    a.apply(&x, &mut b);
+    (x, b)
+}
+
+fn assert_converged<T: Real>(result: &IterativeResult<T>, options: &IterativeOptions, b_norm: T) {
+    assert!(result.converged, "{:?}", result.residuals);
+    assert_eq!(result.residuals.len(), result.iterations + 1);
+    assert!((result.residuals[0] - b_norm).abs() <= T::from_f64(1e-4) * b_norm);
+    assert!(*result.residuals.last().unwrap() <= T::from_f64(options.tol as f64) * b_norm);
+}
+
+// nonsymmetric: diagonally dominant with a convection-like skew
+fn dense_nonsymmetric<T: Real>(n: usize) -> Vec<Vec<T>> {
+    let mut dense = dense_diag_dominant::<T>(n, 0.05);
+    for i in 0..n - 1 {
+        dense[i][i + 1] -= T::from_f64(0.3 * n as f64);
+        dense[i + 1][i] += T::from_f64(0.1 * n as f64);
+    }
+    dense
+}
+
+fn test_cg<T: Real>() {
+    let a = SparseCSR::from_dense(dense_grid_laplacian::<T>(12));
+    let (x, b) = rhs(&a);
+    let options = IterativeOptions::default();
+    let b_norm = b.iter().map(|&v| v * v).sum::<T>().sqrt();
+
+    let plain = cg(&a, &b, None, &options);
+    assert_converged(&plain, &options, b_norm);
+    assert_vec_close(&plain.x, &x, 1e-3);
+
+    let ilu = a.ilu0().unwrap();
+    let preconditioned = cg(&a, &b, Some(&ilu), &options);
+    assert_converged(&preconditioned, &options, b_norm);
+    assert_vec_close(&preconditioned.x, &x, 1e-3);
+    assert!(preconditioned.iterations < plain.iterations);
+}
+
+fn test_bicgstab<T: Real>() {
+    let a = SparseCSR::from_dense(dense_nonsymmetric::<T>(80));
+    let (x, b) = rhs(&a);
+    let options = IterativeOptions::default();
+    let b_norm = b.iter().map(|&v| v * v).sum::<T>().sqrt();
+
+    let plain = bicgstab(&a, &b, None, &options);
+    assert_converged(&plain, &options, b_norm);
+    assert_vec_close(&plain.x, &x, 1e-3);
+
+    let ilut = a.ilut(1e-3, 10).unwrap();
+    let preconditioned = bicgstab(&a, &b, Some(&ilut), &options);
+    assert_converged(&preconditioned, &options, b_norm);
+    assert_vec_close(&preconditioned.x, &x, 1e-3);
+    assert!(preconditioned.iterations <= plain.iterations);
+}
+
+fn test_gmres_restarted<T: Real>() {
+    let a = SparseCSR::from_dense(dense_nonsymmetric::<T>(80));
+    let (x, b) = rhs(&a);
+    let options = IterativeOptions {
+        restart: 5,
+        ..Default::default()
+    };
+    let b_norm = b.iter().map(|&v| v * v).sum::<T>().sqrt();
+
+    let plain = gmres(&a, &b, None, &options);
+    assert_converged(&plain, &options, b_norm);
+    assert_vec_close(&plain.x, &x, 1e-3);
+    // GMRES minimizes the residual within each cycle
+    for cycle in plain.residuals.chunks(options.restart) {
+        assert!(cycle.is_sorted_by(|r0, r1| *r1 <= *r0 * T::from_f64(1.001)));
+    }
+
+    let ilu = a.ilu0().unwrap();
+    let preconditioned = gmres(&a, &b, Some(&ilu), &options);
+    assert_converged(&preconditioned, &options, b_norm);
+    assert_vec_close(&preconditioned.x, &x, 1e-3);
+    assert!(preconditioned.iterations < plain.iterations);
+}
+
+fn test_csc_operator<T: Real>() {
+    let dense = dense_grid_laplacian::<T>(6);
+    let csc = SparseCSC::from_dense(dense.clone());
+    let csr = SparseCSR::from_dense(dense);
+    let (x, b) = rhs(&csr);
+    let options = IterativeOptions::default();
+
+    assert_vec_close(&cg(&csc, &b, None, &options).x, &x, 1e-3);
+    assert_vec_close(&gmres(&csc, &b, None, &options).x, &x, 1e-3);
+    assert_vec_close(&bicgstab(&csc, &b, None, &options).x, &x, 1e-3);
+}
+
+fn test_max_iter<T: Real>() {
+    let a = SparseCSR::from_dense(dense_grid_laplacian::<T>(12));
+    let (_, b) = rhs(&a);
+    let options = IterativeOptions {
+        max_iter: 3,
+        ..Default::default()
+    };
+    for result in [
+        cg(&a, &b, None, &options),
+        bicgstab(&a, &b, None, &options),
+        gmres(&a, &b, None, &options),
+    ] {
+        assert!(!result.converged);
+        assert_eq!(result.iterations, 3);
+        assert_eq!(result.residuals.len(), 4);
+    }
+}
+
+scalar_tests! {
+    fn test_cg;
+    fn test_bicgstab;
+    fn test_gmres_restarted;
+    fn test_csc_operator;
+    fn test_max_iter;
+}
@@ -0,0 +1,248 @@
+use crate::sparse::lu::{ColumnOrdering, LuError, LuOptions};
+use crate::sparse::scalar::Real;
+use crate::sparse::{sparse_csc::SparseCSC, sparse_matrix::SparseMatrixTrait};
+use crate::tests::test_utils::{
+    assert_dense_close, assert_lu_factors, assert_vec_close, dense_diag_dominant, dense_from_f64,
+    dense_matrix_multiply, dense_random_floats, dense_transpose, get_dense_simple, scalar_tests,
+    vec_from_f64,
+};
+use num_complex::Complex;
+
+fn test_lu_reconstructs<T: Real>(dense: Vec<Vec<T>>, options: &LuOptions) {
+    let a = SparseCSC::from_dense(dense.clone());
+    let factors = a.lu_with_options(options).unwrap();
+    let (l, u, p, q) = (&factors.l, &factors.u, &factors.p, &factors.q);
+
+    let l_dense = l.to_dense();
+    let u_dense = u.to_dense();
+
+    // L unit lower triangular, U upper triangular
+    for i in 0..dense.len() {
+        assert_eq!(l.get(i, i), T::one());
+        for j in 0..dense.len() {
+            if j > i {
+                assert_eq!(l_dense[i][j], T::zero());
+            }
+            if j < i {
+                assert_eq!(u_dense[i][j], T::zero());
+            }
+        }
+    }
+
+    // P * A * Q = L * U
+    let permuted: Vec<Vec<T>> = p
+        .iter()
+        .map(|&i| q.iter().map(|&j| dense[i][j]).collect())
+        .collect();
+    let product = dense_matrix_multiply(&l_dense, &u_dense);
+    assert_dense_close(&product, &permuted, 1e-4 * dense.len() as f64);
+}
+
+fn test_lu_simple<T: Real>() {
+    test_lu_reconstructs(get_dense_simple::<T>(), &LuOptions::default());
+}
+
+fn test_lu_identity<T: Real>() {
+    let a = SparseCSC::from_dense(dense_from_f64::<T>(&[
+        vec![1.0, 0.0, 0.0],
+        vec![0.0, 1.0, 0.0],
+        vec![0.0, 0.0, 1.0],
+    ]));
+    let factors = a.lu().unwrap();
+    assert_eq!(factors.p, vec![0, 1, 2]);
+    assert_eq!(factors.l.nnz(), 3);
+    assert_eq!(factors.u.nnz(), 3);
+}
+
+fn stress_test_lu_diag_dominant<T: Real>() {
+    let options = LuOptions::default();
+    test_lu_reconstructs(dense_diag_dominant::<T>(40, 0.1), &options);
+    test_lu_reconstructs(dense_diag_dominant::<T>(25, 0.5), &options);
+
+    // a dominant diagonal always passes the threshold, so no rows move
+    let a = SparseCSC::from_dense(dense_diag_dominant::<T>(30, 0.2));
+    let p = a.lu().unwrap().p;
+    assert_eq!(p, (0..30).collect::<Vec<_>>());
+}
+
+fn stress_test_lu_pivoting<T: Real>() {
+    for thresh in [1.0, 0.1, 0.0] {
+        let options = LuOptions {
+            diag_pivot_thresh: thresh,
+            ..Default::default()
+        };
+        test_lu_reconstructs(dense_random_floats::<T>(30, 30), &options);
+    }
+}
+
+fn test_lu_zero_diagonal<T: Real>() {
+    let dense = dense_from_f64::<T>(&[vec![0.0, 1.0], vec![1.0, 0.0]]);
+    let a = SparseCSC::from_dense(dense.clone());
+    let p = a.lu().unwrap().p;
+    assert_eq!(p, vec![1, 0]);
+    test_lu_reconstructs(dense, &LuOptions::default());
+}
+
+fn test_lu_diag_pivot_thresh<T: Real>() {
+    let dense = dense_from_f64::<T>(&[vec![0.5, 1.0], vec![1.0, 1.0]]);
+    let a = SparseCSC::from_dense(dense.clone());
+
+    let p = a.lu().unwrap().p;
+    assert_eq!(p, vec![1, 0]);
+
+    let options = LuOptions {
+        diag_pivot_thresh: 0.5,
+        ..Default::default()
+    };
+    let p = a.lu_with_options(&options).unwrap().p;
+    assert_eq!(p, vec![0, 1]);
+    test_lu_reconstructs(dense, &options);
+}
+
+fn test_lu_singular<T: Real>() {
+    let a = SparseCSC::from_dense(dense_from_f64::<T>(&[vec![1.0, 0.0], vec![2.0, 0.0]]));
+    assert_eq!(a.lu().unwrap_err(), LuError::ZeroPivot(1));
+}
+
+fn test_lu_not_square<T: Real>() {
+    let a = SparseCSC::from_dense(dense_from_f64::<T>(&[
+        vec![1.0, 1.0, 0.0],
+        vec![1.0, 0.0, 1.0],
+    ]));
+    assert_eq!(a.lu().unwrap_err(), LuError::NotSquare(2, 3));
+}
+
+fn test_lu_invalid_ordering<T: Real>() {
+    let a = SparseCSC::from_dense(get_dense_simple::<T>());
+    let n = a.ncols;
+    for perm in [vec![0; n], (0..n - 1).collect(), (1..=n).collect()] {
+        let options = LuOptions {
+            ordering: ColumnOrdering::Custom(perm),
+            ..Default::default()
+        };
+        assert_eq!(
+            a.lu_with_options(&options).unwrap_err(),
+            LuError::InvalidOrdering(n)
+        );
+        assert_eq!(
+            a.analyze(&options).unwrap_err(),
+            LuError::InvalidOrdering(n)
+        );
+    }
+}
+
+fn column<T: Real>(v: &[T]) -> Vec<Vec<T>> {
+    v.iter().map(|x| vec![*x]).collect()
+}
+
+fn flatten_column<T: Real>(v: Vec<Vec<T>>) -> Vec<T> {
+    v.into_iter().map(|row| row[0]).collect()
+}
+
+fn test_lu_solve<T: Real>() {
+    let dense = dense_random_floats::<T>(30, 30);
+    let x: Vec<T> = dense_random_floats::<T>(1, 30).remove(0);
+    let b = flatten_column(dense_matrix_multiply(&dense, &column(&x)));
+
+    let factors = SparseCSC::from_dense(dense).lu().unwrap();
+    assert_vec_close(&factors.solve(&b), &x, 1e-2);
+}
+
+fn test_lu_solve_transpose<T: Real>() {
+    let dense = dense_random_floats::<T>(30, 30);
+    let x: Vec<T> = dense_random_floats::<T>(1, 30).remove(0);
+    let b = flatten_column(dense_matrix_multiply(&dense_transpose(&dense), &column(&x)));
+
+    let factors = SparseCSC::from_dense(dense).lu().unwrap();
+    assert_vec_close(&factors.solve_transpose(&b), &x, 1e-2);
+}
+
+fn test_lu_solve_many<T: Real>() {
+    let dense = dense_diag_dominant::<T>(25, 0.2);
+    let x = dense_random_floats::<T>(25, 4);
+    let b = dense_matrix_multiply(&dense, &x);
+
+    let factors = SparseCSC::from_dense(dense).lu().unwrap();
+    assert_dense_close(&factors.solve_many(&b), &x, 1e-4);
+}
+
+fn test_lu_solve_simple<T: Real>() {
+    let factors = SparseCSC::from_dense(get_dense_simple::<T>()).lu().unwrap();
+    // [1 2 0; 0 3 4; 5 0 6] * [1, 1, 1] = [3, 7, 11]
+    assert_vec_close(
+        &factors.solve(&vec_from_f64::<T>(&[3.0, 7.0, 11.0])),
+        &vec_from_f64::<T>(&[1.0, 1.0, 1.0]),
+        1e-5,
+    );
+    // transpose * [1, 1, 1] = [6, 5, 10]
+    assert_vec_close(
+        &factors.solve_transpose(&vec_from_f64::<T>(&[6.0, 5.0, 10.0])),
+        &vec_from_f64::<T>(&[1.0, 1.0, 1.0]),
+        1e-5,
+    );
+}
+
+fn stress_test_lu_column_ordering<T: Real>() {
+    let dense = dense_random_floats::<T>(25, 25);
+    let mut perm: Vec<usize> = (0..25).collect();
+    fastrand::shuffle(&mut perm);
+
+    for ordering in [ColumnOrdering::Amd, ColumnOrdering::Custom(perm)] {
+        let options = LuOptions {
+            ordering: ordering.clone(),
+            ..Default::default()
+        };
+        test_lu_reconstructs(dense.clone(), &options);
+
+        let factors = SparseCSC::from_dense(dense.clone())
+            .lu_with_options(&options)
+            .unwrap();
+        if let ColumnOrdering::Custom(perm) = ordering {
+            assert_eq!(factors.q, perm);
+        }
+        let x = dense_random_floats::<T>(1, 25).remove(0);
+        let b = flatten_column(dense_matrix_multiply(&dense, &column(&x)));
+        assert_vec_close(&factors.solve(&b), &x, 1e-2);
+    }
+}
+
+scalar_tests! {
+    fn test_lu_simple;
+    fn test_lu_identity;
+    fn stress_test_lu_diag_dominant;
+    fn stress_test_lu_pivoting;
+    fn test_lu_zero_diagonal;
+    fn test_lu_diag_pivot_thresh;
+    fn test_lu_singular;
+    fn test_lu_not_square;
+    fn test_lu_invalid_ordering;
+    fn test_lu_solve;
+    fn test_lu_solve_transpose;
+    fn test_lu_solve_many;
+    fn test_lu_solve_simple;
+    fn stress_test_lu_column_ordering;
+}
+
+// pivoting compares moduli, so every numeric method takes complex entries
+#[test]
+fn test_lu_complex() {
+    let n = 30;
+    let mut dense = SparseCSC::<Complex<f64>>::random(n, n, 0.15).to_dense();
+    for j in 0..n {
+        // a nonzero somewhere in every column, not necessarily on the diagonal
+        dense[(j * 7) % n][j] = Complex::new(1.0, -2.0);
+    }
+    let a = SparseCSC::from_dense(dense.clone());
+
+    for ordering in [ColumnOrdering::Natural, ColumnOrdering::Colamd] {
+        let options = LuOptions {
+            ordering,
+            ..Default::default()
+        };
+        assert_lu_factors(&dense, &a.lu_with_options(&options).unwrap());
+
+        let symbolic = a.analyze(&options).unwrap();
+        assert_lu_factors(&dense, &symbolic.factor_supernodal(&a).unwrap());
+        assert_lu_factors(&dense, &symbolic.factor_multifrontal(&a).unwrap());
+    }
+}
@@ -0,0 +1,92 @@
+use crate::sparse::lu::{ColumnOrdering, LuError, LuMethod, LuOptions};
+use crate::sparse::scalar::Real;
+use crate::sparse::{sparse_csc::SparseCSC, sparse_matrix::SparseMatrixTrait};
+use crate::tests::test_utils::{
+    assert_dense_close, assert_lu_factors, dense_diag_dominant, dense_from_f64,
+    dense_grid_laplacian, dense_random_floats, get_dense_simple, scalar_tests,
+};
+
+fn multifrontal(ordering: ColumnOrdering) -> LuOptions {
+    LuOptions {
+        ordering,
+        method: LuMethod::Multifrontal,
+        ..Default::default()
+    }
+}
+
+fn test_multifrontal_simple<T: Real>() {
+    let dense = get_dense_simple::<T>();
+    let a = SparseCSC::from_dense(dense.clone());
+    let factors = a
+        .lu_with_options(&multifrontal(ColumnOrdering::Natural))
+        .unwrap();
+    assert_lu_factors(&dense, &factors);
+}
+
+fn stress_test_multifrontal<T: Real>() {
+    for ordering in [ColumnOrdering::Natural, ColumnOrdering::Colamd] {
+        let options = multifrontal(ordering);
+        for dense in [
+            dense_random_floats::<T>(40, 40),
+            dense_diag_dominant::<T>(50, 0.1),
+            dense_grid_laplacian::<T>(6),
+        ] {
+            let factors = SparseCSC::from_dense(dense.clone())
+                .lu_with_options(&options)
+                .unwrap();
+            assert_lu_factors(&dense, &factors);
+        }
+    }
+}
+
+fn test_methods_agree<T: Real>() {
+    let dense = dense_diag_dominant::<T>(40, 0.15);
+    let a = SparseCSC::from_dense(dense.clone());
+    let reference = a.lu().unwrap();
+
+    for method in [LuMethod::Supernodal, LuMethod::Multifrontal] {
+        let options = LuOptions {
+            method,
+            ..Default::default()
+        };
+        let factors = a.lu_with_options(&options).unwrap();
+        assert_eq!(factors.p, reference.p);
+        assert_dense_close(&factors.u.to_dense(), &reference.u.to_dense(), 1e-3);
+        assert_lu_factors(&dense, &factors);
+    }
+}
+
+fn test_multifrontal_refactor<T: Real>() {
+    let dense = dense_random_floats::<T>(30, 30);
+    let a = SparseCSC::from_dense(dense);
+    let symbolic = a.analyze(&multifrontal(ColumnOrdering::Colamd)).unwrap();
+    let mut factors = symbolic.factor(&a).unwrap();
+
+    let mut b = a.clone();
+    for (p, value) in b.values.iter_mut().enumerate() {
+        *value += T::from_f64(0.01 * (p % 5) as f64);
+    }
+    symbolic.refactor(&mut factors, &b).unwrap();
+    assert_lu_factors(&b.to_dense(), &factors);
+}
+
+fn test_multifrontal_singular<T: Real>() {
+    let a = SparseCSC::from_dense(dense_from_f64::<T>(&[
+        vec![1.0, 2.0, 0.0],
+        vec![2.0, 4.0, 0.0],
+        vec![0.0, 0.0, 1.0],
+    ]));
+    let options = multifrontal(ColumnOrdering::Natural);
+    assert_eq!(
+        a.lu_with_options(&options).unwrap_err(),
+        LuError::ZeroPivot(1)
+    );
+}
+
+scalar_tests! {
+    fn test_multifrontal_simple;
+    fn stress_test_multifrontal;
+    fn test_methods_agree;
+    fn test_multifrontal_refactor;
+    fn test_multifrontal_singular;
+}
@@ -1,11 +1,10 @@
-use crate::sparse::{
-    sparse_csc::SparseCSC, sparse_csr::SparseCSR, sparse_matrix::SparseMatrixTrait,
-};
+use crate::sparse::scalar::Real;
+use crate::sparse::{sparse_csr::SparseCSR, sparse_matrix::SparseMatrixTrait};
 use crate::tests::test_utils::{
-    dense_matrix_multiply, dense_random_floats, get_dense_simple, get_dense_simple_b,
+    dense_matrix_multiply, dense_random_floats, get_dense_simple, get_dense_simple_b, scalar_tests,
 };
 
-fn test_sparse_csr_csc_multiplication(dense_a: Vec<Vec<f32>>, dense_b: Vec<Vec<f32>>) {
+fn test_sparse_csr_csc_multiplication<T: Real>(dense_a: Vec<Vec<T>>, dense_b: Vec<Vec<T>>) {
     let sparse_a = SparseCSR::from_dense(dense_a.clone());
     let sparse_b = SparseCSR::from_dense(dense_b.clone());
 
@@ -24,16 +23,19 @@ fn test_sparse_csr_csc_multiplication(dense_a: Vec<Vec<f32>>, dense_b: Vec<Vec<f
     assert_eq!(sparse_result_dense, dense_result);
 }
 
-#[test]
-fn test_sparse_csr_csc_multiplication_simple() {
-    let dense_a = get_dense_simple();
-    let dense_b = get_dense_simple_b();
+fn test_sparse_csr_csc_multiplication_simple<T: Real>() {
+    let dense_a = get_dense_simple::<T>();
+    let dense_b = get_dense_simple_b::<T>();
     test_sparse_csr_csc_multiplication(dense_a, dense_b);
 }
 
-#[test]
-fn test_sparse_csr_csc_multiplication_random() {
-    let dense_a = dense_random_floats(20, 16);
-    let dense_b = dense_random_floats(16, 12);
+fn test_sparse_csr_csc_multiplication_random<T: Real>() {
+    let dense_a = dense_random_floats::<T>(20, 16);
+    let dense_b = dense_random_floats::<T>(16, 12);
     test_sparse_csr_csc_multiplication(dense_a, dense_b);
 }
+
+scalar_tests! {
+    fn test_sparse_csr_csc_multiplication_simple;
+    fn test_sparse_csr_csc_multiplication_random;
+}
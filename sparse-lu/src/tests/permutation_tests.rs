@@ -0,0 +1,105 @@
+use crate::sparse::permutation::{inverse_permutation, is_permutation};
+use crate::sparse::scalar::Real;
+use crate::sparse::{
+    sparse_coo::SparseCOO, sparse_csc::SparseCSC, sparse_csr::SparseCSR,
+    sparse_matrix::SparseMatrixTrait,
+};
+use crate::tests::test_utils::{
+    dense_from_f64, dense_random_floats, get_dense_simple, scalar_tests,
+};
+
+fn random_permutation(n: usize) -> Vec<usize> {
+    let mut perm: Vec<usize> = (0..n).collect();
+    fastrand::shuffle(&mut perm);
+    perm
+}
+
+fn permute_dense<T: Real>(dense: &[Vec<T>], row_perm: &[usize], col_perm: &[usize]) -> Vec<Vec<T>> {
+    row_perm
+        .iter()
+        .map(|&i| col_perm.iter().map(|&j| dense[i][j]).collect())
+        .collect()
+}
+
+fn test_permute<T: Real>(dense: Vec<Vec<T>>) {
+    let (nrows, ncols) = (dense.len(), dense[0].len());
+    let row_perm = random_permutation(nrows);
+    let col_perm = random_permutation(ncols);
+    let expected = permute_dense(&dense, &row_perm, &col_perm);
+
+    let coo = SparseCOO::from_dense(dense.clone()).permute(&row_perm, &col_perm);
+    assert_eq!(coo.to_dense(), expected);
+    let csc = SparseCSC::from_dense(dense.clone()).permute(&row_perm, &col_perm);
+    assert_eq!(csc.to_dense(), expected);
+    let csr = SparseCSR::from_dense(dense.clone()).permute(&row_perm, &col_perm);
+    assert_eq!(csr.to_dense(), expected);
+
+    // indices stay sorted, so get() still works
+    for (i, row) in expected.iter().enumerate() {
+        for (j, value) in row.iter().enumerate() {
+            assert_eq!(csc.get(i, j), *value);
+            assert_eq!(csr.get(i, j), *value);
+        }
+    }
+}
+
+fn test_permute_symmetric<T: Real>(dense: Vec<Vec<T>>) {
+    let perm = random_permutation(dense.len());
+    let expected = permute_dense(&dense, &perm, &perm);
+
+    let coo = SparseCOO::from_dense(dense.clone()).permute_symmetric(&perm);
+    assert_eq!(coo.to_dense(), expected);
+    let csc = SparseCSC::from_dense(dense.clone()).permute_symmetric(&perm);
+    assert_eq!(csc.to_dense(), expected);
+    let csr = SparseCSR::from_dense(dense.clone()).permute_symmetric(&perm);
+    assert_eq!(csr.to_dense(), expected);
+
+    // undoing the permutation gives A back
+    let inverse = inverse_permutation(&perm);
+    assert_eq!(csc.permute_symmetric(&inverse).to_dense(), dense);
+    assert_eq!(csr.permute_symmetric(&inverse).to_dense(), dense);
+}
+
+fn test_permute_simple<T: Real>() {
+    let dense = get_dense_simple::<T>();
+    let csr = SparseCSR::from_dense(dense).permute(&[2, 0, 1], &[1, 2, 0]);
+    assert_eq!(
+        csr.to_dense(),
+        dense_from_f64::<T>(&[
+            vec![0.0, 6.0, 5.0],
+            vec![2.0, 0.0, 1.0],
+            vec![3.0, 4.0, 0.0],
+        ])
+    );
+}
+
+fn stress_test_permute<T: Real>() {
+    test_permute(dense_random_floats::<T>(12, 9));
+    test_permute(SparseCOO::<T>::random(30, 25, 0.1).to_dense());
+    test_permute_symmetric(SparseCOO::<T>::random(30, 30, 0.1).to_dense());
+}
+
+#[test]
+fn test_inverse_permutation() {
+    let perm = vec![2, 0, 3, 1];
+    let inverse = inverse_permutation(&perm);
+    assert_eq!(inverse, vec![1, 3, 0, 2]);
+    assert_eq!(inverse_permutation(&inverse), perm);
+
+    assert!(is_permutation(&perm, 4));
+    assert!(!is_permutation(&perm, 5));
+    assert!(!is_permutation(&[0, 0, 1], 3));
+    assert!(!is_permutation(&[0, 3, 1], 3));
+}
+
+fn test_permute_invalid<T: Real>() {
+    let csc = SparseCSC::from_dense(get_dense_simple::<T>());
+    csc.permute(&[0, 1, 1], &[0, 1, 2]);
+}
+
+scalar_tests! {
+    fn test_permute_simple;
+    fn stress_test_permute;
+    #[should_panic]
+    fn test_permute_invalid;
+}
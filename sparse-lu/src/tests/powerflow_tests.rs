@@ -0,0 +1,171 @@
+use crate::grid::case::{Branch, Bus, BusType, Case, Generator, Load};
+use crate::grid::powerflow::{PowerFlowError, PowerFlowOptions, PowerFlowResult};
+use crate::grid::psse::RawCase;
+use crate::tests::test_utils::data_path;
+
+pub fn raw_case(name: &str) -> Case {
+    RawCase::read(data_path(&format!("raw/{}.RAW", name)))
+        .unwrap()
+        .to_case()
+}
+
+// largest |Vm - Vm_case| and |Va - Va_case| in degrees, over buses in the power flow
+pub fn voltage_error(case: &Case, result: &PowerFlowResult) -> (f64, f64) {
+    case.buses
+        .iter()
+        .enumerate()
+        .filter(|(_, bus)| bus.bus_type != BusType::Isolated)
+        .fold((0.0, 0.0), |(vm, va), (k, bus)| {
+            (
+                f64::max(vm, (result.vm[k] - bus.vm).abs()),
+                f64::max(va, (result.va[k] - bus.va).abs()),
+            )
+        })
+}
+
+fn bus(number: usize, bus_type: BusType, vm: f64) -> Bus {
+    Bus {
+        number,
+        name: format!("BUS {}", number),
+        base_kv: 138.0,
+        bus_type,
+        vm,
+        va: 0.0,
+    }
+}
+
+// lossless line from a 1.02 pu slack bus 1 to a load bus 2, 150 MW + 50 MVAr
+pub fn load_bus_case() -> Case {
+    Case {
+        base_mva: 100.0,
+        buses: vec![bus(1, BusType::Slack, 1.02), bus(2, BusType::Pq, 1.0)],
+        branches: vec![Branch {
+            from: 1,
+            to: 2,
+            circuit: "1".to_string(),
+            r: 0.0,
+            x: 0.1,
+            b: 0.0,
+            tap: 1.0,
+            shift: 0.0,
+            in_service: true,
+        }],
+        shunts: vec![],
+        loads: vec![Load {
+            bus: 2,
+            id: "1".to_string(),
+            pd: 150.0,
+            qd: 50.0,
+            in_service: true,
+        }],
+        generators: vec![],
+    }
+}
+
+#[test]
+fn test_newton_two_bus() {
+    let case = load_bus_case();
+    let result = case
+        .newton_power_flow(&PowerFlowOptions::default())
+        .unwrap();
+    assert!(result.converged);
+    assert_eq!((result.vm[0], result.va[0]), (1.02, 0.0));
+
+    // power received over a lossless line, P = V1 V2 sin(d) / x and
+    // Q = (V1 V2 cos(d) - V2^2) / x with d = Va1 - Va2
+    let (v1, v2) = (result.vm[0], result.vm[1]);
+    let d = (result.va[0] - result.va[1]).to_radians();
+    assert!((v1 * v2 * d.sin() / 0.1 - 1.5).abs() < 1e-8);
+    assert!(((v1 * v2 * d.cos() - v2 * v2) / 0.1 - 0.5).abs() < 1e-8);
+    assert!(result.vm[1] < 1.0 && result.va[1] < 0.0);
+}
+
+#[test]
+fn test_newton_pv_bus() {
+    // a generator at bus 2 holding 1.0 pu while covering part of the load
+    let mut case = load_bus_case();
+    case.buses[1].bus_type = BusType::Pv;
+    case.generators.push(Generator {
+        bus: 2,
+        id: "1".to_string(),
+        pg: 50.0,
+        qg: 0.0,
+        qmax: 100.0,
+        qmin: -100.0,
+        vset: 1.0,
+        in_service: true,
+    });
+    let result = case
+        .newton_power_flow(&PowerFlowOptions::default())
+        .unwrap();
+    assert!(result.converged);
+    assert_eq!(result.vm[1], 1.0);
+
+    let d = (result.va[0] - result.va[1]).to_radians();
+    assert!((1.02 * d.sin() / 0.1 - 1.0).abs() < 1e-8);
+}
+
+#[test]
+fn test_newton_errors() {
+    let mut case = load_bus_case();
+    case.buses[0].bus_type = BusType::Pq;
+    let err = case
+        .newton_power_flow(&PowerFlowOptions::default())
+        .unwrap_err();
+    assert_eq!(err, PowerFlowError::NoSlack);
+
+    // more load than the line can carry, no solution to converge to
+    let mut case = load_bus_case();
+    case.loads[0].pd = 1000.0;
+    let result = case
+        .newton_power_flow(&PowerFlowOptions::default())
+        .unwrap();
+    assert!(!result.converged);
+    assert_eq!(result.iterations, 20);
+    assert_eq!(result.mismatches.len(), 21);
+}
+
+#[test]
+fn test_newton_hawaii() {
+    let case = raw_case("Hawaii40_20231026");
+    let result = case
+        .newton_power_flow(&PowerFlowOptions::default())
+        .unwrap();
+    assert!(result.converged);
+    assert!(result.iterations <= 5);
+
+    // quadratic convergence, each mismatch roughly the square of the last
+    let tail = &result.mismatches[1..];
+    for pair in tail.windows(2) {
+        assert!(pair[1] < 10.0 * pair[0] * pair[0]);
+    }
+
+    let (vm_error, va_error) = voltage_error(&case, &result);
+    assert!(vm_error < 1e-5, "Vm off by {}", vm_error);
+    assert!(va_error < 1e-3, "Va off by {} degrees", va_error);
+}
+
+#[test]
+fn test_newton_texas() {
+    let case = raw_case("Texas7k_20210804");
+    let result = case
+        .newton_power_flow(&PowerFlowOptions::default())
+        .unwrap();
+    assert!(result.converged);
+    assert!(result.iterations <= 8);
+
+    // the case voltages are rounded in the file, so they match to the
+    // accuracy of the file rather than of the power flow
+    let (vm_error, va_error) = voltage_error(&case, &result);
+    assert!(vm_error < 1e-4, "Vm off by {}", vm_error);
+    assert!(va_error < 0.05, "Va off by {} degrees", va_error);
+
+    // starting from the case voltages takes fewer iterations
+    let options = PowerFlowOptions {
+        flat_start: false,
+        ..Default::default()
+    };
+    let warm = case.newton_power_flow(&options).unwrap();
+    assert!(warm.converged);
+    assert!(warm.iterations < result.iterations);
+}
@@ -0,0 +1,301 @@
+use crate::grid::case::{BusType, Case};
+use crate::grid::powerflow::PowerFlowOptions;
+use crate::grid::psse::{PsseError, RawCase};
+use crate::sparse::sparse_matrix::SparseMatrixTrait;
+use crate::tests::grid_tests::csv_case;
+use crate::tests::test_utils::{assert_dense_close, data_path};
+
+const HEADER: &str = " 0,   100.00, 33, 0, 0, 60.00     / comment\nTITLE ONE\n\n";
+
+// a three-bus case with one record of every kind, Q right after the transformers
+fn small_raw(transformer: &str) -> String {
+    format!(
+        "{}\
+         1,'NORTH / 1', 138.0,3,1,1,1,1.02,0.0\n\
+         2,'SOUTH',  69.0,1,1,1,1,0.99,-2.5\n\
+         3,'EAST',   13.8,2,1,1,1,1.01,-1.0\n\
+         0 / END OF BUS DATA, BEGIN LOAD DATA\n\
+         2,'1 ',1,1,1,  40.0, 10.0\n\
+         0 / END OF LOAD DATA, BEGIN FIXED SHUNT DATA\n\
+         2,'1 ',0, 0.0, 15.0\n\
+         0 / END OF FIXED SHUNT DATA, BEGIN GENERATOR DATA\n\
+         1,'1 ',50.0,5.0,30.0,-30.0,1.02,0,100.0\n\
+         0 / END OF GENERATOR DATA, BEGIN BRANCH DATA\n\
+         1,2,'1 ',0.01,0.1,0.02,100.0,100.0,100.0,0,0,0,0,1,1,12.5\n\
+         0 / END OF BRANCH DATA, BEGIN TRANSFORMER DATA\n\
+         {}\
+         0 / END OF TRANSFORMER DATA, BEGIN AREA DATA\n\
+         Q\n",
+        HEADER, transformer
+    )
+}
+
+const TWO_WINDING: &str = "2,3,0,'T1',1,1,1,0.0,0.0,2,'XFMR',1\n\
+                           0.001,0.08,100.0\n\
+                           1.025,69.0,-30.0,50.0,60.0,70.0,0\n\
+                           1.0,13.8\n";
+
+const THREE_WINDING: &str = "1,2,3,'T3',1,2,1,0.0,-0.01,2,'',1\n\
+                             0.001,0.08,50.0,0.002,0.09,40.0,0.003,0.1,30.0,1.01,-5.0\n\
+                             1.05,138.0,0.0\n\
+                             1.0,69.0,0.0\n\
+                             0.98,13.8,30.0\n";
+
+#[test]
+fn test_parse_records() {
+    let case = RawCase::parse(&small_raw(TWO_WINDING)).unwrap();
+    assert_eq!(
+        (case.ic, case.sbase, case.rev, case.basfrq),
+        (0, 100.0, 33, 60.0)
+    );
+    assert_eq!(case.titles, ["TITLE ONE".to_string(), String::new()]);
+
+    // '/' inside a quoted string is not a comment
+    assert_eq!(case.buses[0].name, "NORTH / 1");
+    assert_eq!(case.buses[0].bus_type, BusType::Slack);
+    assert_eq!((case.buses[1].vm, case.buses[1].va), (0.99, -2.5));
+    assert_eq!(case.buses[2].bus_type, BusType::Pv);
+
+    let load = &case.loads[0];
+    assert_eq!(
+        (load.bus, load.id.as_str(), load.pl, load.ql),
+        (2, "1", 40.0, 10.0)
+    );
+    // left off the record, so the default
+    assert_eq!(load.yq, 0.0);
+    assert!(!case.fixed_shunts[0].in_service);
+    assert_eq!(case.fixed_shunts[0].bl, 15.0);
+
+    let generator = &case.generators[0];
+    assert_eq!(
+        (generator.pg, generator.qmax, generator.qmin),
+        (50.0, 30.0, -30.0)
+    );
+    assert_eq!(generator.vs, 1.02);
+    assert!(generator.in_service);
+
+    let branch = &case.branches[0];
+    assert_eq!(
+        (branch.from, branch.to, branch.circuit.as_str()),
+        (1, 2, "1")
+    );
+    assert_eq!(
+        (branch.r, branch.x, branch.b, branch.length),
+        (0.01, 0.1, 0.02, 12.5)
+    );
+
+    let transformer = &case.transformers[0];
+    assert_eq!((transformer.i, transformer.j, transformer.k), (2, 3, None));
+    assert_eq!(transformer.name, "XFMR");
+    assert_eq!(transformer.impedances.len(), 1);
+    assert_eq!(transformer.impedances[0].x, 0.08);
+    assert_eq!(transformer.windings.len(), 2);
+    assert_eq!(transformer.windings[0].windv, 1.025);
+    assert_eq!(transformer.windings[0].ang, -30.0);
+    assert_eq!(transformer.windings[0].rate_c, 70.0);
+    assert_eq!(transformer.windings[1].nomv, 13.8);
+}
+
+#[test]
+fn test_parse_three_winding() {
+    let raw = small_raw(&format!("{}{}", THREE_WINDING, TWO_WINDING));
+    let case = RawCase::parse(&raw).unwrap();
+    assert_eq!(case.transformers.len(), 2);
+
+    let transformer = &case.transformers[0];
+    assert_eq!(transformer.k, Some(3));
+    assert_eq!((transformer.cz, transformer.mag2), (2, -0.01));
+    let sbases: Vec<f64> = transformer.impedances.iter().map(|z| z.sbase).collect();
+    assert_eq!(sbases, vec![50.0, 40.0, 30.0]);
+    assert_eq!((transformer.vmstar, transformer.anstar), (1.01, -5.0));
+    let windv: Vec<f64> = transformer.windings.iter().map(|w| w.windv).collect();
+    assert_eq!(windv, vec![1.05, 1.0, 0.98]);
+    assert_eq!(transformer.windings[2].ang, 30.0);
+
+    // the record after it starts on the right line
+    assert_eq!(case.transformers[1].circuit, "T1");
+}
+
+// area through FACTS sections, with a record in a few of them, then switched shunts
+const AFTER_TRANSFORMERS: &str = "1,1,0.0,10.0,'NORTH'\n\
+                                  0 / END OF AREA DATA, BEGIN TWO-TERMINAL DC DATA\n\
+                                  0 / END OF TWO-TERMINAL DC DATA, BEGIN VSC DC LINE DATA\n\
+                                  0 / END OF VSC DC LINE DATA, BEGIN IMPEDANCE CORRECTION DATA\n\
+                                  0 / END OF IMPEDANCE CORRECTION DATA, BEGIN MULTI-TERMINAL DC DATA\n\
+                                  0 / END OF MULTI-TERMINAL DC DATA, BEGIN MULTI-SECTION LINE DATA\n\
+                                  0 / END OF MULTI-SECTION LINE DATA, BEGIN ZONE DATA\n\
+                                  1,'ZONE 1'\n\
+                                  0 / END OF ZONE DATA, BEGIN INTER-AREA TRANSFER DATA\n\
+                                  0 / END OF INTER-AREA TRANSFER DATA, BEGIN OWNER DATA\n\
+                                  1,'OWNER 1'\n\
+                                  0 / END OF OWNER DATA, BEGIN FACTS DEVICE DATA\n\
+                                  0 / END OF FACTS DEVICE DATA, BEGIN SWITCHED SHUNT DATA\n\
+                                  2,1,0,1,1.05,0.95,0,100.0,'',20.0,2,10.0,1,-5.0\n\
+                                  3,0,0,0,1.0,1.0,0,100.0,'',5.0\n\
+                                  0 / END OF SWITCHED SHUNT DATA, BEGIN GNE DATA\n\
+                                  0 / END OF GNE DATA, BEGIN INDUCTION MACHINE DATA\n\
+                                  0 / END OF INDUCTION MACHINE DATA\n";
+
+#[test]
+fn test_parse_switched_shunts() {
+    let raw = small_raw(TWO_WINDING).replace("Q\n", AFTER_TRANSFORMERS);
+    let case = RawCase::parse(&raw).unwrap();
+    assert_eq!(case.switched_shunts.len(), 2);
+
+    let shunt = &case.switched_shunts[0];
+    assert_eq!((shunt.bus, shunt.modsw), (2, 1));
+    assert!(shunt.in_service);
+    assert_eq!((shunt.vswhi, shunt.vswlo, shunt.binit), (1.05, 0.95, 20.0));
+    assert_eq!(shunt.blocks, vec![(2, 10.0), (1, -5.0)]);
+    assert!(!case.switched_shunts[1].in_service);
+    assert!(case.switched_shunts[1].blocks.is_empty());
+
+    // held at BINIT as a fixed shunt, the out of service one left out
+    let shunts: Vec<(usize, f64, f64)> = case
+        .to_case()
+        .shunts
+        .iter()
+        .filter(|shunt| shunt.in_service)
+        .map(|shunt| (shunt.bus, shunt.g, shunt.b))
+        .collect();
+    assert!(shunts.contains(&(2, 0.0, 20.0)));
+    assert!(!shunts.iter().any(|&(bus, _, _)| bus == 3));
+
+    // Q before the switched shunt section leaves it empty
+    let early = raw.replace("0 / END OF OWNER DATA", "Q\n0 / END OF OWNER DATA");
+    assert!(RawCase::parse(&early).unwrap().switched_shunts.is_empty());
+}
+
+#[test]
+fn test_to_case_three_winding_out_of_service() {
+    let out_of_service = THREE_WINDING.replace(",'',1\n", ",'',0\n");
+    let raw = small_raw(&format!("{}{}", out_of_service, TWO_WINDING));
+    let case = RawCase::parse(&raw).unwrap().to_case();
+
+    // the star bus is still numbered, but takes no part in the power flow
+    assert_eq!(case.buses.len(), 4);
+    assert_eq!(case.buses[3].number, 4);
+    assert_eq!(case.buses[3].bus_type, BusType::Isolated);
+    assert!(case.branches[1..4].iter().all(|branch| !branch.in_service));
+
+    let result = case
+        .newton_power_flow(&PowerFlowOptions::default())
+        .unwrap();
+    assert!(result.converged);
+}
+
+#[test]
+fn test_line_shunts_match_csv() {
+    // GI, BI, GJ, BJ on the 1-2 line, no transformer
+    let line = "1,2,'1 ',0.01,0.1,0.02,100.0,100.0,100.0,0,0,0,0,1,1,12.5\n";
+    let with_shunts = line.replace(",0,0,0,0,1,", ",0.01,0.05,0,0.03,1,");
+    let raw = small_raw("").replace(line, &with_shunts);
+    let raw_case = RawCase::parse(&raw).unwrap().to_case();
+
+    let bus = "BusNum,Name,BaseKV,Type,Area,Zone,Owner,Vm,Va\n\
+               1,NORTH / 1,138.0,3,1,1,1,1.02,0.0\n\
+               2,SOUTH,69.0,1,1,1,1,0.99,-2.5\n\
+               3,EAST,13.8,2,1,1,1,1.01,-1.0\n";
+    let branch = "FromBus,ToBus,CircuitID,R,X,B,RateA,RateB,RateC,GI,BI,GJ,BJ,Status\n\
+                  1,2,1,0.01,0.1,0.02,100.0,100.0,100.0,0.01,0.05,0,0.03,1\n";
+    let shunt = "BusNum,ID,Status,G,B\n2,1,0,0.0,15.0\n";
+    let load = "BusNum,ID,Status,Area,Zone,Pd,Qd\n2,1,1,1,1,40.0,10.0\n";
+    let generator = "BusNum,ID,Pg,Qg,Qmax,Qmin,Vset,RegBus,MBase,Status\n\
+                     1,1,50.0,5.0,30.0,-30.0,1.02,0,100.0,1\n";
+    let csv_case = Case::from_csv(bus, branch, shunt, load, generator).unwrap();
+
+    // the fixed shunt, out of service, then the line shunts at 1 and 2 in MW / MVAr
+    let shunts: Vec<_> = csv_case
+        .shunts
+        .iter()
+        .map(|shunt| (shunt.bus, shunt.g, shunt.b))
+        .collect();
+    assert_eq!(shunts, [(2, 0.0, 15.0), (1, 1.0, 5.0), (2, 0.0, 3.0)]);
+    assert_eq!(csv_case.shunts, raw_case.shunts);
+    assert_dense_close(
+        &csv_case.ybus().to_dense(),
+        &raw_case.ybus().to_dense(),
+        1e-12,
+    );
+}
+
+#[test]
+fn test_parse_errors() {
+    let raw = small_raw(TWO_WINDING);
+    let line_of = |text: &str| raw.lines().position(|line| line.starts_with(text)).unwrap() + 1;
+
+    let bad = raw.replace("0.99,-2.5", "0.99,-2.5.1");
+    let err = RawCase::parse(&bad).unwrap_err();
+    assert!(matches!(err, PsseError::Parse(line, "VA", _) if line == line_of("2,'SOUTH'")));
+    assert_eq!(
+        err.to_string(),
+        format!("line {}: invalid VA \"-2.5.1\"", line_of("2,'SOUTH'"))
+    );
+
+    let bad = raw.replace("0.001,0.08,100.0", "0.001");
+    let err = RawCase::parse(&bad).unwrap_err();
+    assert!(matches!(err, PsseError::MissingField(line, "X1-2") if line == line_of("0.001")));
+
+    let bad = raw.replace("'SOUTH'", "'SOUTH");
+    let err = RawCase::parse(&bad).unwrap_err();
+    assert!(matches!(err, PsseError::UnterminatedString(line) if line == line_of("2,'SOUTH'")));
+
+    let bad = raw.replace(", 33,", ", 34,");
+    let err = RawCase::parse(&bad).unwrap_err();
+    assert!(matches!(err, PsseError::UnsupportedVersion(34)));
+
+    let end = raw.find("1.0,13.8").unwrap();
+    let err = RawCase::parse(&raw[..end]).unwrap_err();
+    assert!(matches!(err, PsseError::UnexpectedEnd("transformer")));
+
+    let err = RawCase::read(data_path("raw/missing.RAW")).unwrap_err();
+    assert!(matches!(err, PsseError::Io(..)));
+}
+
+#[test]
+fn test_read_hawaii_raw() {
+    let raw = RawCase::read(data_path("raw/Hawaii40_20231026.RAW")).unwrap();
+    assert_eq!(raw.sbase, 100.0);
+    assert_eq!(raw.buses.len(), 37);
+    assert_eq!(raw.loads.len(), 27);
+    assert_eq!(raw.fixed_shunts.len(), 0);
+    assert_eq!(raw.generators.len(), 45);
+    assert_eq!(raw.branches.len(), 77);
+    assert_eq!(raw.transformers.len(), 12);
+    assert_eq!(raw.switched_shunts.len(), 1);
+    assert_eq!(
+        (raw.switched_shunts[0].bus, raw.switched_shunts[0].binit),
+        (16, 32.0)
+    );
+    assert!(raw.transformers.iter().all(|t| t.k.is_none()));
+
+    // the CSV export holds the same buses and lines
+    let case = csv_case("Hawaii40_20231026");
+    for (raw_bus, bus) in raw.buses.iter().zip(&case.buses) {
+        assert_eq!(raw_bus.number, bus.number);
+        assert_eq!(raw_bus.bus_type, bus.bus_type);
+        assert_eq!((raw_bus.vm, raw_bus.va), (bus.vm, bus.va));
+    }
+    for (raw_branch, branch) in raw.branches.iter().zip(&case.branches) {
+        assert_eq!((raw_branch.from, raw_branch.to), (branch.from, branch.to));
+        assert_eq!(
+            (raw_branch.r, raw_branch.x, raw_branch.b),
+            (branch.r, branch.x, branch.b)
+        );
+    }
+}
+
+#[test]
+fn test_read_texas_raw() {
+    let raw = RawCase::read(data_path("raw/Texas7k_20210804.RAW")).unwrap();
+    assert!(raw.titles[0].starts_with("This is a synthetic electric grid"));
+    assert_eq!(raw.buses.len(), 6717);
+    assert_eq!(raw.loads.len(), 5095);
+    assert_eq!(raw.fixed_shunts.len(), 205);
+    assert_eq!(raw.generators.len(), 731);
+    assert_eq!(raw.branches.len(), 7173);
+    assert_eq!(raw.transformers.len(), 1967);
+    assert_eq!(raw.switched_shunts.len(), 429);
+    assert_eq!(raw.transformers[0].windings[0].cod, 0);
+    assert_eq!(raw.transformers[0].windings[1].nomv, 1.0);
+}
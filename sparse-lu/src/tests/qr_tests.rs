@@ -0,0 +1,133 @@
+use crate::sparse::lu::ColumnOrdering;
+use crate::sparse::qr::QrError;
+use crate::sparse::scalar::Real;
+use crate::sparse::{sparse_csc::SparseCSC, sparse_matrix::SparseMatrixTrait};
+use crate::tests::test_utils::{
+    assert_dense_close, assert_vec_close, dense_diag_dominant, dense_from_f64,
+    dense_matrix_multiply, dense_random_floats, dense_transpose, get_dense_simple, scalar_tests,
+    vec_from_f64,
+};
+
+fn multiply_vector<T: Real>(a: &[Vec<T>], x: &[T]) -> Vec<T> {
+    a.iter()
+        .map(|row| row.iter().zip(x).map(|(&a, &x)| a * x).sum())
+        .collect()
+}
+
+// sparse tall matrix, every column has a nonzero on a diagonal block
+fn dense_tall<T: Real>(m: usize, n: usize, density: f64) -> Vec<Vec<T>> {
+    let mut dense = dense_random_floats::<T>(m, n);
+    for (i, row) in dense.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            if i % n != j && *value > T::from_f64(density) {
+                *value = T::zero();
+            }
+        }
+    }
+    dense
+}
+
+fn test_qr_square<T: Real>() {
+    let dense = get_dense_simple::<T>();
+    let factors = SparseCSC::from_dense(dense.clone()).qr().unwrap();
+    let b = multiply_vector(&dense, &vec_from_f64::<T>(&[1.0, 2.0, 3.0]));
+    assert_vec_close(
+        &factors.solve_least_squares(&b),
+        &vec_from_f64::<T>(&[1.0, 2.0, 3.0]),
+        1e-4,
+    );
+}
+
+fn stress_test_qr_r_factor<T: Real>() {
+    // R^T R = Q^T A^T A Q
+    for ordering in [ColumnOrdering::Natural, ColumnOrdering::Colamd] {
+        let dense = dense_tall::<T>(60, 25, 0.15);
+        let factors = SparseCSC::from_dense(dense.clone())
+            .qr_with_ordering(&ordering)
+            .unwrap();
+        let q = &factors.q;
+
+        let ata = dense_matrix_multiply(&dense_transpose(&dense), &dense);
+        let permuted: Vec<Vec<T>> = q
+            .iter()
+            .map(|&i| q.iter().map(|&j| ata[i][j]).collect())
+            .collect();
+        let r = factors.r.to_dense();
+        for (i, row) in r.iter().enumerate() {
+            assert!(row[..i].iter().all(|&v| v.is_zero()));
+        }
+        let rtr = dense_matrix_multiply(&dense_transpose(&r), &r);
+        assert_dense_close(&rtr, &permuted, 1e-3);
+    }
+}
+
+fn test_qr_consistent_system<T: Real>() {
+    let dense = dense_tall::<T>(50, 20, 0.2);
+    let x = dense_random_floats::<T>(1, 20).remove(0);
+    let b = multiply_vector(&dense, &x);
+
+    let factors = SparseCSC::from_dense(dense)
+        .qr_with_ordering(&ColumnOrdering::Colamd)
+        .unwrap();
+    assert_vec_close(&factors.solve_least_squares(&b), &x, 1e-3);
+}
+
+fn stress_test_least_squares<T: Real>() {
+    let dense = dense_tall::<T>(80, 30, 0.1);
+    let b = dense_random_floats::<T>(1, 80).remove(0);
+    let factors = SparseCSC::from_dense(dense.clone()).qr().unwrap();
+    let x = factors.solve_least_squares(&b);
+
+    // normal equations hold at the minimizer: A^T (A x - b) = 0
+    let residual: Vec<T> = multiply_vector(&dense, &x)
+        .iter()
+        .zip(&b)
+        .map(|(&ax, &b)| ax - b)
+        .collect();
+    let gradient = multiply_vector(&dense_transpose(&dense), &residual);
+    assert_vec_close(&gradient, &[T::zero(); 30], 1e-3);
+}
+
+fn test_qr_square_diag_dominant<T: Real>() {
+    let dense = dense_diag_dominant::<T>(30, 0.1);
+    let x = dense_random_floats::<T>(1, 30).remove(0);
+    let b = multiply_vector(&dense, &x);
+    let factors = SparseCSC::from_dense(dense).qr().unwrap();
+    assert_vec_close(&factors.solve_least_squares(&b), &x, 1e-4);
+}
+
+fn test_qr_errors<T: Real>() {
+    let wide = SparseCSC::from_dense(dense_from_f64::<T>(&[
+        vec![1.0, 2.0, 3.0],
+        vec![4.0, 5.0, 6.0],
+    ]));
+    assert_eq!(wide.qr().unwrap_err(), QrError::TooFewRows(2, 3));
+
+    let zero_column = SparseCSC::from_dense(dense_from_f64::<T>(&[
+        vec![1.0, 0.0],
+        vec![2.0, 0.0],
+        vec![3.0, 0.0],
+    ]));
+    assert_eq!(zero_column.qr().unwrap_err(), QrError::RankDeficient(1));
+
+    let custom = ColumnOrdering::Custom(vec![1, 1]);
+    assert_eq!(
+        zero_column.qr_with_ordering(&custom).unwrap_err(),
+        QrError::InvalidOrdering(2)
+    );
+    assert_eq!(
+        zero_column
+            .qr_with_ordering(&ColumnOrdering::Amd)
+            .unwrap_err(),
+        QrError::AmdNotSquare(3, 2)
+    );
+}
+
+scalar_tests! {
+    fn test_qr_square;
+    fn stress_test_qr_r_factor;
+    fn test_qr_consistent_system;
+    fn stress_test_least_squares;
+    fn test_qr_square_diag_dominant;
+    fn test_qr_errors;
+}
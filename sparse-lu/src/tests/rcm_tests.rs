@@ -0,0 +1,98 @@
+use crate::sparse::scalar::Real;
+use crate::sparse::{
+    sparse_csc::SparseCSC, sparse_csr::SparseCSR, sparse_matrix::SparseMatrixTrait,
+};
+use crate::tests::test_utils::{
+    assert_permutation, dense_diag_dominant, dense_grid_laplacian, get_dense_simple, scalar_tests,
+};
+
+// P A P^T with perm[k] = row/column placed k-th
+fn permute_dense<T: Real>(dense: &[Vec<T>], perm: &[usize]) -> Vec<Vec<T>> {
+    perm.iter()
+        .map(|&i| perm.iter().map(|&j| dense[i][j]).collect())
+        .collect()
+}
+
+fn dense_tridiagonal<T: Real>(n: usize) -> Vec<Vec<T>> {
+    let mut dense = vec![vec![T::zero(); n]; n];
+    for i in 0..n {
+        dense[i][i] = T::from_f64(2.0);
+        if i + 1 < n {
+            dense[i][i + 1] = T::from_f64(-1.0);
+            dense[i + 1][i] = T::from_f64(-1.0);
+        }
+    }
+    dense
+}
+
+fn test_band_stats_simple<T: Real>() {
+    // [1 2 0; 0 3 4; 5 0 6] has A + A^T full, row 1 starts at 0 and row 2 at 0
+    let stats = SparseCSR::from_dense(get_dense_simple::<T>()).band_stats();
+    assert_eq!(stats.bandwidth, 2);
+    assert_eq!(stats.profile, 3);
+
+    let stats = SparseCSR::from_dense(dense_tridiagonal::<T>(10)).band_stats();
+    assert_eq!(stats.bandwidth, 1);
+    assert_eq!(stats.profile, 9);
+}
+
+fn test_rcm_recovers_tridiagonal<T: Real>() {
+    let n = 30;
+    let mut shuffle: Vec<usize> = (0..n).collect();
+    fastrand::shuffle(&mut shuffle);
+    let scrambled = permute_dense(&dense_tridiagonal::<T>(n), &shuffle);
+
+    let report = SparseCSR::from_dense(scrambled.clone()).rcm();
+    assert_permutation(&report.perm, n);
+    assert_eq!(report.after.bandwidth, 1);
+    assert_eq!(report.after.profile, n - 1);
+
+    // the report agrees with actually permuting the matrix
+    let reordered = SparseCSR::from_dense(permute_dense(&scrambled, &report.perm));
+    assert_eq!(reordered.band_stats(), report.after);
+}
+
+fn test_rcm_grid<T: Real>() {
+    let k = 10;
+    let mut shuffle: Vec<usize> = (0..k * k).collect();
+    fastrand::shuffle(&mut shuffle);
+    let scrambled = permute_dense(&dense_grid_laplacian::<T>(k), &shuffle);
+
+    let report = SparseCSC::from_dense(scrambled).rcm();
+    assert_permutation(&report.perm, k * k);
+    assert!(report.after.bandwidth <= k + 1);
+    assert!(report.after.profile <= report.before.profile);
+}
+
+fn test_rcm_csc_csr_agree<T: Real>() {
+    let dense = dense_diag_dominant::<T>(40, 0.05);
+    let csr = SparseCSR::from_dense(dense.clone()).rcm();
+    let csc = SparseCSC::from_dense(dense).rcm();
+    assert_eq!(csr.perm, csc.perm);
+    assert_eq!(csr.after, csc.after);
+}
+
+fn test_rcm_disconnected<T: Real>() {
+    // two tridiagonal blocks interleaved, plus an isolated node
+    let n = 21;
+    let mut dense = vec![vec![T::zero(); n]; n];
+    for i in 0..n {
+        dense[i][i] = T::one();
+        if i + 2 < n - 1 {
+            dense[i][i + 2] = T::one();
+            dense[i + 2][i] = T::one();
+        }
+    }
+    let report = SparseCSR::from_dense(dense).rcm();
+    assert_permutation(&report.perm, n);
+    assert_eq!(report.before.bandwidth, 2);
+    assert_eq!(report.after.bandwidth, 1);
+}
+
+scalar_tests! {
+    fn test_band_stats_simple;
+    fn test_rcm_recovers_tridiagonal;
+    fn test_rcm_grid;
+    fn test_rcm_csc_csr_agree;
+    fn test_rcm_disconnected;
+}
@@ -0,0 +1,144 @@
+use crate::sparse::lu::{ColumnOrdering, LuError, LuOptions};
+use crate::sparse::scalar::Real;
+use crate::sparse::{sparse_csc::SparseCSC, sparse_matrix::SparseMatrixTrait};
+use crate::tests::test_utils::{
+    assert_dense_close, assert_vec_close, dense_diag_dominant, dense_from_f64,
+    dense_matrix_multiply, dense_random_floats, scalar_tests,
+};
+
+// same pattern as a, new values
+fn with_new_values<T: Real>(a: &SparseCSC<T>) -> SparseCSC<T> {
+    let mut b = a.clone();
+    for (p, value) in b.values.iter_mut().enumerate() {
+        *value *= T::from_f64(1.0 + 0.1 * (p % 7) as f64);
+    }
+    b
+}
+
+fn check_solve<T: Real>(a: &SparseCSC<T>, solve: impl Fn(&[T]) -> Vec<T>) {
+    let dense = a.to_dense();
+    let x = dense_random_floats::<T>(1, a.ncols).remove(0);
+    let column: Vec<Vec<T>> = x.iter().map(|v| vec![*v]).collect();
+    let b: Vec<T> = dense_matrix_multiply(&dense, &column)
+        .into_iter()
+        .map(|row| row[0])
+        .collect();
+    assert_vec_close(&solve(&b), &x, 1e-3);
+}
+
+fn test_factor_matches_lu<T: Real>() {
+    let a = SparseCSC::from_dense(dense_diag_dominant::<T>(30, 0.15));
+    let options = LuOptions {
+        ordering: ColumnOrdering::Colamd,
+        ..Default::default()
+    };
+    let symbolic = a.analyze(&options).unwrap();
+    let factors = symbolic.factor(&a).unwrap();
+    let direct = a.lu_with_options(&options).unwrap();
+
+    assert_eq!(factors.p, direct.p);
+    assert_eq!(factors.q, direct.q);
+    assert_eq!(factors.l.colptr, direct.l.colptr);
+    assert_eq!(factors.u.rowind, direct.u.rowind);
+    // the column counts bound the factors
+    assert!(factors.l.nnz() <= symbolic.counts.nnz());
+    assert!(factors.u.nnz() <= symbolic.counts.row_counts.iter().sum());
+}
+
+fn stress_test_refactor<T: Real>() {
+    let a = SparseCSC::from_dense(dense_diag_dominant::<T>(40, 0.1));
+    let options = LuOptions {
+        ordering: ColumnOrdering::Amd,
+        ..Default::default()
+    };
+    let symbolic = a.analyze(&options).unwrap();
+    let mut factors = symbolic.factor(&a).unwrap();
+
+    let mut current = a;
+    for _ in 0..3 {
+        current = with_new_values(&current);
+        let (p, l_pattern) = (factors.p.clone(), factors.l.rowind.clone());
+        symbolic.refactor(&mut factors, &current).unwrap();
+
+        // pivot order and patterns are kept, values match a fresh factorization
+        assert_eq!(factors.p, p);
+        assert_eq!(factors.l.rowind, l_pattern);
+        check_solve(&current, |b| factors.solve(b));
+
+        let fresh = current.lu_with_options(&options).unwrap();
+        if fresh.p == factors.p {
+            assert_dense_close(&factors.u.to_dense(), &fresh.u.to_dense(), 1e-3);
+        }
+    }
+}
+
+fn test_refactor_with_pivoting<T: Real>() {
+    // the first factorization swaps rows, refactor must reuse the swap
+    let a = SparseCSC::from_dense(dense_from_f64::<T>(&[
+        vec![0.0, 2.0, 1.0],
+        vec![3.0, 1.0, 0.0],
+        vec![1.0, 0.0, 4.0],
+    ]));
+    let symbolic = a.analyze(&LuOptions::default()).unwrap();
+    let mut factors = symbolic.factor(&a).unwrap();
+    assert_ne!(factors.p, vec![0, 1, 2]);
+
+    let b = with_new_values(&a);
+    symbolic.refactor(&mut factors, &b).unwrap();
+    check_solve(&b, |rhs| factors.solve(rhs));
+}
+
+fn test_pattern_mismatch<T: Real>() {
+    let a = SparseCSC::from_dense(dense_from_f64::<T>(&[vec![2.0, 1.0], vec![0.0, 3.0]]));
+    let other = SparseCSC::from_dense(dense_from_f64::<T>(&[vec![2.0, 0.0], vec![1.0, 3.0]]));
+    let symbolic = a.analyze(&LuOptions::default()).unwrap();
+    let mut factors = symbolic.factor(&a).unwrap();
+
+    assert_eq!(
+        symbolic.factor(&other).unwrap_err(),
+        LuError::PatternMismatch
+    );
+    assert_eq!(
+        symbolic.refactor(&mut factors, &other).unwrap_err(),
+        LuError::PatternMismatch
+    );
+
+    // factors from an analysis with another column ordering
+    let reversed = LuOptions {
+        ordering: ColumnOrdering::Custom(vec![1, 0]),
+        ..Default::default()
+    };
+    let mut other_factors = a.analyze(&reversed).unwrap().factor(&a).unwrap();
+    assert_eq!(
+        symbolic.refactor(&mut other_factors, &a).unwrap_err(),
+        LuError::PatternMismatch
+    );
+}
+
+fn test_refactor_zero_pivot<T: Real>() {
+    let a = SparseCSC::from_dense(dense_from_f64::<T>(&[vec![2.0, 1.0], vec![1.0, 3.0]]));
+    let symbolic = a.analyze(&LuOptions::default()).unwrap();
+    let mut factors = symbolic.factor(&a).unwrap();
+
+    // same pattern, singular values
+    let mut singular = a.clone();
+    singular.values = vec![T::one(); 4];
+    assert_eq!(
+        symbolic.refactor(&mut factors, &singular).unwrap_err(),
+        LuError::ZeroPivot(1)
+    );
+
+    // the failed refactor leaves the factors of a untouched
+    let before = symbolic.factor(&a).unwrap();
+    assert_eq!(factors.l.values, before.l.values);
+    assert_eq!(factors.u.values, before.u.values);
+    check_solve(&a, |b| factors.solve(b));
+}
+
+scalar_tests! {
+    fn test_factor_matches_lu;
+    fn stress_test_refactor;
+    fn test_refactor_with_pivoting;
+    fn test_pattern_mismatch;
+    fn test_refactor_zero_pivot;
+}
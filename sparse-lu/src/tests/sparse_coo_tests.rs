@@ -1,14 +1,15 @@
+use crate::sparse::scalar::Real;
 use crate::sparse::{sparse_coo::SparseCOO, sparse_matrix::SparseMatrixTrait};
-use crate::tests::test_utils::dense_matrix_multiply;
+use crate::tests::test_utils::scalar_tests;
+use crate::tests::test_utils::{dense_from_f64, dense_matrix_multiply};
 
-#[test]
-fn test_sparse_coo_multiplication() {
-    let dense_a = vec![
+fn test_sparse_coo_multiplication<T: Real>() {
+    let dense_a = dense_from_f64::<T>(&[
         vec![1.0, 2.0, 0.0],
         vec![0.0, 3.0, 4.0],
         vec![5.0, 0.0, 6.0],
-    ];
-    let dense_b = vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![1.0, 1.0]];
+    ]);
+    let dense_b = dense_from_f64::<T>(&[vec![1.0, 0.0], vec![0.0, 1.0], vec![1.0, 1.0]]);
 
     let sparse_a = SparseCOO::from_dense(dense_a.clone());
     let sparse_b = SparseCOO::from_dense(dense_b.clone());
@@ -30,19 +31,18 @@ fn test_sparse_coo_multiplication() {
     assert_eq!(sparse_result.size(), (3, 2));
 }
 
-#[test]
-fn test_sparse_coo_multiplication_edge_cases() {
+fn test_sparse_coo_multiplication_edge_cases<T: Real>() {
     // Test multiplication with identity matrix
-    let test_matrix = vec![
+    let test_matrix = dense_from_f64::<T>(&[
         vec![1.0, 2.0, 0.0],
         vec![0.0, 3.0, 4.0],
         vec![5.0, 0.0, 6.0],
-    ];
-    let identity = vec![
+    ]);
+    let identity = dense_from_f64::<T>(&[
         vec![1.0, 0.0, 0.0],
         vec![0.0, 1.0, 0.0],
         vec![0.0, 0.0, 1.0],
-    ];
+    ]);
 
     let sparse_test = SparseCOO::from_dense(test_matrix.clone());
     let sparse_identity = SparseCOO::from_dense(identity);
@@ -54,39 +54,44 @@ fn test_sparse_coo_multiplication_edge_cases() {
     assert_eq!(result_dense, test_matrix);
 
     // Test multiplication with zero matrix
-    let zero_matrix = vec![vec![0.0, 0.0], vec![0.0, 0.0], vec![0.0, 0.0]];
+    let zero_matrix = dense_from_f64::<T>(&[vec![0.0, 0.0], vec![0.0, 0.0], vec![0.0, 0.0]]);
 
     let sparse_zero = SparseCOO::from_dense(zero_matrix);
     let result_zero = sparse_test.multiply(&sparse_zero);
 
     // Result should be all zeros
     let result_zero_dense = result_zero.to_dense();
-    let expected_zero = vec![vec![0.0; 2]; 3];
+    let expected_zero = vec![vec![T::zero(); 2]; 3];
     assert_eq!(result_zero_dense, expected_zero);
 }
 
-#[test]
-fn test_sparse_coo_set_operations() {
-    let mut sparse = SparseCOO::new(3, 3);
+fn test_sparse_coo_set_operations<T: Real>() {
+    let mut sparse = SparseCOO::<T>::new(3, 3);
 
     // Test setting new elements
-    sparse.set(0, 0, 1.0);
-    sparse.set(1, 1, 2.0);
-    sparse.set(2, 2, 3.0);
+    sparse.set(0, 0, T::one());
+    sparse.set(1, 1, T::from_f64(2.0));
+    sparse.set(2, 2, T::from_f64(3.0));
 
     // Test getting set elements
-    assert_eq!(sparse.get(0, 0), 1.0);
-    assert_eq!(sparse.get(1, 1), 2.0);
-    assert_eq!(sparse.get(2, 2), 3.0);
+    assert_eq!(sparse.get(0, 0), T::one());
+    assert_eq!(sparse.get(1, 1), T::from_f64(2.0));
+    assert_eq!(sparse.get(2, 2), T::from_f64(3.0));
 
     // Test getting unset elements (should return 0.0)
-    assert_eq!(sparse.get(0, 1), 0.0);
-    assert_eq!(sparse.get(1, 0), 0.0);
+    assert_eq!(sparse.get(0, 1), T::zero());
+    assert_eq!(sparse.get(1, 0), T::zero());
 
     // Test updating existing elements
-    sparse.set(0, 0, 5.0);
-    assert_eq!(sparse.get(0, 0), 5.0);
+    sparse.set(0, 0, T::from_f64(5.0));
+    assert_eq!(sparse.get(0, 0), T::from_f64(5.0));
 
     // Test nnz count
     assert_eq!(sparse.nnz(), 3);
 }
+
+scalar_tests! {
+    fn test_sparse_coo_multiplication;
+    fn test_sparse_coo_multiplication_edge_cases;
+    fn test_sparse_coo_set_operations;
+}
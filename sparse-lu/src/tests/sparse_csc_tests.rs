@@ -1,12 +1,13 @@
+use crate::sparse::scalar::Real;
 use crate::sparse::{sparse_csc::SparseCSC, sparse_matrix::SparseMatrixTrait};
+use crate::tests::test_utils::{dense_from_f64, scalar_tests};
 
-#[test]
-fn test_sparse_csc_column_operations() {
-    let dense = vec![
+fn test_sparse_csc_column_operations<T: Real>() {
+    let dense = dense_from_f64::<T>(&[
         vec![1.0, 2.0, 0.0],
         vec![0.0, 3.0, 4.0],
         vec![5.0, 0.0, 6.0],
-    ];
+    ]);
     let sparse = SparseCSC::from_dense(dense);
 
     // Test column operations
@@ -24,3 +25,7 @@ fn test_sparse_csc_column_operations() {
     let (start, end) = sparse.get_column_range(2);
     assert_eq!(end - start, 2);
 }
+
+scalar_tests! {
+    fn test_sparse_csc_column_operations;
+}
@@ -1,18 +1,23 @@
+use crate::sparse::scalar::{Real, Scalar};
 use crate::sparse::{
     sparse_coo::SparseCOO, sparse_csc::SparseCSC, sparse_csr::SparseCSR,
     sparse_matrix::SparseMatrixTrait,
 };
+use num_complex::Complex;
 
-use crate::tests::test_utils::{dense_random_floats, get_dense_simple};
+use crate::tests::test_utils::{
+    assert_vec_close, dense_from_f64, dense_matrix_multiply, dense_random_floats, dense_transpose,
+    get_dense_simple, scalar_tests,
+};
 
-fn test_from_to_dense<T: SparseMatrixTrait>(dense: Vec<Vec<f32>>) {
-    let sparse = T::from_dense(dense.clone());
+fn test_from_to_dense<T: Scalar, M: SparseMatrixTrait<T>>(dense: Vec<Vec<T>>) {
+    let sparse = M::from_dense(dense.clone());
 
     // Test size
     assert_eq!(sparse.size(), (dense.len(), dense[0].len()));
     assert_eq!(
         sparse.nnz(),
-        dense.iter().flatten().filter(|x| **x != 0.0).count()
+        dense.iter().flatten().filter(|x| !x.is_zero()).count()
     );
 
     // Test conversion back to dense
@@ -20,46 +25,50 @@ fn test_from_to_dense<T: SparseMatrixTrait>(dense: Vec<Vec<f32>>) {
     assert_eq!(converted_back, dense);
 }
 
-fn test_sparse_get<T: SparseMatrixTrait>(dense: Vec<Vec<f32>>) {
-    let sparse = T::from_dense(dense.clone());
+fn test_sparse_get<T: Scalar, M: SparseMatrixTrait<T>>(dense: Vec<Vec<T>>) {
+    let sparse = M::from_dense(dense.clone());
 
     // Test size
     assert_eq!(sparse.size(), (dense.len(), dense[0].len()));
     assert_eq!(
         sparse.nnz(),
-        dense.iter().flatten().filter(|x| **x != 0.0).count()
+        dense.iter().flatten().filter(|x| !x.is_zero()).count()
     );
 
-    for i in 0..dense.len() {
-        for j in 0..dense[0].len() {
-            assert_eq!(sparse.get(i, j), dense[i][j]);
+    for (i, row) in dense.iter().enumerate() {
+        for (j, value) in row.iter().enumerate() {
+            assert_eq!(sparse.get(i, j), *value);
         }
     }
 }
 
-fn test_sparse_edge_cases<T: SparseMatrixTrait>() {
+fn test_sparse_edge_cases<T: Scalar, M: SparseMatrixTrait<T>>() {
     // Test empty matrix
-    let empty_dense = vec![vec![0.0; 3]; 3];
-    let empty_sparse = T::from_dense(empty_dense);
+    let empty_dense = vec![vec![T::zero(); 3]; 3];
+    let empty_sparse = M::from_dense(empty_dense);
 
     assert_eq!(empty_sparse.nnz(), 0);
 
     // Test matrix with all ones
-    let ones_dense = vec![vec![1.0; 3]; 3];
-    let ones_sparse = T::from_dense(ones_dense);
+    let ones_dense = vec![vec![T::one(); 3]; 3];
+    let ones_sparse = M::from_dense(ones_dense);
 
     assert_eq!(ones_sparse.nnz(), 9);
 
     // Test single element matrix
-    let single_dense = vec![vec![5.0]];
-    let single_sparse = T::from_dense(single_dense);
+    let single_dense = dense_from_f64::<T>(&[vec![5.0]]);
+    let single_sparse = M::from_dense(single_dense);
 
     assert_eq!(single_sparse.size(), (1, 1));
-    assert_eq!(single_sparse.get(0, 0), 5.0);
+    assert_eq!(single_sparse.get(0, 0), T::from_f64(5.0));
 }
 
-fn test_sparse_random_generation<T: SparseMatrixTrait>(rows: usize, cols: usize, density: f32) {
-    let random_sparse = T::random(rows, cols, density);
+fn test_sparse_random_generation<T: Scalar, M: SparseMatrixTrait<T>>(
+    rows: usize,
+    cols: usize,
+    density: f32,
+) {
+    let random_sparse = M::random(rows, cols, density);
 
     // Test dimensions
     assert_eq!(random_sparse.size(), (rows, cols));
@@ -76,84 +85,164 @@ fn test_sparse_random_generation<T: SparseMatrixTrait>(rows: usize, cols: usize,
     assert_eq!(sparse_dense[0].len(), cols);
 }
 
-#[test]
-fn test_from_to_dense_coo() {
-    let dense_simple = get_dense_simple();
-    test_from_to_dense::<SparseCOO>(dense_simple);
-    let dense_random = dense_random_floats(20, 18);
-    test_from_to_dense::<SparseCOO>(dense_random);
+// dense[.] * x as a plain vector
+fn dense_matvec<T: Scalar>(dense: &[Vec<T>], x: &[T]) -> Vec<T> {
+    let column: Vec<Vec<T>> = x.iter().map(|v| vec![*v]).collect();
+    dense_matrix_multiply(dense, &column)
+        .into_iter()
+        .map(|row| row[0])
+        .collect()
 }
 
-#[test]
-fn test_get_coo() {
-    let dense_simple = get_dense_simple();
-    test_sparse_get::<SparseCOO>(dense_simple);
-    let dense_random = dense_random_floats(20, 18);
-    test_sparse_get::<SparseCOO>(dense_random);
+fn test_sparse_matvec<T: Scalar, M: SparseMatrixTrait<T>>(dense: Vec<Vec<T>>) {
+    let sparse = M::from_dense(dense.clone());
+    let (nrows, ncols) = (dense.len(), dense[0].len());
+    let x = dense_random_floats::<T>(1, ncols).remove(0);
+    let xt = dense_random_floats::<T>(1, nrows).remove(0);
+    let ax = dense_matvec(&dense, &x);
+    let atx = dense_matvec(&dense_transpose(&dense), &xt);
+
+    assert_vec_close(&sparse.matvec(&x), &ax, 1e-4);
+    assert_vec_close(&sparse.matvec_transpose(&xt), &atx, 1e-4);
+
+    // y = 2 A x - 0.5 y
+    let y0 = dense_random_floats::<T>(1, nrows).remove(0);
+    let mut y = y0.clone();
+    let (two, half) = (T::from_f64(2.0), T::from_f64(0.5));
+    sparse.gemv(two, &x, -half, &mut y);
+    let expected: Vec<T> = ax
+        .iter()
+        .zip(&y0)
+        .map(|(&a, &y)| two * a - half * y)
+        .collect();
+    assert_vec_close(&y, &expected, 1e-4);
+
+    let y0 = dense_random_floats::<T>(1, ncols).remove(0);
+    let mut y = y0.clone();
+    sparse.gemv_transpose(-T::one(), &xt, T::one(), &mut y);
+    let expected: Vec<T> = atx.iter().zip(&y0).map(|(&a, &y)| y - a).collect();
+    assert_vec_close(&y, &expected, 1e-4);
+
+    // beta = 0 overwrites whatever y held
+    let mut y = vec![T::from_f64(f64::NAN); nrows];
+    sparse.gemv(T::one(), &x, T::zero(), &mut y);
+    assert_vec_close(&y, &ax, 1e-4);
 }
 
-#[test]
-fn test_edge_cases_coo() {
-    test_sparse_edge_cases::<SparseCOO>();
+fn test_from_to_dense_coo<T: Real>() {
+    let dense_simple = get_dense_simple::<T>();
+    test_from_to_dense::<T, SparseCOO<T>>(dense_simple);
+    let dense_random = dense_random_floats::<T>(20, 18);
+    test_from_to_dense::<T, SparseCOO<T>>(dense_random);
 }
 
-#[test]
-fn test_random_generation_coo() {
-    test_sparse_random_generation::<SparseCOO>(10, 8, 0.3);
+fn test_get_coo<T: Real>() {
+    let dense_simple = get_dense_simple::<T>();
+    test_sparse_get::<T, SparseCOO<T>>(dense_simple);
+    let dense_random = dense_random_floats::<T>(20, 18);
+    test_sparse_get::<T, SparseCOO<T>>(dense_random);
+}
+
+fn test_edge_cases_coo<T: Real>() {
+    test_sparse_edge_cases::<T, SparseCOO<T>>();
+}
+
+fn test_random_generation_coo<T: Real>() {
+    test_sparse_random_generation::<T, SparseCOO<T>>(10, 8, 0.3);
+}
+
+fn test_matvec_coo<T: Real>() {
+    test_sparse_matvec::<T, SparseCOO<T>>(get_dense_simple::<T>());
+    test_sparse_matvec::<T, SparseCOO<T>>(SparseCOO::<T>::random(20, 18, 0.2).to_dense());
 }
 
 // END COO TESTS --------------------------------------------------------------------------------
 
-#[test]
-fn test_from_to_dense_csc() {
-    let dense_simple = get_dense_simple();
-    test_from_to_dense::<SparseCSC>(dense_simple);
-    let dense_random = dense_random_floats(20, 18);
-    test_from_to_dense::<SparseCSC>(dense_random);
+fn test_from_to_dense_csc<T: Real>() {
+    let dense_simple = get_dense_simple::<T>();
+    test_from_to_dense::<T, SparseCSC<T>>(dense_simple);
+    let dense_random = dense_random_floats::<T>(20, 18);
+    test_from_to_dense::<T, SparseCSC<T>>(dense_random);
 }
 
-#[test]
-fn test_get_csc() {
-    let dense_simple = get_dense_simple();
-    test_sparse_get::<SparseCSC>(dense_simple);
-    let dense_random = dense_random_floats(20, 18);
-    test_sparse_get::<SparseCSC>(dense_random);
+fn test_get_csc<T: Real>() {
+    let dense_simple = get_dense_simple::<T>();
+    test_sparse_get::<T, SparseCSC<T>>(dense_simple);
+    let dense_random = dense_random_floats::<T>(20, 18);
+    test_sparse_get::<T, SparseCSC<T>>(dense_random);
 }
 
-#[test]
-fn test_edge_cases_csc() {
-    test_sparse_edge_cases::<SparseCSC>();
+fn test_edge_cases_csc<T: Real>() {
+    test_sparse_edge_cases::<T, SparseCSC<T>>();
 }
 
-#[test]
-fn test_random_generation_csc() {
-    test_sparse_random_generation::<SparseCSC>(10, 8, 0.3);
+fn test_random_generation_csc<T: Real>() {
+    test_sparse_random_generation::<T, SparseCSC<T>>(10, 8, 0.3);
+}
+
+fn test_matvec_csc<T: Real>() {
+    test_sparse_matvec::<T, SparseCSC<T>>(get_dense_simple::<T>());
+    test_sparse_matvec::<T, SparseCSC<T>>(SparseCSC::<T>::random(20, 18, 0.2).to_dense());
 }
 
 // END CSC TESTS --------------------------------------------------------------------------------
 
-#[test]
-fn test_from_to_dense_csr() {
-    let dense_simple = get_dense_simple();
-    test_from_to_dense::<SparseCSR>(dense_simple);
-    let dense_random = dense_random_floats(20, 18);
-    test_from_to_dense::<SparseCSR>(dense_random);
+fn test_from_to_dense_csr<T: Real>() {
+    let dense_simple = get_dense_simple::<T>();
+    test_from_to_dense::<T, SparseCSR<T>>(dense_simple);
+    let dense_random = dense_random_floats::<T>(20, 18);
+    test_from_to_dense::<T, SparseCSR<T>>(dense_random);
 }
 
-#[test]
-fn test_get_csr() {
-    let dense_simple = get_dense_simple();
-    test_sparse_get::<SparseCSR>(dense_simple);
-    let dense_random = dense_random_floats(20, 18);
-    test_sparse_get::<SparseCSR>(dense_random);
+fn test_get_csr<T: Real>() {
+    let dense_simple = get_dense_simple::<T>();
+    test_sparse_get::<T, SparseCSR<T>>(dense_simple);
+    let dense_random = dense_random_floats::<T>(20, 18);
+    test_sparse_get::<T, SparseCSR<T>>(dense_random);
 }
 
-#[test]
-fn test_edge_cases_csr() {
-    test_sparse_edge_cases::<SparseCSR>();
+fn test_edge_cases_csr<T: Real>() {
+    test_sparse_edge_cases::<T, SparseCSR<T>>();
+}
+
+fn test_random_generation_csr<T: Real>() {
+    test_sparse_random_generation::<T, SparseCSR<T>>(10, 8, 0.3);
+}
+
+fn test_matvec_csr<T: Real>() {
+    test_sparse_matvec::<T, SparseCSR<T>>(get_dense_simple::<T>());
+    test_sparse_matvec::<T, SparseCSR<T>>(SparseCSR::<T>::random(20, 18, 0.2).to_dense());
+}
+
+scalar_tests! {
+    fn test_from_to_dense_coo;
+    fn test_get_coo;
+    fn test_edge_cases_coo;
+    fn test_random_generation_coo;
+    fn test_matvec_coo;
+    fn test_from_to_dense_csc;
+    fn test_get_csc;
+    fn test_edge_cases_csc;
+    fn test_random_generation_csc;
+    fn test_matvec_csc;
+    fn test_from_to_dense_csr;
+    fn test_get_csr;
+    fn test_edge_cases_csr;
+    fn test_random_generation_csr;
+    fn test_matvec_csr;
 }
 
+// the generic helpers again with complex entries
 #[test]
-fn test_random_generation_csr() {
-    test_sparse_random_generation::<SparseCSR>(10, 8, 0.3);
+fn test_complex_coo_csc_csr() {
+    type C = Complex<f64>;
+    let dense = SparseCOO::<C>::random(12, 10, 0.3).to_dense();
+    test_from_to_dense::<C, SparseCOO<C>>(dense.clone());
+    test_from_to_dense::<C, SparseCSC<C>>(dense.clone());
+    test_from_to_dense::<C, SparseCSR<C>>(dense.clone());
+    test_sparse_get::<C, SparseCSC<C>>(dense.clone());
+    test_sparse_get::<C, SparseCSR<C>>(dense.clone());
+    test_sparse_matvec::<C, SparseCOO<C>>(dense.clone());
+    test_sparse_matvec::<C, SparseCSC<C>>(dense.clone());
+    test_sparse_matvec::<C, SparseCSR<C>>(dense);
 }
@@ -0,0 +1,117 @@
+use crate::sparse::scalar::Real;
+use crate::sparse::{
+    sparse_csc::SparseCSC, sparse_csr::SparseCSR, sparse_matrix::SparseMatrixTrait,
+};
+use crate::tests::test_utils::{
+    assert_dense_close, dense_from_f64, dense_matrix_multiply, scalar_tests,
+};
+
+fn assert_sorted_rows<T: Real>(c: &SparseCSR<T>) {
+    for i in 0..c.nrows {
+        let (start, end) = c.get_row_range(i);
+        assert!(c.colind[start..end].is_sorted_by(|a, b| a < b));
+    }
+}
+
+fn test_spgemm_sparse_product<T: Real>() {
+    let a = SparseCSR::<T>::random(40, 30, 0.05);
+    let b = SparseCSR::<T>::random(30, 35, 0.05);
+    let c = a.spgemm(&b);
+    assert_sorted_rows(&c);
+    let expected = dense_matrix_multiply(&a.to_dense(), &b.to_dense());
+    assert_dense_close(&c.to_dense(), &expected, 1e-5);
+    assert_dense_close(&a.multiply_csr(&b).to_dense(), &expected, 1e-5);
+}
+
+fn test_spgemm_symbolic_reuse<T: Real>() {
+    let a = SparseCSR::<T>::random(25, 20, 0.1);
+    let b = SparseCSR::<T>::random(20, 25, 0.1);
+    let symbolic = a.spgemm_symbolic(&b);
+    let mut c = symbolic.numeric(&a, &b);
+
+    // same patterns, new values
+    let mut a2 = a.clone();
+    let mut b2 = b.clone();
+    a2.values
+        .iter_mut()
+        .for_each(|v| *v = T::from_f64(2.0) * *v - T::one());
+    b2.values.iter_mut().for_each(|v| *v += T::from_f64(0.5));
+    symbolic.numeric_into(&a2, &b2, &mut c);
+
+    assert_eq!(c.rowptr, symbolic.rowptr);
+    let expected = dense_matrix_multiply(&a2.to_dense(), &b2.to_dense());
+    assert_dense_close(&c.to_dense(), &expected, 1e-5);
+}
+
+fn test_spgemm_keeps_cancellation<T: Real>() {
+    // [1 1] * [1; -1] = 0, still one structural entry
+    let a = SparseCSR::from_dense(dense_from_f64::<T>(&[vec![1.0, 1.0]]));
+    let b = SparseCSR::from_dense(dense_from_f64::<T>(&[vec![1.0], vec![-1.0]]));
+    let c = a.spgemm(&b);
+    assert_eq!(c.nnz(), 1);
+    assert_eq!(c.get(0, 0), T::zero());
+}
+
+fn test_spgemm_pattern_mismatch<T: Real>() {
+    let a = SparseCSR::from_dense(dense_from_f64::<T>(&[vec![1.0, 0.0], vec![0.0, 1.0]]));
+    let b = a.clone();
+    let symbolic = a.spgemm_symbolic(&b);
+    let other = SparseCSR::from_dense(dense_from_f64::<T>(&[vec![0.0, 1.0], vec![1.0, 0.0]]));
+    symbolic.numeric(&other, &b);
+}
+
+// every mixed-format product against the dense one
+fn test_mixed_products<T: Real>(dense_a: Vec<Vec<T>>, dense_b: Vec<Vec<T>>) {
+    let expected = dense_matrix_multiply(&dense_a, &dense_b);
+    let (a_csr, a_csc) = (
+        SparseCSR::from_dense(dense_a.clone()),
+        SparseCSC::from_dense(dense_a),
+    );
+    let (b_csr, b_csc) = (
+        SparseCSR::from_dense(dense_b.clone()),
+        SparseCSC::from_dense(dense_b),
+    );
+
+    let csc_csc = a_csc.multiply_csc(&b_csc);
+    assert_dense_close(&csc_csc.to_dense(), &expected, 1e-5);
+    assert_sorted_rows(&csc_csc.to_csr());
+    for c in [a_csr.multiply_csc(&b_csc), a_csc.multiply_csr(&b_csr)] {
+        assert_sorted_rows(&c);
+        assert_dense_close(&c.to_dense(), &expected, 1e-5);
+    }
+}
+
+fn test_mixed_products_random<T: Real>() {
+    test_mixed_products(
+        SparseCSR::<T>::random(30, 25, 0.08).to_dense(),
+        SparseCSR::<T>::random(25, 20, 0.08).to_dense(),
+    );
+}
+
+fn test_mixed_products_small_output<T: Real>() {
+    // 2 x 2 result from long vectors, CSR x CSC takes the inner product path
+    test_mixed_products(
+        SparseCSR::<T>::random(2, 60, 0.3).to_dense(),
+        SparseCSR::<T>::random(60, 2, 0.3).to_dense(),
+    );
+}
+
+fn test_multiply_csc_matches_csr<T: Real>() {
+    let a = SparseCSC::<T>::random(20, 20, 0.1);
+    let b = SparseCSC::<T>::random(20, 20, 0.1);
+    let by_columns = a.multiply_csc(&b);
+    let by_rows = a.to_csr().spgemm(&b.to_csr());
+    assert_eq!(by_columns.nnz(), by_rows.nnz());
+    assert_dense_close(&by_columns.to_dense(), &by_rows.to_dense(), 1e-5);
+}
+
+scalar_tests! {
+    fn test_spgemm_sparse_product;
+    fn test_spgemm_symbolic_reuse;
+    fn test_spgemm_keeps_cancellation;
+    #[should_panic]
+    fn test_spgemm_pattern_mismatch;
+    fn test_mixed_products_random;
+    fn test_mixed_products_small_output;
+    fn test_multiply_csc_matches_csr;
+}
@@ -0,0 +1,74 @@
+use crate::sparse::scalar::Real;
+use crate::sparse::spmm::DenseLayout;
+use crate::sparse::{
+    sparse_csc::SparseCSC, sparse_csr::SparseCSR, sparse_matrix::SparseMatrixTrait,
+};
+use crate::tests::test_utils::{
+    assert_dense_close, dense_matrix_multiply, dense_random_floats, get_dense_simple,
+    get_dense_simple_b, scalar_tests,
+};
+
+fn flatten<T: Real>(dense: &[Vec<T>], layout: DenseLayout) -> Vec<T> {
+    match layout {
+        DenseLayout::RowMajor => dense.iter().flatten().copied().collect(),
+        DenseLayout::ColMajor => (0..dense[0].len())
+            .flat_map(|j| dense.iter().map(move |row| row[j]))
+            .collect(),
+    }
+}
+
+fn unflatten<T: Real>(flat: &[T], nrows: usize, ncols: usize, layout: DenseLayout) -> Vec<Vec<T>> {
+    (0..nrows)
+        .map(|i| {
+            (0..ncols)
+                .map(|j| match layout {
+                    DenseLayout::RowMajor => flat[i * ncols + j],
+                    DenseLayout::ColMajor => flat[j * nrows + i],
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn test_mul_dense<T: Real>(dense_a: Vec<Vec<T>>, dense_b: Vec<Vec<T>>) {
+    let csr = SparseCSR::from_dense(dense_a.clone());
+    let csc = SparseCSC::from_dense(dense_a.clone());
+    let expected = dense_matrix_multiply(&dense_a, &dense_b);
+    let (m, k) = (dense_a.len(), dense_b[0].len());
+
+    for layout in [DenseLayout::RowMajor, DenseLayout::ColMajor] {
+        let b = flatten(&dense_b, layout);
+        let from_csr = csr.mul_dense(&b, k, layout);
+        let from_csc = csc.mul_dense(&b, k, layout);
+        assert_dense_close(&unflatten(&from_csr, m, k, layout), &expected, 1e-4);
+        assert_dense_close(&unflatten(&from_csc, m, k, layout), &expected, 1e-4);
+    }
+}
+
+fn test_mul_dense_simple<T: Real>() {
+    test_mul_dense(get_dense_simple::<T>(), get_dense_simple_b::<T>());
+}
+
+fn test_mul_dense_random<T: Real>() {
+    let dense_a = SparseCSR::<T>::random(20, 16, 0.2).to_dense();
+    test_mul_dense(dense_a, dense_random_floats::<T>(16, 7));
+}
+
+fn test_mul_dense_single_column<T: Real>() {
+    // one column is a matvec in either layout
+    let dense_a = SparseCSC::<T>::random(12, 9, 0.3).to_dense();
+    test_mul_dense(dense_a, dense_random_floats::<T>(9, 1));
+}
+
+fn test_mul_dense_wrong_size<T: Real>() {
+    let a = SparseCSR::from_dense(get_dense_simple::<T>());
+    a.mul_dense(&[T::one(); 4], 2, DenseLayout::RowMajor);
+}
+
+scalar_tests! {
+    fn test_mul_dense_simple;
+    fn test_mul_dense_random;
+    fn test_mul_dense_single_column;
+    #[should_panic]
+    fn test_mul_dense_wrong_size;
+}
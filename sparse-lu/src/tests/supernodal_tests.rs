@@ -0,0 +1,99 @@
+use crate::sparse::lu::{ColumnOrdering, LuError, LuOptions};
+use crate::sparse::scalar::Real;
+use crate::sparse::{sparse_csc::SparseCSC, sparse_matrix::SparseMatrixTrait};
+use crate::tests::test_utils::{
+    assert_dense_close, assert_lu_factors, assert_vec_close, dense_diag_dominant, dense_from_f64,
+    dense_grid_laplacian, dense_random_floats, scalar_tests,
+};
+
+fn test_supernodal_reconstructs<T: Real>(dense: Vec<Vec<T>>, options: &LuOptions) {
+    let a = SparseCSC::from_dense(dense.clone());
+    let factors = a.analyze(options).unwrap().factor_supernodal(&a).unwrap();
+    assert_lu_factors(&dense, &factors);
+}
+
+fn test_supernodes<T: Real>() {
+    let n = 6;
+    // dense: one supernode
+    let dense = SparseCSC::from_dense(vec![vec![T::one(); n]; n]);
+    assert_eq!(dense.column_symbolic_counts().supernodes(), vec![0, n]);
+
+    // diagonal: every column on its own
+    let mut diag = vec![vec![T::zero(); n]; n];
+    for (i, row) in diag.iter_mut().enumerate() {
+        row[i] = T::one();
+    }
+    let diag = SparseCSC::from_dense(diag);
+    assert_eq!(
+        diag.column_symbolic_counts().supernodes(),
+        (0..=n).collect::<Vec<_>>()
+    );
+}
+
+fn test_supernodal_dense<T: Real>() {
+    // 70 columns form one supernode, split into panels of at most 32
+    test_supernodal_reconstructs(dense_random_floats::<T>(70, 70), &LuOptions::default());
+}
+
+fn stress_test_supernodal_sparse<T: Real>() {
+    for ordering in [ColumnOrdering::Natural, ColumnOrdering::Colamd] {
+        let options = LuOptions {
+            ordering,
+            ..Default::default()
+        };
+        test_supernodal_reconstructs(dense_diag_dominant::<T>(50, 0.1), &options);
+        test_supernodal_reconstructs(dense_random_floats::<T>(30, 30), &options);
+    }
+}
+
+fn test_supernodal_matches_left_looking<T: Real>() {
+    let a = SparseCSC::from_dense(dense_grid_laplacian::<T>(8));
+    let options = LuOptions {
+        ordering: ColumnOrdering::Amd,
+        ..Default::default()
+    };
+    let symbolic = a.analyze(&options).unwrap();
+    let supernodal = symbolic.factor_supernodal(&a).unwrap();
+    let left_looking = symbolic.factor(&a).unwrap();
+
+    assert_eq!(supernodal.p, left_looking.p);
+    assert_dense_close(&supernodal.u.to_dense(), &left_looking.u.to_dense(), 1e-4);
+    assert_dense_close(&supernodal.l.to_dense(), &left_looking.l.to_dense(), 1e-4);
+}
+
+fn test_supernodal_refactor<T: Real>() {
+    let a = SparseCSC::from_dense(dense_diag_dominant::<T>(40, 0.2));
+    let symbolic = a.analyze(&LuOptions::default()).unwrap();
+    let mut factors = symbolic.factor_supernodal(&a).unwrap();
+
+    let mut b = a.clone();
+    for value in b.values.iter_mut() {
+        *value *= T::from_f64(2.0);
+    }
+    symbolic.refactor(&mut factors, &b).unwrap();
+    let x = dense_random_floats::<T>(1, 40).remove(0);
+    let rhs: Vec<T> = b
+        .to_dense()
+        .iter()
+        .map(|row| row.iter().zip(&x).map(|(&a, &x)| a * x).sum())
+        .collect();
+    assert_vec_close(&factors.solve(&rhs), &x, 1e-3);
+}
+
+fn test_supernodal_singular<T: Real>() {
+    let a = SparseCSC::from_dense(dense_from_f64::<T>(&[vec![1.0, 2.0], vec![2.0, 4.0]]));
+    let symbolic = a.analyze(&LuOptions::default()).unwrap();
+    assert_eq!(
+        symbolic.factor_supernodal(&a).unwrap_err(),
+        LuError::ZeroPivot(1)
+    );
+}
+
+scalar_tests! {
+    fn test_supernodes;
+    fn test_supernodal_dense;
+    fn stress_test_supernodal_sparse;
+    fn test_supernodal_matches_left_looking;
+    fn test_supernodal_refactor;
+    fn test_supernodal_singular;
+}
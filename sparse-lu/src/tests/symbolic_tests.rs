@@ -0,0 +1,121 @@
+use crate::sparse::lu::LuOptions;
+use crate::sparse::scalar::Real;
+use crate::sparse::symbolic::postorder;
+use crate::sparse::{sparse_csc::SparseCSC, sparse_matrix::SparseMatrixTrait};
+use crate::tests::test_utils::{
+    assert_permutation, dense_diag_dominant, dense_from_f64, dense_grid_laplacian,
+    dense_random_floats, dense_symmetric, scalar_tests,
+};
+
+fn check_postorder(parent: &[Option<usize>], post: &[usize]) {
+    assert_permutation(post, parent.len());
+    let mut position = vec![0; post.len()];
+    for (k, &j) in post.iter().enumerate() {
+        position[j] = k;
+    }
+    for (j, p) in parent.iter().enumerate() {
+        if let Some(p) = p {
+            assert!(position[j] < position[*p]);
+        }
+    }
+}
+
+fn test_etree_tridiagonal<T: Real>() {
+    let n = 6;
+    let mut dense = vec![vec![T::zero(); n]; n];
+    for i in 0..n {
+        dense[i][i] = T::from_f64(2.0);
+        if i + 1 < n {
+            dense[i + 1][i] = T::from_f64(-1.0);
+        }
+    }
+    // only the lower triangle is stored, the tree is of A + A^T
+    let parent = SparseCSC::from_dense(dense).etree();
+    let mut expected: Vec<Option<usize>> = (1..n).map(Some).collect();
+    expected.push(None);
+    assert_eq!(parent, expected);
+}
+
+fn test_etree_arrow<T: Real>() {
+    // last node connected to everything: a star rooted at n - 1
+    let n = 5;
+    let mut dense = vec![vec![T::zero(); n]; n];
+    for (i, row) in dense.iter_mut().enumerate() {
+        row[i] = T::one();
+        row[n - 1] = T::one();
+    }
+    let parent = SparseCSC::from_dense(dense).etree();
+    assert_eq!(parent, vec![Some(4), Some(4), Some(4), Some(4), None]);
+}
+
+fn test_postorder<T: Real>() {
+    let parent = vec![Some(2), Some(2), Some(4), None, None];
+    let post = postorder(&parent);
+    assert_eq!(post, vec![3, 0, 1, 2, 4]);
+
+    let counts = SparseCSC::from_dense(dense_symmetric::<T>(40, 0.05)).symbolic_counts();
+    check_postorder(&counts.parent, &counts.post);
+}
+
+fn test_counts_exact_without_pivoting<T: Real>() {
+    // symmetric with a dominant diagonal: diagonal pivots are always taken,
+    // so L is exactly the Cholesky pattern predicted by the counts
+    for dense in [dense_symmetric::<T>(40, 0.05), dense_grid_laplacian::<T>(7)] {
+        let a = SparseCSC::from_dense(dense);
+        let counts = a.symbolic_counts();
+        let options = LuOptions {
+            diag_pivot_thresh: 0.0,
+            ..Default::default()
+        };
+        let factors = a.lu_with_options(&options).unwrap();
+
+        for j in 0..a.ncols {
+            assert_eq!(factors.l.num_nnz_in_column(j), counts.col_counts[j]);
+            assert_eq!(factors.u.num_nnz_in_column(j), counts.row_counts[j]);
+        }
+        assert_eq!(factors.l.nnz(), counts.nnz());
+        assert_eq!(
+            counts.lu_fill(a.nnz()),
+            factors.l.nnz() + factors.u.nnz() - a.ncols - a.nnz()
+        );
+    }
+}
+
+fn test_column_counts_bound_lu<T: Real>() {
+    for dense in [
+        dense_random_floats::<T>(20, 20),
+        dense_diag_dominant::<T>(50, 0.04),
+    ] {
+        let a = SparseCSC::from_dense(dense);
+        let counts = a.column_symbolic_counts();
+        check_postorder(&counts.parent, &counts.post);
+
+        let factors = a.lu().unwrap();
+        for j in 0..a.ncols {
+            assert!(factors.l.num_nnz_in_column(j) <= counts.col_counts[j]);
+            assert!(factors.u.num_nnz_in_column(j) <= counts.row_counts[j]);
+        }
+    }
+}
+
+fn test_column_etree_rectangular<T: Real>() {
+    // rows couple columns (0, 1) and (1, 2), A^T A is tridiagonal
+    let dense = dense_from_f64::<T>(&[
+        vec![1.0, 1.0, 0.0],
+        vec![0.0, 1.0, 1.0],
+        vec![0.0, 0.0, 0.0],
+        vec![1.0, 0.0, 0.0],
+    ]);
+    let a = SparseCSC::from_dense(dense);
+    assert_eq!(a.column_etree(), vec![Some(1), Some(2), None]);
+    assert_eq!(a.column_symbolic_counts().col_counts, vec![2, 2, 1]);
+}
+
+scalar_tests! {
+    fn test_etree_tridiagonal;
+    fn test_etree_arrow;
+    fn test_postorder;
+    fn test_counts_exact_without_pivoting;
+    fn test_column_counts_bound_lu;
+    fn test_column_etree_rectangular;
+}
@@ -1,5 +1,48 @@
+use crate::sparse::lu::{ColumnOrdering, LuFactors, LuOptions};
+use crate::sparse::scalar::Scalar;
+use crate::sparse::sparse_csc::SparseCSC;
+use crate::sparse::sparse_matrix::SparseMatrixTrait;
+use std::path::PathBuf;
+
+/*
+    Runs generic test functions once per real scalar type,
+
+        scalar_tests! {
+            fn test_something;
+            #[should_panic(expected = "...")]
+            fn test_something_else;
+        }
+
+    expands to tests f32::test_something, f64::test_something, ... each
+    calling test_something::<f32> or ::<f64> from the enclosing module.
+*/
+macro_rules! scalar_tests {
+    ($($(#[$meta:meta])* fn $name:ident;)*) => {
+        mod f32 {
+            $(
+                #[test]
+                $(#[$meta])*
+                fn $name() {
+                    super::$name::<core::primitive::f32>()
+                }
+            )*
+        }
+
+        mod f64 {
+            $(
+                #[test]
+                $(#[$meta])*
+                fn $name() {
+                    super::$name::<core::primitive::f64>()
+                }
+            )*
+        }
+    };
+}
+pub(crate) use scalar_tests;
+
 /// Dense matrix multiplication for verification
-pub fn dense_matrix_multiply(a: &Vec<Vec<f32>>, b: &Vec<Vec<f32>>) -> Vec<Vec<f32>> {
+pub fn dense_matrix_multiply<T: Scalar>(a: &[Vec<T>], b: &[Vec<T>]) -> Vec<Vec<T>> {
     let rows_a = a.len();
     let cols_a = a[0].len();
     let cols_b = b[0].len();
@@ -10,7 +53,7 @@ pub fn dense_matrix_multiply(a: &Vec<Vec<f32>>, b: &Vec<Vec<f32>>) -> Vec<Vec<f3
         "Matrix dimensions must be compatible for multiplication"
     );
 
-    let mut result = vec![vec![0.0; cols_b]; rows_a];
+    let mut result = vec![vec![T::zero(); cols_b]; rows_a];
 
     for i in 0..rows_a {
         for j in 0..cols_b {
@@ -23,21 +66,149 @@ pub fn dense_matrix_multiply(a: &Vec<Vec<f32>>, b: &Vec<Vec<f32>>) -> Vec<Vec<f3
     result
 }
 
-pub fn get_dense_simple() -> Vec<Vec<f32>> {
-    vec![
+/// f64 literals as scalars
+pub fn dense_from_f64<T: Scalar>(dense: &[Vec<f64>]) -> Vec<Vec<T>> {
+    dense.iter().map(|row| vec_from_f64(row)).collect()
+}
+
+pub fn vec_from_f64<T: Scalar>(values: &[f64]) -> Vec<T> {
+    values.iter().map(|&x| T::from_f64(x)).collect()
+}
+
+pub fn get_dense_simple<T: Scalar>() -> Vec<Vec<T>> {
+    dense_from_f64(&[
         vec![1.0, 2.0, 0.0],
         vec![0.0, 3.0, 4.0],
         vec![5.0, 0.0, 6.0],
-    ]
+    ])
 }
 
-pub fn get_dense_simple_b() -> Vec<Vec<f32>> {
-    vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![1.0, 1.0]]
+pub fn get_dense_simple_b<T: Scalar>() -> Vec<Vec<T>> {
+    dense_from_f64(&[vec![1.0, 0.0], vec![0.0, 1.0], vec![1.0, 1.0]])
 }
 
-pub fn dense_random_floats(rows: usize, cols: usize) -> Vec<Vec<f32>> {
+pub fn dense_random_floats<T: Scalar>(rows: usize, cols: usize) -> Vec<Vec<T>> {
     let mut rng = fastrand::Rng::new();
     (0..rows)
-        .map(|_| (0..cols).map(|_| rng.f32()).collect())
+        .map(|_| (0..cols).map(|_| T::random(&mut rng)).collect())
+        .collect()
+}
+
+/// Random square matrix with a dominant diagonal, safe to factor without pivoting
+pub fn dense_diag_dominant<T: Scalar>(n: usize, density: f32) -> Vec<Vec<T>> {
+    let mut rng = fastrand::Rng::new();
+    let mut dense = vec![vec![T::zero(); n]; n];
+    for (i, row) in dense.iter_mut().enumerate() {
+        for value in row.iter_mut() {
+            if rng.f32() < density {
+                *value = T::from_f64(rng.f64() - 0.5);
+            }
+        }
+        row[i] = T::from_f64(n as f64);
+    }
+    dense
+}
+
+/// Symmetric, diagonally dominant with a positive diagonal, so positive definite
+pub fn dense_symmetric<T: Scalar>(n: usize, density: f32) -> Vec<Vec<T>> {
+    let mut dense = dense_diag_dominant(n, density);
+    let lower = dense.clone();
+    for (i, row) in dense.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate().skip(i + 1) {
+            *value = lower[j][i];
+        }
+    }
+    dense
+}
+
+pub fn assert_dense_close<T: Scalar>(a: &[Vec<T>], b: &[Vec<T>], tol: f64) {
+    assert_eq!(a.len(), b.len());
+    for (row_a, row_b) in a.iter().zip(b.iter()) {
+        assert_vec_close(row_a, row_b, tol);
+    }
+}
+
+pub fn assert_vec_close<T: Scalar>(a: &[T], b: &[T], tol: f64) {
+    assert_eq!(a.len(), b.len());
+    for (&x, &y) in a.iter().zip(b.iter()) {
+        assert!(
+            (x - y).modulus() <= T::Real::from_f64(tol),
+            "{:?} != {:?} (tol {})",
+            x,
+            y,
+            tol
+        );
+    }
+}
+
+pub fn dense_transpose<T: Copy>(a: &[Vec<T>]) -> Vec<Vec<T>> {
+    (0..a[0].len())
+        .map(|j| a.iter().map(|row| row[j]).collect())
         .collect()
 }
+
+/// 5-point Laplacian on a k x k grid, the classic matrix where ordering matters
+pub fn dense_grid_laplacian<T: Scalar>(k: usize) -> Vec<Vec<T>> {
+    let n = k * k;
+    let mut dense = vec![vec![T::zero(); n]; n];
+    for r in 0..k {
+        for c in 0..k {
+            let i = r * k + c;
+            dense[i][i] = T::from_f64(4.0);
+            if r + 1 < k {
+                dense[i][i + k] = -T::one();
+                dense[i + k][i] = -T::one();
+            }
+            if c + 1 < k {
+                dense[i][i + 1] = -T::one();
+                dense[i + 1][i] = -T::one();
+            }
+        }
+    }
+    dense
+}
+
+pub fn assert_permutation(perm: &[usize], n: usize) {
+    assert_eq!(perm.len(), n);
+    let mut seen = vec![false; n];
+    for &i in perm {
+        assert!(!seen[i], "{} appears twice in {:?}", i, perm);
+        seen[i] = true;
+    }
+}
+
+/// Checks P * A * Q = L * U and that the factors solve A x = b
+pub fn assert_lu_factors<T: Scalar>(dense: &[Vec<T>], factors: &LuFactors<T>) {
+    let (p, q) = (&factors.p, &factors.q);
+    let permuted: Vec<Vec<T>> = p
+        .iter()
+        .map(|&i| q.iter().map(|&j| dense[i][j]).collect())
+        .collect();
+    let product = dense_matrix_multiply(&factors.l.to_dense(), &factors.u.to_dense());
+    assert_dense_close(&product, &permuted, 1e-4 * dense.len() as f64);
+
+    let x = dense_random_floats(1, dense.len()).remove(0);
+    let column: Vec<Vec<T>> = x.iter().map(|v| vec![*v]).collect();
+    let b: Vec<T> = dense_matrix_multiply(dense, &column)
+        .into_iter()
+        .map(|row| row[0])
+        .collect();
+    assert_vec_close(&factors.solve(&b), &x, 1e-2);
+}
+
+/// Fill of an LU factorization, nnz(L) + nnz(U) - n, under a column ordering
+pub fn lu_fill<T: Scalar>(a: &SparseCSC<T>, ordering: ColumnOrdering) -> usize {
+    let options = LuOptions {
+        ordering,
+        ..Default::default()
+    };
+    let factors = a.lu_with_options(&options).unwrap();
+    factors.l.nnz() + factors.u.nnz() - a.ncols
+}
+
+/// Path of a file or case directory under the repository's data/ folder
+pub fn data_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../data")
+        .join(name)
+}
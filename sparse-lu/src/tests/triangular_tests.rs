@@ -0,0 +1,162 @@
+use crate::sparse::scalar::Real;
+use crate::sparse::triangular::Diag;
+use crate::sparse::{
+    sparse_csc::SparseCSC, sparse_csr::SparseCSR, sparse_matrix::SparseMatrixTrait,
+};
+use crate::tests::test_utils::{
+    assert_vec_close, dense_diag_dominant, dense_from_f64, dense_matrix_multiply,
+    dense_random_floats, dense_transpose, scalar_tests, vec_from_f64,
+};
+
+fn dense_lower<T: Real>(n: usize, density: f32) -> Vec<Vec<T>> {
+    let mut dense = dense_diag_dominant::<T>(n, density);
+    for (i, row) in dense.iter_mut().enumerate() {
+        for value in row.iter_mut().skip(i + 1) {
+            *value = T::zero();
+        }
+    }
+    dense
+}
+
+// the matrix the solver actually sees, with the stored diagonal ignored for Diag::Unit
+fn effective<T: Real>(dense: &[Vec<T>], diag: Diag) -> Vec<Vec<T>> {
+    let mut dense = dense.to_vec();
+    if diag == Diag::Unit {
+        for (i, row) in dense.iter_mut().enumerate() {
+            row[i] = T::one();
+        }
+    }
+    dense
+}
+
+fn check_solve<T: Real>(t: &[Vec<T>], solve: impl Fn(&mut [T])) {
+    let n = t.len();
+    let x_true = dense_random_floats::<T>(1, n).remove(0);
+    let x_column: Vec<Vec<T>> = x_true.iter().map(|x| vec![*x]).collect();
+    let mut x: Vec<T> = dense_matrix_multiply(t, &x_column)
+        .into_iter()
+        .map(|row| row[0])
+        .collect();
+    solve(&mut x);
+    assert_vec_close(&x, &x_true, 1e-4);
+}
+
+fn test_triangular_solves<T: Real>(n: usize, density: f32) {
+    let lower = dense_lower::<T>(n, density);
+    let upper = dense_transpose(&lower);
+
+    for diag in [Diag::Unit, Diag::NonUnit] {
+        let l_ref = effective(&lower, diag);
+        let u_ref = effective(&upper, diag);
+        let lt_ref = dense_transpose(&l_ref);
+        let ut_ref = dense_transpose(&u_ref);
+
+        let l_csc = SparseCSC::from_dense(lower.clone());
+        let u_csc = SparseCSC::from_dense(upper.clone());
+        check_solve(&l_ref, |x| l_csc.lower_solve(x, diag));
+        check_solve(&u_ref, |x| u_csc.upper_solve(x, diag));
+        check_solve(&lt_ref, |x| l_csc.lower_transpose_solve(x, diag));
+        check_solve(&ut_ref, |x| u_csc.upper_transpose_solve(x, diag));
+
+        let l_csr = SparseCSR::from_dense(lower.clone());
+        let u_csr = SparseCSR::from_dense(upper.clone());
+        check_solve(&l_ref, |x| l_csr.lower_solve(x, diag));
+        check_solve(&u_ref, |x| u_csr.upper_solve(x, diag));
+        check_solve(&lt_ref, |x| l_csr.lower_transpose_solve(x, diag));
+        check_solve(&ut_ref, |x| u_csr.upper_transpose_solve(x, diag));
+    }
+}
+
+fn test_triangular_solve_simple<T: Real>() {
+    let l = SparseCSC::from_dense(dense_from_f64::<T>(&[
+        vec![2.0, 0.0, 0.0],
+        vec![1.0, 1.0, 0.0],
+        vec![0.0, 3.0, 4.0],
+    ]));
+    let mut x = vec_from_f64::<T>(&[2.0, 3.0, 10.0]);
+    l.lower_solve(&mut x, Diag::NonUnit);
+    assert_vec_close(&x, &vec_from_f64::<T>(&[1.0, 2.0, 1.0]), 1e-6);
+}
+
+fn stress_test_triangular_solves<T: Real>() {
+    test_triangular_solves::<T>(30, 0.1);
+    test_triangular_solves::<T>(20, 0.6);
+}
+
+fn test_sparse_rhs<T: Real>(t: &SparseCSC<T>, lower: bool, diag: Diag) {
+    let n = t.ncols;
+    let b_pattern = vec![n / 2, n / 4];
+    let b_values = vec_from_f64::<T>(&[1.0, -2.0]);
+
+    let mut dense_x = vec![T::zero(); n];
+    for (&i, &value) in b_pattern.iter().zip(b_values.iter()) {
+        dense_x[i] = value;
+    }
+    if lower {
+        t.lower_solve(&mut dense_x, diag);
+    } else {
+        t.upper_solve(&mut dense_x, diag);
+    }
+    let (pattern, values) = t.triangular_solve_sparse(&b_pattern, &b_values, diag);
+
+    let mut sparse_x = vec![T::zero(); n];
+    for (&i, &value) in pattern.iter().zip(values.iter()) {
+        sparse_x[i] = value;
+    }
+    assert_vec_close(&sparse_x, &dense_x, 1e-5);
+
+    // rows outside the reach of b are never touched
+    for (i, value) in dense_x.iter().enumerate() {
+        if !pattern.contains(&i) {
+            assert_eq!(*value, T::zero());
+        }
+    }
+}
+
+fn test_triangular_solve_sparse_rhs<T: Real>() {
+    let lower = dense_lower::<T>(40, 0.05);
+    let l = SparseCSC::from_dense(lower.clone());
+    let u = SparseCSC::from_dense(dense_transpose(&lower));
+    for diag in [Diag::Unit, Diag::NonUnit] {
+        test_sparse_rhs(&l, true, diag);
+        test_sparse_rhs(&u, false, diag);
+    }
+}
+
+fn test_triangular_solve_sparse_rhs_pattern<T: Real>() {
+    // L = I except L[2, 0] = 1, so b = e0 reaches rows {0, 2} only
+    let mut dense = vec![vec![T::zero(); 4]; 4];
+    for (i, row) in dense.iter_mut().enumerate() {
+        row[i] = T::one();
+    }
+    dense[2][0] = T::one();
+    let l = SparseCSC::from_dense(dense);
+
+    let (mut pattern, values) =
+        l.triangular_solve_sparse(&[0], &vec_from_f64::<T>(&[3.0]), Diag::Unit);
+    assert_eq!(pattern[0], 0);
+    assert_eq!(values, vec_from_f64::<T>(&[3.0, -3.0]));
+    pattern.sort_unstable();
+    assert_eq!(pattern, vec![0, 2]);
+
+    // repeated indices are summed, b = 3 e0 + 5 e2
+    let (pattern, values) =
+        l.triangular_solve_sparse(&[0, 2, 0], &vec_from_f64::<T>(&[1.0, 5.0, 2.0]), Diag::Unit);
+    assert_eq!(pattern.len(), 2);
+    assert_eq!(values, vec_from_f64::<T>(&[3.0, 2.0]));
+}
+
+fn test_triangular_solve_zero_diagonal<T: Real>() {
+    let l = SparseCSR::from_dense(dense_from_f64::<T>(&[vec![1.0, 0.0], vec![1.0, 0.0]]));
+    let mut x = vec_from_f64::<T>(&[1.0, 1.0]);
+    l.lower_solve(&mut x, Diag::NonUnit);
+}
+
+scalar_tests! {
+    fn test_triangular_solve_simple;
+    fn stress_test_triangular_solves;
+    fn test_triangular_solve_sparse_rhs;
+    fn test_triangular_solve_sparse_rhs_pattern;
+    #[should_panic]
+    fn test_triangular_solve_zero_diagonal;
+}